@@ -0,0 +1,62 @@
+use bevy::prelude::*;
+use bevy_asset_loader::{AssetCollection, AssetLoader, NamedTextureAtlas};
+
+/// This example demonstrates how to stitch all images in a folder into a texture atlas
+///
+/// Requires the feature 'render'
+fn main() {
+    let mut app = App::new();
+    AssetLoader::new(MyStates::AssetLoading)
+        .continue_to_state(MyStates::Next)
+        .with_collection::<MyAssets>()
+        .build(&mut app);
+    app.add_state(MyStates::AssetLoading)
+        .insert_resource(Msaa { samples: 1 })
+        .add_plugins(DefaultPlugins)
+        .add_system_set(SystemSet::on_enter(MyStates::Next).with_system(draw_atlas))
+        .run();
+}
+
+#[derive(AssetCollection)]
+struct MyAssets {
+    // every image in the folder becomes one texture in the atlas
+    #[asset(texture_atlas(folder = "images"))]
+    images: NamedTextureAtlas,
+}
+
+fn draw_atlas(
+    mut commands: Commands,
+    my_assets: Res<MyAssets>,
+    texture_atlases: Res<Assets<TextureAtlas>>,
+) {
+    commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+    // draw the stitched image (whole atlas)
+    let atlas = texture_atlases
+        .get(my_assets.images.atlas.clone())
+        .expect("Failed to find our atlas");
+    commands.spawn_bundle(SpriteBundle {
+        texture: atlas.texture.clone(),
+        transform: Transform::from_xyz(0., -150., 0.).with_scale(Vec3::splat(0.4)),
+        ..Default::default()
+    });
+    // draw single textures from the atlas by their file names
+    for (index, name) in ["player", "tree", "zombie"].iter().enumerate() {
+        commands.spawn_bundle(SpriteSheetBundle {
+            transform: Transform::from_xyz(-150. + index as f32 * 150., 250., 0.),
+            sprite: TextureAtlasSprite::new(
+                my_assets
+                    .images
+                    .index(name)
+                    .expect("Image is missing in the atlas"),
+            ),
+            texture_atlas: my_assets.images.atlas.clone(),
+            ..Default::default()
+        });
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+enum MyStates {
+    AssetLoading,
+    Next,
+}
@@ -1,5 +1,5 @@
 use bevy::prelude::*;
-use bevy_asset_loader::{AssetCollection, AssetLoader};
+use bevy_asset_loader::{AnimationClip, AssetCollection, AssetLoader, NamedTextureAtlas};
 
 /// This example demonstrates how to load a texture atlas from a sprite sheet
 ///
@@ -23,9 +23,17 @@ fn main() {
 #[derive(AssetCollection)]
 struct MyAssets {
     // if the sheet would have padding, we could set that with `padding_x` and `padding_y`
-    #[asset(texture_atlas(tile_size_x = 96., tile_size_y = 99., columns = 8, rows = 1))]
+    // an outer margin can be set with `offset_x` and `offset_y`
+    // animations are optional and can be looked up by name in the `NamedTextureAtlas`
+    #[asset(texture_atlas(
+        tile_size_x = 96.,
+        tile_size_y = 99.,
+        columns = 8,
+        rows = 1,
+        animations(walk(start = 0, end = 8, fps = 10.))
+    ))]
     #[asset(path = "images/female_adventurer_sheet.png")]
-    female_adventurer: Handle<TextureAtlas>,
+    female_adventurer: NamedTextureAtlas,
 }
 
 fn draw_atlas(
@@ -36,38 +44,49 @@ fn draw_atlas(
     commands.spawn_bundle(OrthographicCameraBundle::new_2d());
     // draw the original image (whole atlas)
     let atlas = texture_atlases
-        .get(my_assets.female_adventurer.clone())
+        .get(my_assets.female_adventurer.atlas.clone())
         .expect("Failed to find our atlas");
     commands.spawn_bundle(SpriteBundle {
         texture: atlas.texture.clone(),
         transform: Transform::from_xyz(0., -150., 0.),
         ..Default::default()
     });
-    // draw single texture from sprite sheet starting at index 0
+    // draw single texture from sprite sheet starting at the first frame of the walk animation
+    let walk = *my_assets
+        .female_adventurer
+        .animation("walk")
+        .expect("Failed to find the walk animation");
     commands
         .spawn_bundle(SpriteSheetBundle {
             transform: Transform {
                 translation: Vec3::new(0., 150., 0.),
                 ..Default::default()
             },
-            sprite: TextureAtlasSprite::new(0),
-            texture_atlas: my_assets.female_adventurer.clone(),
+            sprite: TextureAtlasSprite::new(walk.start),
+            texture_atlas: my_assets.female_adventurer.atlas.clone(),
             ..Default::default()
         })
-        .insert(AnimationTimer(Timer::from_seconds(0.1, true)));
+        .insert(Animation {
+            timer: Timer::from_seconds(walk.frame_duration(), true),
+            clip: walk,
+        });
 }
 
 #[derive(Component)]
-struct AnimationTimer(Timer);
+struct Animation {
+    timer: Timer,
+    clip: AnimationClip,
+}
 
 fn animate_sprite_system(
     time: Res<Time>,
-    mut query: Query<(&mut AnimationTimer, &mut TextureAtlasSprite)>,
+    mut query: Query<(&mut Animation, &mut TextureAtlasSprite)>,
 ) {
-    for (mut timer, mut sprite) in query.iter_mut() {
-        timer.0.tick(time.delta());
-        if timer.0.finished() {
-            sprite.index = (sprite.index + 1) % 8;
+    for (mut animation, mut sprite) in query.iter_mut() {
+        animation.timer.tick(time.delta());
+        if animation.timer.finished() {
+            let clip = animation.clip;
+            sprite.index = clip.start + (sprite.index + 1 - clip.start) % clip.len();
         }
     }
 }
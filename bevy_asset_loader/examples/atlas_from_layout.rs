@@ -0,0 +1,69 @@
+use bevy::prelude::*;
+use bevy_asset_loader::{
+    AssetCollection, AssetLoader, NamedTextureAtlas, PackedTextureAtlasPlugin,
+};
+
+/// This example demonstrates how to load a texture atlas from a sprite sheet
+/// and a TexturePacker/Aseprite JSON layout
+///
+/// Requires the feature 'render'
+fn main() {
+    let mut app = App::new();
+    AssetLoader::new(MyStates::AssetLoading)
+        .continue_to_state(MyStates::Next)
+        .with_collection::<MyAssets>()
+        .build(&mut app);
+    app.add_state(MyStates::AssetLoading)
+        .insert_resource(Msaa { samples: 1 })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(PackedTextureAtlasPlugin)
+        .add_system_set(SystemSet::on_enter(MyStates::Next).with_system(draw_atlas))
+        .add_system_set(SystemSet::on_update(MyStates::Next).with_system(animate_sprite_system))
+        .run();
+}
+
+#[derive(AssetCollection)]
+struct MyAssets {
+    // the layout file defines the area and name of every frame on the sprite sheet
+    #[asset(texture_atlas(layout = "images/female_adventurer_sheet.atlas.json"))]
+    #[asset(path = "images/female_adventurer_sheet.png")]
+    female_adventurer: NamedTextureAtlas,
+}
+
+fn draw_atlas(mut commands: Commands, my_assets: Res<MyAssets>) {
+    commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+    // draw the frame with the name "walk_0"
+    commands
+        .spawn_bundle(SpriteSheetBundle {
+            sprite: TextureAtlasSprite::new(
+                my_assets
+                    .female_adventurer
+                    .index("walk_0")
+                    .expect("Frame is missing in the layout"),
+            ),
+            texture_atlas: my_assets.female_adventurer.atlas.clone(),
+            ..Default::default()
+        })
+        .insert(AnimationTimer(Timer::from_seconds(0.1, true)));
+}
+
+#[derive(Component)]
+struct AnimationTimer(Timer);
+
+fn animate_sprite_system(
+    time: Res<Time>,
+    mut query: Query<(&mut AnimationTimer, &mut TextureAtlasSprite)>,
+) {
+    for (mut timer, mut sprite) in query.iter_mut() {
+        timer.0.tick(time.delta());
+        if timer.0.finished() {
+            sprite.index = (sprite.index + 1) % 8;
+        }
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+enum MyStates {
+    AssetLoading,
+    Next,
+}
@@ -0,0 +1,74 @@
+use bevy::gltf::GltfMesh;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_asset_loader::{AssetCollection, AssetLoader};
+
+/// This example demonstrates how to load named sub assets of a glTF file
+///
+/// Requires the feature 'gltf'
+fn main() {
+    let mut app = App::new();
+    AssetLoader::new(MyStates::AssetLoading)
+        .continue_to_state(MyStates::Next)
+        .with_collection::<MyAssets>()
+        .build(&mut app);
+    app.add_state(MyStates::AssetLoading)
+        .insert_resource(Msaa { samples: 4 })
+        .add_plugins(DefaultPlugins)
+        .add_system_set(SystemSet::on_enter(MyStates::Next).with_system(spawn_crates))
+        .run();
+}
+
+#[derive(AssetCollection)]
+struct MyAssets {
+    #[asset(path = "models/crate.gltf", gltf(scene = "Crate"))]
+    crate_scene: Handle<Scene>,
+    #[asset(path = "models/crate.gltf", gltf(mesh = "Crate"))]
+    crate_mesh: Handle<GltfMesh>,
+    #[asset(path = "models/crate.gltf", gltf(named_materials))]
+    materials: HashMap<String, Handle<StandardMaterial>>,
+}
+
+fn spawn_crates(
+    mut commands: Commands,
+    my_assets: Res<MyAssets>,
+    gltf_meshes: Res<Assets<GltfMesh>>,
+) {
+    commands.spawn_bundle(PerspectiveCameraBundle {
+        transform: Transform::from_xyz(0., 2., 4.).looking_at(Vec3::ZERO, Vec3::Y),
+        ..Default::default()
+    });
+    commands.spawn_bundle(PointLightBundle {
+        transform: Transform::from_xyz(2., 4., 2.),
+        ..Default::default()
+    });
+    // the whole scene
+    commands
+        .spawn_bundle((
+            Transform::from_xyz(-1., 0., 0.),
+            GlobalTransform::identity(),
+        ))
+        .with_children(|parent| {
+            parent.spawn_scene(my_assets.crate_scene.clone());
+        });
+    // a single mesh with a material looked up by its name
+    let crate_mesh = gltf_meshes
+        .get(my_assets.crate_mesh.clone())
+        .expect("Failed to find the crate mesh");
+    commands.spawn_bundle(PbrBundle {
+        mesh: crate_mesh.primitives[0].mesh.clone(),
+        material: my_assets
+            .materials
+            .get("Wood")
+            .expect("Failed to find the wood material")
+            .clone(),
+        transform: Transform::from_xyz(1., 0., 0.),
+        ..Default::default()
+    });
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+enum MyStates {
+    AssetLoading,
+    Next,
+}
@@ -48,11 +48,7 @@ impl FromWorld for CombinedImage {
             .data
             .drain(..)
             .enumerate()
-            .map(|(index, player_value)| {
-                player_value
-                    .checked_add(tree_image.data[index])
-                    .unwrap_or(u8::MAX)
-            })
+            .map(|(index, player_value)| player_value.saturating_add(tree_image.data[index]))
             .collect();
         CombinedImage {
             combined: images.add(combined),
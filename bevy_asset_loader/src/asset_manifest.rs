@@ -0,0 +1,416 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use bevy::app::{App, Plugin};
+use bevy::asset::{
+    create_platform_default_asset_io, AssetIo, AssetIoError, AssetServer, HandleUntyped,
+};
+use bevy::ecs::event::Events;
+use bevy::ecs::prelude::State;
+use bevy::ecs::schedule::StateData;
+use bevy::ecs::world::WorldCell;
+use bevy::tasks::IoTaskPool;
+use bevy::utils::{BoxedFuture, HashMap};
+use sha2::{Digest, Sha256};
+
+use crate::systems::LoadingProgress;
+use crate::AssetLoaderConfiguration;
+
+/// Expected content hashes and sizes of asset files
+///
+/// An [`AssetLoader`](crate::AssetLoader) with a manifest verifies all files of a collection
+/// after they finished loading and before the collection is inserted. If any file is missing from
+/// the manifest or its size or SHA-256 hash differ, the collection is not inserted and the loading
+/// state does not finish. An [`AssetManifestMismatch`] event lists all mismatched files.
+///
+/// The manifest is a text file with one line per asset file: the hex encoded SHA-256 hash, the size
+/// in bytes, and the path relative to the asset folder, separated by single spaces.
+/// Generate it with [`AssetManifest::from_folder`] whenever the assets change:
+/// ```edition2021,no_run
+/// # use bevy_asset_loader::AssetManifest;
+/// let manifest = AssetManifest::from_folder("assets").unwrap();
+/// std::fs::write("assets.manifest", manifest.to_string()).unwrap();
+/// ```
+///
+/// The files are hashed while they are loaded, which requires the [`AssetManifestPlugin`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AssetManifest {
+    files: BTreeMap<String, ManifestEntry>,
+}
+
+/// Expected content of a single file in an [`AssetManifest`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ManifestEntry {
+    /// Hex encoded SHA-256 hash of the file content
+    pub hash: String,
+    /// Size of the file in bytes
+    pub size: u64,
+}
+
+impl ManifestEntry {
+    /// Hash and size of the given file content
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        let hash = Sha256::digest(bytes)
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        ManifestEntry {
+            hash,
+            size: bytes.len() as u64,
+        }
+    }
+}
+
+impl AssetManifest {
+    /// Create a manifest with all files in the given folder and its sub folders
+    ///
+    /// The paths in the manifest are relative to the folder.
+    pub fn from_folder<P: AsRef<Path>>(folder: P) -> io::Result<Self> {
+        let folder = folder.as_ref();
+        let mut files = vec![];
+        collect_files(folder, &mut files)?;
+        let mut manifest = AssetManifest::default();
+        for file in files {
+            let bytes = fs::read(&file)?;
+            manifest.insert(
+                relative_path(folder, &file),
+                ManifestEntry::from_bytes(&bytes),
+            );
+        }
+
+        Ok(manifest)
+    }
+
+    /// Add a file or replace its entry
+    pub fn insert<P: Into<String>>(&mut self, path: P, entry: ManifestEntry) {
+        self.files.insert(path.into(), entry);
+    }
+
+    /// Get the entry of a file by its path relative to the asset folder
+    pub fn get(&self, path: &str) -> Option<&ManifestEntry> {
+        self.files.get(path)
+    }
+
+    /// Iterate over all files and their entries sorted by path
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &ManifestEntry)> {
+        self.files.iter()
+    }
+
+    /// Check the hash and size of a file against the manifest
+    ///
+    /// Fails with a description if the file is missing from the manifest or its size or hash differ.
+    pub fn verify(&self, path: &str, actual: &ManifestEntry) -> Result<(), String> {
+        let expected = self
+            .get(path)
+            .ok_or_else(|| format!("'{}' is not listed in the manifest", path))?;
+        if actual.size != expected.size {
+            return Err(format!(
+                "'{}' has {} bytes, but the manifest expects {}",
+                path, actual.size, expected.size
+            ));
+        }
+        if actual.hash != expected.hash {
+            return Err(format!(
+                "'{}' has the hash {}, but the manifest expects {}",
+                path, actual.hash, expected.hash
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for AssetManifest {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (path, entry) in self.files.iter() {
+            writeln!(formatter, "{} {} {}", entry.hash, entry.size, path)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for AssetManifest {
+    type Err = String;
+
+    fn from_str(manifest: &str) -> Result<Self, Self::Err> {
+        let mut files = BTreeMap::new();
+        for (index, line) in manifest.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut parts = line.splitn(3, ' ');
+            let (hash, size, path) = match (parts.next(), parts.next(), parts.next()) {
+                (Some(hash), Some(size), Some(path)) => (hash, size, path),
+                _ => {
+                    return Err(format!(
+                        "Line {} of the asset manifest is not '<hash> <size> <path>'",
+                        index + 1
+                    ))
+                }
+            };
+            let size = size.parse().map_err(|_| {
+                format!(
+                    "Line {} of the asset manifest has the invalid size '{}'",
+                    index + 1,
+                    size
+                )
+            })?;
+            files.insert(
+                path.to_owned(),
+                ManifestEntry {
+                    hash: hash.to_lowercase(),
+                    size,
+                },
+            );
+        }
+
+        Ok(AssetManifest { files })
+    }
+}
+
+/// Sent when files of a collection do not match the [`AssetManifest`] of its loading state
+#[derive(Clone, Debug)]
+pub struct AssetManifestMismatch {
+    /// Type name of the collection
+    pub collection: &'static str,
+    /// Descriptions of all mismatched files
+    pub mismatches: Vec<String>,
+}
+
+/// Hash the files of the app while they are loaded to verify them against an [`AssetManifest`]
+///
+/// The plugin replaces the [`AssetServer`] and needs to be added before Bevy's `AssetPlugin`.
+/// Files are loaded from the platform's default [`AssetIo`] or the one given to
+/// [`AssetManifestPlugin::with_asset_io`]. They are hashed on the IO threads of the
+/// [`AssetServer`], so the verification does not read any file again.
+/// ```edition2021,no_run
+/// # use bevy::prelude::*;
+/// # use bevy::asset::AssetPlugin;
+/// # use bevy_asset_loader::AssetManifestPlugin;
+/// # fn main() {
+/// App::new()
+///     .add_plugins_with(DefaultPlugins, |group| {
+///         group.add_before::<AssetPlugin, _>(AssetManifestPlugin::default())
+///     })
+///     .run();
+/// # }
+/// ```
+#[derive(Default)]
+pub struct AssetManifestPlugin {
+    io: Mutex<Option<Box<dyn AssetIo>>>,
+}
+
+impl AssetManifestPlugin {
+    /// Load and hash the files of the given [`AssetIo`], e.g. a [`PackAssetIo`](crate::PackAssetIo)
+    pub fn with_asset_io<T: AssetIo>(io: T) -> Self {
+        AssetManifestPlugin {
+            io: Mutex::new(Some(Box::new(io))),
+        }
+    }
+}
+
+impl Plugin for AssetManifestPlugin {
+    fn build(&self, app: &mut App) {
+        if app.world.contains_resource::<AssetServer>() {
+            panic!("The AssetManifestPlugin needs to be added before the AssetPlugin");
+        }
+        let io = self
+            .io
+            .lock()
+            .unwrap()
+            .take()
+            .unwrap_or_else(|| create_platform_default_asset_io(app));
+        let io = hash_loaded_files(app, io);
+        let task_pool = app
+            .world
+            .get_resource::<IoTaskPool>()
+            .expect("Cannot get resource IoTaskPool")
+            .0
+            .clone();
+        app.insert_resource(AssetServer::with_boxed_io(io, task_pool));
+    }
+}
+
+/// Hashes and sizes of all files loaded through a [`HashingAssetIo`] by their path
+#[derive(Clone, Default)]
+pub(crate) struct LoadedFiles(Arc<Mutex<HashMap<String, ManifestEntry>>>);
+
+/// Wrap the [`AssetIo`] to hash all files it loads
+pub(crate) fn hash_loaded_files(app: &mut App, io: Box<dyn AssetIo>) -> Box<dyn AssetIo> {
+    let loaded_files = app
+        .world
+        .get_resource_or_insert_with(LoadedFiles::default)
+        .clone();
+    app.add_event::<AssetManifestMismatch>();
+
+    Box::new(HashingAssetIo { io, loaded_files })
+}
+
+struct HashingAssetIo {
+    io: Box<dyn AssetIo>,
+    loaded_files: LoadedFiles,
+}
+
+impl AssetIo for HashingAssetIo {
+    fn load_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+        Box::pin(async move {
+            let bytes = self.io.load_path(path).await?;
+            self.loaded_files.0.lock().unwrap().insert(
+                relative_path(Path::new(""), path),
+                ManifestEntry::from_bytes(&bytes),
+            );
+            Ok(bytes)
+        })
+    }
+
+    fn read_directory(
+        &self,
+        path: &Path,
+    ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
+        self.io.read_directory(path)
+    }
+
+    fn is_directory(&self, path: &Path) -> bool {
+        self.io.is_directory(path)
+    }
+
+    fn watch_path_for_changes(&self, path: &Path) -> Result<(), AssetIoError> {
+        self.io.watch_path_for_changes(path)
+    }
+
+    fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        self.io.watch_for_changes()
+    }
+}
+
+/// Verify the files of the given handles against the manifest of the current loading state
+///
+/// Returns `false` if a file does not match. Mismatched files are recorded as failed
+/// and reported once with an [`AssetManifestMismatch`] event.
+pub(crate) fn verify_loaded_files<S: StateData>(
+    cell: &WorldCell,
+    handles: &[HandleUntyped],
+    collection: &'static str,
+) -> bool {
+    let state = cell.get_resource::<State<S>>().expect("Cannot get state");
+    let asset_loader_configuration = cell
+        .get_resource::<AssetLoaderConfiguration<S>>()
+        .expect("Cannot get AssetLoaderConfiguration");
+    let manifest = match asset_loader_configuration
+        .configuration
+        .get(state.current())
+        .and_then(|config| config.manifest.as_ref())
+    {
+        Some(manifest) => manifest,
+        None => return true,
+    };
+    let asset_server = cell
+        .get_resource::<AssetServer>()
+        .expect("Cannot get AssetServer resource");
+    let loaded_files = cell
+        .get_resource::<LoadedFiles>()
+        .expect("Cannot get LoadedFiles resource");
+    let loaded_files = loaded_files.0.lock().unwrap();
+    let mut paths: Vec<String> = handles
+        .iter()
+        .filter_map(|handle| asset_server.get_handle_path(handle))
+        .map(|asset_path| relative_path(Path::new(""), asset_path.path()))
+        .collect();
+    paths.sort();
+    paths.dedup();
+    let mismatches: Vec<(String, String)> = paths
+        .into_iter()
+        .filter_map(|path| {
+            let verified = match loaded_files.get(&path) {
+                Some(actual) => manifest.verify(&path, actual),
+                None => Err(format!("'{}' was not hashed while loading", path)),
+            };
+            verified.err().map(|mismatch| (path, mismatch))
+        })
+        .collect();
+    if mismatches.is_empty() {
+        return true;
+    }
+
+    let mut progress = cell
+        .get_resource_mut::<LoadingProgress>()
+        .expect("Cannot get LoadingProgress resource");
+    let mut newly_failed = false;
+    for (path, _) in mismatches.iter() {
+        newly_failed |= progress.failed.insert(path.clone());
+    }
+    if newly_failed {
+        cell.get_resource_mut::<Events<AssetManifestMismatch>>()
+            .expect("Cannot get Events<AssetManifestMismatch> resource")
+            .send(AssetManifestMismatch {
+                collection,
+                mismatches: mismatches
+                    .into_iter()
+                    .map(|(_, mismatch)| mismatch)
+                    .collect(),
+            });
+    }
+
+    false
+}
+
+fn collect_files(folder: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(folder)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Path relative to the folder with '/' as separator
+fn relative_path(folder: &Path, path: &Path) -> String {
+    let components: Vec<_> = path
+        .strip_prefix(folder)
+        .unwrap_or(path)
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect();
+    components.join("/")
+}
+
+#[cfg(test)]
+mod test {
+    use super::{AssetManifest, ManifestEntry};
+
+    #[test]
+    fn parses_written_manifests() {
+        let mut manifest = AssetManifest::default();
+        manifest.insert("images/player.png", ManifestEntry::from_bytes(b"player"));
+        manifest.insert("audio/with space.ogg", ManifestEntry::from_bytes(b""));
+
+        assert_eq!(
+            manifest.to_string(),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855 0 audio/with space.ogg\n\
+             cdb59355f3ba293977fc0945fb85f11822d412c45c7520c7121bd2234f6c1f48 6 images/player.png\n"
+        );
+        assert_eq!(manifest.to_string().parse::<AssetManifest>(), Ok(manifest));
+    }
+
+    #[test]
+    fn rejects_invalid_lines() {
+        assert_eq!(
+            "abc 12".parse::<AssetManifest>(),
+            Err("Line 1 of the asset manifest is not '<hash> <size> <path>'".to_owned())
+        );
+        assert_eq!(
+            "\nabc big file.png".parse::<AssetManifest>(),
+            Err("Line 2 of the asset manifest has the invalid size 'big'".to_owned())
+        );
+    }
+}
@@ -0,0 +1,532 @@
+use std::collections::{BTreeSet, VecDeque};
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+
+use bevy::app::{App, Plugin};
+use bevy::asset::{
+    create_platform_default_asset_io, AssetIo, AssetIoError, AssetServer, FileAssetIo,
+};
+use bevy::tasks::IoTaskPool;
+use bevy::utils::{BoxedFuture, HashMap};
+use serde::Deserialize;
+
+use crate::asset_references::{collect_files, is_referenced, read_asset_collection_file};
+use crate::{AssetCollection, DynamicAsset};
+
+/// An [`AssetIo`] serving assets from a tar pack
+///
+/// Paths in the pack are relative to the asset folder. Optionally, the pack is layered over
+/// another [`AssetIo`]. Files that are not in the pack are then loaded from there, which allows
+/// shipping loose files next to the pack, e.g. for patches or mods.
+///
+/// Opening a pack only reads the tar headers. The content of a file is read from the pack when
+/// the file is loaded, so the pack stays open as long as the [`PackAssetIo`] exists.
+///
+/// Use the [`AssetPackPlugin`] to load all assets of an app from a pack.
+pub struct PackAssetIo {
+    pack: Mutex<Box<dyn PackReader>>,
+    files: HashMap<PathBuf, PackedFile>,
+    directories: HashMap<PathBuf, BTreeSet<PathBuf>>,
+    fallback: Option<Box<dyn AssetIo>>,
+}
+
+trait PackReader: Read + Seek + Send {}
+
+impl<R: Read + Seek + Send> PackReader for R {}
+
+/// Position of a file's content in the pack
+struct PackedFile {
+    offset: u64,
+    size: u64,
+}
+
+impl PackAssetIo {
+    /// Open the pack at the given path of a tar file
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        PackAssetIo::from_reader(File::open(path)?)
+    }
+
+    /// Read the pack from tar data
+    ///
+    /// Only the headers are read right away. The reader is kept to read files on demand.
+    pub fn from_reader<R: Read + Seek + Send + 'static>(reader: R) -> io::Result<Self> {
+        let mut files = HashMap::default();
+        let mut directories: HashMap<PathBuf, BTreeSet<PathBuf>> = HashMap::default();
+        let mut archive = tar::Archive::new(reader);
+        for entry in archive.entries_with_seek()? {
+            let entry = entry?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let path = normalize(&entry.path()?);
+            let mut child = path.clone();
+            while let Some(parent) = child.parent() {
+                directories
+                    .entry(parent.to_path_buf())
+                    .or_default()
+                    .insert(child.clone());
+                child = parent.to_path_buf();
+            }
+            files.insert(
+                path,
+                PackedFile {
+                    offset: entry.raw_file_position(),
+                    size: entry.size(),
+                },
+            );
+        }
+
+        Ok(PackAssetIo {
+            pack: Mutex::new(Box::new(archive.into_inner())),
+            files,
+            directories,
+            fallback: None,
+        })
+    }
+
+    /// Load files that are not in the pack from the given [`AssetIo`]
+    pub fn with_fallback<T: AssetIo>(self, fallback: T) -> Self {
+        self.with_boxed_fallback(Box::new(fallback))
+    }
+
+    /// Load files that are not in the pack from the given boxed [`AssetIo`]
+    pub fn with_boxed_fallback(mut self, fallback: Box<dyn AssetIo>) -> Self {
+        self.fallback = Some(fallback);
+
+        self
+    }
+
+    /// Returns `true` if the pack contains the file
+    pub fn contains(&self, path: &Path) -> bool {
+        self.files.contains_key(&normalize(path))
+    }
+
+    fn read(&self, file: &PackedFile) -> io::Result<Vec<u8>> {
+        let mut pack = self.pack.lock().unwrap();
+        pack.seek(SeekFrom::Start(file.offset))?;
+        let mut bytes = vec![0; file.size as usize];
+        pack.read_exact(&mut bytes)?;
+
+        Ok(bytes)
+    }
+}
+
+impl AssetIo for PackAssetIo {
+    fn load_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+        Box::pin(async move {
+            if let Some(file) = self.files.get(&normalize(path)) {
+                return self.read(file).map_err(AssetIoError::Io);
+            }
+            match self.fallback.as_ref() {
+                Some(fallback) => fallback.load_path(path).await,
+                None => Err(AssetIoError::NotFound(path.to_path_buf())),
+            }
+        })
+    }
+
+    fn read_directory(
+        &self,
+        path: &Path,
+    ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
+        let mut children = self
+            .directories
+            .get(&normalize(path))
+            .cloned()
+            .unwrap_or_default();
+        if let Some(fallback) = self.fallback.as_ref() {
+            if fallback.is_directory(path) {
+                children.extend(
+                    fallback
+                        .read_directory(path)?
+                        .map(|child| normalize(&child)),
+                );
+            }
+        }
+        if children.is_empty() && !self.is_directory(path) {
+            return Err(AssetIoError::NotFound(path.to_path_buf()));
+        }
+
+        Ok(Box::new(children.into_iter()))
+    }
+
+    fn is_directory(&self, path: &Path) -> bool {
+        self.directories.contains_key(&normalize(path))
+            || self
+                .fallback
+                .as_ref()
+                .map(|fallback| fallback.is_directory(path))
+                .unwrap_or(false)
+    }
+
+    fn watch_path_for_changes(&self, path: &Path) -> Result<(), AssetIoError> {
+        match self.fallback.as_ref() {
+            Some(fallback) => fallback.watch_path_for_changes(path),
+            None => Ok(()),
+        }
+    }
+
+    fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        match self.fallback.as_ref() {
+            Some(fallback) => fallback.watch_for_changes(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Path without leading `./` components
+fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| matches!(component, Component::Normal(_)))
+        .collect()
+}
+
+/// Load all assets of the app from a tar pack
+///
+/// The plugin replaces the [`AssetServer`] and needs to be added before Bevy's `AssetPlugin`.
+/// The path of the pack is relative to the same root as the asset folder.
+/// ```edition2021,no_run
+/// # use bevy::prelude::*;
+/// # use bevy::asset::AssetPlugin;
+/// # use bevy_asset_loader::AssetPackPlugin;
+/// # fn main() {
+/// App::new()
+///     .add_plugins_with(DefaultPlugins, |group| {
+///         group.add_before::<AssetPlugin, _>(
+///             AssetPackPlugin::new("assets.tar").layered_over_file_system(),
+///         )
+///     })
+///     .run();
+/// # }
+/// ```
+pub struct AssetPackPlugin {
+    path: PathBuf,
+    layered_over_file_system: bool,
+}
+
+impl AssetPackPlugin {
+    /// Load assets from the pack at the given path
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        AssetPackPlugin {
+            path: path.into(),
+            layered_over_file_system: false,
+        }
+    }
+
+    /// Load files that are not in the pack from the asset folder
+    pub fn layered_over_file_system(mut self) -> Self {
+        self.layered_over_file_system = true;
+
+        self
+    }
+}
+
+impl Plugin for AssetPackPlugin {
+    fn build(&self, app: &mut App) {
+        if app.world.contains_resource::<AssetServer>() {
+            panic!("The AssetPackPlugin needs to be added before the AssetPlugin");
+        }
+        let path = FileAssetIo::get_root_path().join(&self.path);
+        let mut pack = PackAssetIo::open(&path).unwrap_or_else(|error| {
+            panic!(
+                "Failed to open the asset pack '{}': {}",
+                path.display(),
+                error
+            )
+        });
+        if self.layered_over_file_system {
+            pack = pack.with_boxed_fallback(create_platform_default_asset_io(app));
+        }
+        let task_pool = app
+            .world
+            .get_resource::<IoTaskPool>()
+            .expect("Cannot get resource IoTaskPool")
+            .0
+            .clone();
+        app.insert_resource(AssetServer::new(pack, task_pool));
+    }
+}
+
+/// Writes the files referenced by asset collections and asset collection files into a tar pack
+///
+/// Folders add all files they contain. Paths with [asset variables](crate::AssetVariables) add all
+/// files matching the path for any value of the variables. glTF files add the buffers and images
+/// they reference by relative paths.
+/// Files of custom dynamic asset types are unknown to the packer and need to be added
+/// with [`AssetPacker::with_file`].
+///
+/// Packs are uncompressed tar files, other archive formats are not supported.
+/// ```edition2021,no_run
+/// # use bevy::prelude::*;
+/// # use bevy_asset_loader::{AssetCollection, AssetPacker};
+/// AssetPacker::new("assets")
+///     .with_asset_collection_file("levels.assets")
+///     .with_collection::<AudioAssets>()
+///     .pack("assets.tar")
+///     .unwrap();
+/// # #[derive(AssetCollection)]
+/// # pub struct AudioAssets {
+/// #     #[asset(path = "audio/background.ogg")]
+/// #     pub background: Handle<AudioSource>,
+/// # }
+/// ```
+pub struct AssetPacker {
+    asset_folder: PathBuf,
+    asset_collection_files: Vec<String>,
+    collections: Vec<Vec<(String, DynamicAsset)>>,
+    files: Vec<String>,
+}
+
+impl AssetPacker {
+    /// Create a packer for files in the given asset folder
+    pub fn new<P: Into<PathBuf>>(asset_folder: P) -> Self {
+        AssetPacker {
+            asset_folder: asset_folder.into(),
+            asset_collection_files: vec![],
+            collections: vec![],
+            files: vec![],
+        }
+    }
+
+    /// Pack an asset collection file and all files it references
+    ///
+    /// The path is relative to the asset folder. Included files are packed as well.
+    pub fn with_asset_collection_file(mut self, path: &str) -> Self {
+        self.asset_collection_files.push(path.to_owned());
+
+        self
+    }
+
+    /// Pack all files referenced by `path` attributes of a derived [`AssetCollection`]
+    pub fn with_collection<A: AssetCollection>(mut self) -> Self {
+        self.collections.push(A::dynamic_assets());
+
+        self
+    }
+
+    /// Pack a file or folder
+    pub fn with_file(mut self, path: &str) -> Self {
+        self.files.push(path.to_owned());
+
+        self
+    }
+
+    /// All files that will be packed, relative to the asset folder and sorted
+    ///
+    /// Fails with a list of all asset collection files that cannot be read
+    /// and all referenced files that do not exist.
+    pub fn files(&self) -> Result<Vec<String>, String> {
+        let mut available = vec![];
+        collect_files(&self.asset_folder, &self.asset_folder, &mut available);
+        let mut packed = BTreeSet::new();
+        let mut errors = vec![];
+
+        let mut queue: VecDeque<String> = self.asset_collection_files.iter().cloned().collect();
+        let mut read_files = BTreeSet::new();
+        while let Some(file) = queue.pop_front() {
+            if !read_files.insert(file.clone()) {
+                continue;
+            }
+            add_referenced_files(&available, &file, &file, &mut packed, &mut errors);
+            let collection = match read_asset_collection_file(&self.asset_folder, &file) {
+                Ok(collection) => collection,
+                Err(error) => {
+                    errors.push(format!("{}: {}", file, error));
+                    continue;
+                }
+            };
+            queue.extend(collection.includes);
+            let localized = collection
+                .localized
+                .into_iter()
+                .flat_map(|(_, assets)| assets);
+            for (key, asset) in collection.assets.into_iter().chain(localized) {
+                if let Some(asset) = asset {
+                    for path in asset.get_file_paths() {
+                        let source = format!("{} '{}'", file, key);
+                        add_referenced_files(&available, &source, path, &mut packed, &mut errors);
+                    }
+                }
+            }
+        }
+        for (field, asset) in self.collections.iter().flatten() {
+            for path in asset.get_file_paths() {
+                let source = format!("field '{}'", field);
+                add_referenced_files(&available, &source, path, &mut packed, &mut errors);
+            }
+        }
+        for file in self.files.iter() {
+            add_referenced_files(&available, file, file, &mut packed, &mut errors);
+        }
+        let gltf_files: Vec<String> = packed
+            .iter()
+            .filter(|file| file.ends_with(".gltf") || file.ends_with(".glb"))
+            .cloned()
+            .collect();
+        for file in gltf_files {
+            match gltf_references(&self.asset_folder.join(&file)) {
+                Ok(uris) => {
+                    for uri in uris {
+                        let path = resolve_gltf_reference(&file, &uri);
+                        add_referenced_files(&available, &file, &path, &mut packed, &mut errors);
+                    }
+                }
+                Err(error) => errors.push(format!(
+                    "{}: failed to read the files referenced by the glTF: {}",
+                    file, error
+                )),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(packed.into_iter().collect())
+        } else {
+            Err(errors.join("\n"))
+        }
+    }
+
+    /// Write all files into a tar pack at the given path and return the packed files
+    ///
+    /// The pack only depends on the content of the files, so packing unchanged files
+    /// results in the same pack.
+    pub fn pack<P: AsRef<Path>>(&self, output: P) -> Result<Vec<String>, String> {
+        let files = self.files()?;
+        let output = output.as_ref();
+        let write = || -> io::Result<()> {
+            let mut builder = tar::Builder::new(File::create(output)?);
+            for file in files.iter() {
+                let bytes = std::fs::read(self.asset_folder.join(file))?;
+                let mut header = tar::Header::new_gnu();
+                header.set_size(bytes.len() as u64);
+                header.set_mode(0o644);
+                header.set_mtime(0);
+                header.set_cksum();
+                builder.append_data(&mut header, file, bytes.as_slice())?;
+            }
+            builder.into_inner()?;
+            Ok(())
+        };
+        write().map_err(|error| {
+            format!(
+                "Failed to write the asset pack '{}': {}",
+                output.display(),
+                error
+            )
+        })?;
+
+        Ok(files)
+    }
+}
+
+/// Buffers and images of a glTF file
+#[derive(Deserialize)]
+struct GltfReferences {
+    #[serde(default)]
+    buffers: Vec<GltfUri>,
+    #[serde(default)]
+    images: Vec<GltfUri>,
+}
+
+#[derive(Deserialize)]
+struct GltfUri {
+    uri: Option<String>,
+}
+
+/// URIs of the external files a glTF file references
+///
+/// Embedded data URIs and buffer views are skipped.
+fn gltf_references(path: &Path) -> Result<Vec<String>, String> {
+    let bytes = std::fs::read(path).map_err(|error| error.to_string())?;
+    let json = if bytes.starts_with(b"glTF") {
+        glb_json_chunk(&bytes)?
+    } else {
+        &bytes
+    };
+    let references: GltfReferences =
+        serde_json::from_slice(json).map_err(|error| error.to_string())?;
+
+    Ok(references
+        .buffers
+        .into_iter()
+        .chain(references.images)
+        .filter_map(|reference| reference.uri)
+        .filter(|uri| !uri.starts_with("data:"))
+        .collect())
+}
+
+/// The JSON chunk of a binary glTF file, which directly follows the 12 byte header
+fn glb_json_chunk(bytes: &[u8]) -> Result<&[u8], String> {
+    let length = bytes
+        .get(12..16)
+        .map(|length| u32::from_le_bytes([length[0], length[1], length[2], length[3]]) as usize);
+    match (length, bytes.get(16..20)) {
+        (Some(length), Some(b"JSON")) => bytes
+            .get(20..20 + length)
+            .ok_or_else(|| "the JSON chunk is truncated".to_owned()),
+        _ => Err("the file has no JSON chunk".to_owned()),
+    }
+}
+
+/// Path relative to the asset folder of a URI relative to the glTF file
+///
+/// Like Bevy's glTF loader, the URI is percent decoded.
+fn resolve_gltf_reference(file: &str, uri: &str) -> String {
+    let mut components: Vec<String> = file.split('/').map(str::to_owned).collect();
+    components.pop();
+    for component in percent_decode(uri).split('/') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                components.pop();
+            }
+            component => components.push(component.to_owned()),
+        }
+    }
+
+    components.join("/")
+}
+
+fn percent_decode(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = bytes
+            .get(index + 1..index + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[index], escaped) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn add_referenced_files(
+    available: &[String],
+    source: &str,
+    path: &str,
+    packed: &mut BTreeSet<String>,
+    errors: &mut Vec<String>,
+) {
+    // labels are not part of the file path
+    let path = path.split('#').next().unwrap_or_default();
+    let path = path.trim_end_matches('/');
+    let referenced: Vec<&String> = available
+        .iter()
+        .filter(|file| is_referenced(path, file))
+        .collect();
+    if referenced.is_empty() {
+        errors.push(format!("{}: the file '{}' does not exist", source, path));
+    }
+    packed.extend(referenced.into_iter().cloned());
+}
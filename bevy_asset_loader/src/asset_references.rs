@@ -0,0 +1,128 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "asset_pack")]
+use crate::asset_variables::is_variable_name;
+use crate::dynamic_asset::{
+    deserialize_asset_collection_file, BuiltInTypes, ParsedAssetCollection,
+};
+use crate::{DynamicAsset, DynamicAssetFormat};
+
+/// Parse an asset collection file at the given path relative to the asset folder
+///
+/// Dynamic assets with custom types are `None`, since their registrations are unknown.
+pub(crate) fn read_asset_collection_file(
+    asset_folder: &Path,
+    file: &str,
+) -> Result<ParsedAssetCollection<Option<DynamicAsset>>, String> {
+    let format = file_format(file).ok_or_else(|| {
+        "the file ending does not belong to an enabled asset collection file format".to_owned()
+    })?;
+    let bytes = fs::read(asset_folder.join(file))
+        .map_err(|error| format!("failed to read the file: {}", error))?;
+    deserialize_asset_collection_file(format, &bytes, BuiltInTypes)
+        .map_err(|error| error.to_string())
+}
+
+/// Returns `true` if the path references the file or a folder containing the file
+///
+/// Asset variables in the path match any value without a `/`.
+#[cfg(feature = "asset_pack")]
+pub(crate) fn is_referenced(path: &str, file: &str) -> bool {
+    if !path.contains('{') {
+        return file == path || file.starts_with(&format!("{}/", path));
+    }
+    file.match_indices('/')
+        .map(|(index, _)| &file[..index])
+        .chain(std::iter::once(file))
+        .any(|candidate| matches_pattern(path, candidate))
+}
+
+#[cfg(feature = "asset_pack")]
+fn matches_pattern(pattern: &str, text: &str) -> bool {
+    let start = match pattern.find('{') {
+        Some(start) => start,
+        None => return pattern == text,
+    };
+    if text.get(..start) != Some(&pattern[..start]) {
+        return false;
+    }
+    let candidate = &pattern[start + 1..];
+    let text = &text[start..];
+    match candidate.find('}') {
+        Some(end) if is_variable_name(&candidate[..end]) => {
+            let rest = &candidate[end + 1..];
+            text.char_indices()
+                .skip(1)
+                .map(|(index, _)| index)
+                .chain(std::iter::once(text.len()))
+                .take_while(|&index| !text[..index].contains('/'))
+                .any(|index| matches_pattern(rest, &text[index..]))
+        }
+        _ => text.starts_with('{') && matches_pattern(candidate, &text[1..]),
+    }
+}
+
+/// Format of an asset collection file by its ending
+pub(crate) fn file_format(file: &str) -> Option<DynamicAssetFormat> {
+    // longer endings like "assets.json" take precedence over "assets"
+    let mut formats = DynamicAssetFormat::enabled();
+    formats.sort_by_key(|format| std::cmp::Reverse(format.default_file_ending().len()));
+    formats
+        .into_iter()
+        .find(|format| file.ends_with(&format!(".{}", format.default_file_ending())))
+}
+
+/// All files below the folder with paths relative to the root, sorted
+pub(crate) fn collect_files(root: &Path, folder: &Path, files: &mut Vec<String>) {
+    let mut entries: Vec<PathBuf> = match fs::read_dir(folder) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .collect(),
+        Err(_) => return,
+    };
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            collect_files(root, &path, files);
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            let relative: Vec<_> = relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect();
+            files.push(relative.join("/"));
+        }
+    }
+}
+
+#[cfg(all(test, feature = "asset_pack"))]
+mod test {
+    use super::is_referenced;
+
+    #[test]
+    fn references_files_and_folders() {
+        assert!(is_referenced("images/player.png", "images/player.png"));
+        assert!(is_referenced("images", "images/player.png"));
+        assert!(!is_referenced("image", "images/player.png"));
+    }
+
+    #[test]
+    fn matches_asset_variables() {
+        assert!(is_referenced(
+            "levels/{level}/tiles.png",
+            "levels/forest/tiles.png"
+        ));
+        assert!(is_referenced("{locale}/voice.ogg", "de-AT/voice.ogg"));
+        assert!(is_referenced("levels/{level}", "levels/forest/tiles.png"));
+        assert!(!is_referenced(
+            "levels/{level}.png",
+            "levels/forest/tiles.png"
+        ));
+        assert!(!is_referenced(
+            "levels/{level}/tiles.png",
+            "levels//tiles.png"
+        ));
+        assert!(is_referenced("some/{}/path", "some/{}/path"));
+    }
+}
@@ -0,0 +1,183 @@
+use bevy::ecs::world::WorldCell;
+use bevy::utils::HashMap;
+
+use crate::locale::{CurrentLocale, LOCALE_VARIABLE};
+use crate::quality::{AssetQuality, QualityTier, QUALITY_VARIABLE};
+
+/// Values for variables in asset paths
+///
+/// Asset paths can contain variables in curly braces, like `"levels/{level}/tiles.png"`. This works
+/// for paths in `#[asset(...)]` attributes and in asset collection files. The variables are replaced
+/// with their current values whenever a collection is loaded, so the same collection can be loaded
+/// for different levels or skins.
+/// ```edition2021
+/// # use bevy::prelude::*;
+/// # use bevy_asset_loader::{AssetCollection, AssetVariables};
+/// #[derive(AssetCollection)]
+/// struct LevelAssets {
+///     #[asset(path = "levels/{level}/tiles.png")]
+///     tiles: Handle<Image>,
+/// }
+///
+/// fn select_level(mut variables: ResMut<AssetVariables>) {
+///     variables.set("level", "forest");
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct AssetVariables {
+    variables: HashMap<String, String>,
+}
+
+impl AssetVariables {
+    /// Set the value of a variable
+    pub fn set<N: Into<String>, V: Into<String>>(&mut self, name: N, value: V) {
+        self.variables.insert(name.into(), value.into());
+    }
+
+    /// Get the value of a variable
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.variables.get(name).map(|value| value.as_str())
+    }
+
+    /// Remove a variable and return its value
+    pub fn remove(&mut self, name: &str) -> Option<String> {
+        self.variables.remove(name)
+    }
+
+    /// Replace all variables in the given path with their values
+    ///
+    /// Braces that do not enclose a variable name are kept as they are.
+    ///
+    /// # Panics
+    /// Panics if the path contains a variable without a value.
+    pub fn resolve(&self, path: &str) -> String {
+        resolve_variables(path, |name| self.get(name).map(str::to_owned))
+    }
+
+    /// Replace all variables in the given path with the values from the [`AssetVariables`] resource
+    ///
+    /// The variable `{locale}` defaults to the locale of the [`CurrentLocale`] resource and
+    /// `{quality}` to the tier of the [`AssetQuality`] resource. Once the file of a tier failed to
+    /// load, `{quality}` resolves to the next lower tier for that path. Once the file of a locale
+    /// failed to load, `{locale}` resolves to the next locale of the fallback chain.
+    /// Paths without variables are returned unchanged, even if the resources do not exist.
+    /// This is useful when implementing [`DynamicAssetType`](crate::DynamicAssetType).
+    ///
+    /// # Panics
+    /// Panics if the path contains a variable without a value.
+    pub fn resolve_path(cell: &WorldCell, path: &str) -> String {
+        if !path.contains('{') {
+            return path.to_owned();
+        }
+        let variables = cell.get_resource::<AssetVariables>();
+        let resolve = |quality: Option<QualityTier>, locale: Option<&str>| {
+            resolve_variables(path, |name| {
+                if let Some(value) = variables.as_ref().and_then(|variables| variables.get(name)) {
+                    return Some(value.to_owned());
+                }
+                if name == QUALITY_VARIABLE {
+                    return quality.map(|tier| tier.folder_name().to_owned());
+                }
+                if name == LOCALE_VARIABLE {
+                    return locale.map(|locale| locale.to_owned());
+                }
+                None
+            })
+        };
+        let is_set = |name: &str| {
+            variables
+                .as_ref()
+                .map(|variables| variables.get(name).is_some())
+                .unwrap_or(false)
+        };
+        let resolve_quality = |locale: Option<&str>| {
+            let quality_variable = format!("{{{}}}", QUALITY_VARIABLE);
+            if !path.contains(&quality_variable) || is_set(QUALITY_VARIABLE) {
+                return resolve(None, locale);
+            }
+            let mut quality = cell.get_resource_mut::<AssetQuality>().unwrap_or_else(|| {
+                panic!(
+                    "Cannot get resource AssetQuality to resolve the path '{}'",
+                    path
+                )
+            });
+            quality.resolve_variant(|tier| resolve(Some(tier), locale))
+        };
+        let locale_variable = format!("{{{}}}", LOCALE_VARIABLE);
+        if !path.contains(&locale_variable) || is_set(LOCALE_VARIABLE) {
+            return resolve_quality(None);
+        }
+        let mut locale = cell.get_resource_mut::<CurrentLocale>().unwrap_or_else(|| {
+            panic!(
+                "Cannot get resource CurrentLocale to resolve the localized path '{}'",
+                path
+            )
+        });
+        locale.resolve_variant(|locale| resolve_quality(Some(locale)))
+    }
+}
+
+fn resolve_variables<F: Fn(&str) -> Option<String>>(path: &str, value: F) -> String {
+    let mut resolved = String::with_capacity(path.len());
+    let mut rest = path;
+    while let Some(start) = rest.find('{') {
+        resolved.push_str(&rest[..start]);
+        let candidate = &rest[start + 1..];
+        match candidate.find('}') {
+            Some(end) if is_variable_name(&candidate[..end]) => {
+                let name = &candidate[..end];
+                let value = value(name).unwrap_or_else(|| {
+                    panic!("Unknown asset variable '{}' in path '{}'", name, path)
+                });
+                resolved.push_str(&value);
+                rest = &candidate[end + 1..];
+            }
+            _ => {
+                resolved.push('{');
+                rest = candidate;
+            }
+        }
+    }
+    resolved.push_str(rest);
+    resolved
+}
+
+pub(crate) fn is_variable_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|character| character.is_ascii_alphanumeric() || character == '_')
+}
+
+#[cfg(test)]
+mod test {
+    use super::AssetVariables;
+
+    #[test]
+    fn resolves_variables() {
+        let mut variables = AssetVariables::default();
+        variables.set("level", "forest");
+        variables.set("skin_name", "night");
+
+        assert_eq!(
+            variables.resolve("levels/{level}/{skin_name}.png"),
+            "levels/forest/night.png"
+        );
+        assert_eq!(variables.resolve("{level}"), "forest");
+    }
+
+    #[test]
+    fn keeps_braces_without_variable_names() {
+        let variables = AssetVariables::default();
+
+        assert_eq!(variables.resolve("some/{}/path"), "some/{}/path");
+        assert_eq!(variables.resolve("some/{not a var}"), "some/{not a var}");
+        assert_eq!(variables.resolve("some/{open"), "some/{open");
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown asset variable 'level' in path 'levels/{level}.png'")]
+    fn panics_for_unknown_variables() {
+        AssetVariables::default().resolve("levels/{level}.png");
+    }
+}
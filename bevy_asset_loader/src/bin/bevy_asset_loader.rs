@@ -0,0 +1,62 @@
+//! Validate asset collection files without starting the game
+//!
+//! ```text
+//! bevy_asset_loader [--assets <folder>] [--deny-unused] [<file>...]
+//! ```
+//! Without files, all asset collection files in the asset folder are checked.
+//! The process exits with an error code if any check fails, so it can run in CI.
+
+use std::env;
+use std::process::exit;
+
+use bevy_asset_loader::AssetValidator;
+
+const USAGE: &str = "Usage: bevy_asset_loader [--assets <folder>] [--deny-unused] [<file>...]
+
+Checks asset collection files against the asset folder (default: \"assets\").
+Without files, all asset collection files in the asset folder are checked.
+
+Options:
+    --assets <folder>  Path to the asset folder
+    --deny-unused      Fail if the asset folder contains unused files
+    --help             Print this message";
+
+fn main() {
+    let mut asset_folder = "assets".to_owned();
+    let mut deny_unused = false;
+    let mut files = vec![];
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--assets" => {
+                asset_folder = args.next().unwrap_or_else(|| {
+                    eprintln!("Missing folder after '--assets'\n\n{}", USAGE);
+                    exit(2);
+                })
+            }
+            "--deny-unused" => deny_unused = true,
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                return;
+            }
+            _ if arg.starts_with("--") => {
+                eprintln!("Unknown option '{}'\n\n{}", arg, USAGE);
+                exit(2);
+            }
+            _ => files.push(arg),
+        }
+    }
+
+    let mut validator = AssetValidator::new(asset_folder);
+    if files.is_empty() {
+        validator = validator.with_all_asset_collection_files();
+    }
+    for file in files.iter() {
+        validator = validator.with_asset_collection_file(file);
+    }
+    let report = validator.validate();
+    println!("{}", report);
+    if !report.is_ok() || (deny_unused && !report.unused_files.is_empty()) {
+        exit(1);
+    }
+}
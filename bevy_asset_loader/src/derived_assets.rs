@@ -0,0 +1,226 @@
+use bevy::app::App;
+use bevy::asset::{Asset, AssetEvent, Assets, Handle};
+use bevy::ecs::event::{Events, ManualEventReader};
+use bevy::ecs::prelude::{IntoExclusiveSystem, World};
+use bevy::ecs::world::WorldCell;
+use bevy::log::error;
+use bevy::pbr::StandardMaterial;
+use bevy::render::texture::Image;
+use bevy::sprite::TextureAtlas;
+
+use crate::{NamedTextureAtlas, TextureAtlasGrid};
+
+/// Assets that asset collections derived from images
+///
+/// Texture atlases built from a grid and standard materials created from an image are tracked
+/// together with their image. When Bevy hot reloads the image, the atlas is rebuilt and the
+/// material updated in place. Their handles stay the same, so sprites and meshes using them
+/// show the new image. Texture atlases stitched from a folder are stitched again whenever one of
+/// their images is modified. Every image keeps its index in the atlas.
+///
+/// Derived assets are not tracked anymore once they are removed.
+///
+/// Asset collections track their derived assets automatically. Custom dynamic asset types can
+/// use [`DerivedAssets::track`].
+#[derive(Default)]
+pub struct DerivedAssets {
+    derived: Vec<DerivedAsset>,
+}
+
+/// An asset derived from an image
+pub enum DerivedAsset {
+    /// A texture atlas with the tiles of a grid on its texture
+    TextureAtlasGrid {
+        /// Handle to the texture atlas
+        atlas: Handle<TextureAtlas>,
+        /// The grid the atlas is rebuilt with
+        grid: TextureAtlasGrid,
+    },
+    /// A standard material using an image
+    StandardMaterial {
+        /// Handle to the material
+        material: Handle<StandardMaterial>,
+        /// Handle to the image used by the material
+        image: Handle<Image>,
+    },
+    /// A texture atlas stitched from images
+    ///
+    /// The atlas is stitched again from the images in its `texture_handles`.
+    StitchedTextureAtlas {
+        /// Handle to the texture atlas
+        atlas: Handle<TextureAtlas>,
+    },
+}
+
+impl DerivedAssets {
+    /// Update the derived asset whenever its image is modified
+    ///
+    /// Only weak handles are kept, so tracking an asset does not keep it alive.
+    /// If the [`DerivedAssets`] resource does not exist, the asset is not tracked.
+    pub fn track(cell: &WorldCell, derived: DerivedAsset) {
+        let mut derived_assets = match cell.get_resource_mut::<DerivedAssets>() {
+            Some(derived_assets) => derived_assets,
+            None => return,
+        };
+        derived_assets.derived.push(match derived {
+            DerivedAsset::TextureAtlasGrid { atlas, grid } => DerivedAsset::TextureAtlasGrid {
+                atlas: atlas.clone_weak(),
+                grid,
+            },
+            DerivedAsset::StandardMaterial { material, image } => DerivedAsset::StandardMaterial {
+                material: material.clone_weak(),
+                image: image.clone_weak(),
+            },
+            DerivedAsset::StitchedTextureAtlas { atlas } => DerivedAsset::StitchedTextureAtlas {
+                atlas: atlas.clone_weak(),
+            },
+        });
+    }
+
+    /// The number of tracked derived assets
+    pub fn len(&self) -> usize {
+        self.derived.len()
+    }
+
+    /// Returns `true` if no derived assets are tracked
+    pub fn is_empty(&self) -> bool {
+        self.derived.is_empty()
+    }
+
+    fn remove(&mut self, atlases: &[Handle<TextureAtlas>], materials: &[Handle<StandardMaterial>]) {
+        self.derived.retain(|derived| match derived {
+            DerivedAsset::TextureAtlasGrid { atlas, .. }
+            | DerivedAsset::StitchedTextureAtlas { atlas } => !atlases.contains(atlas),
+            DerivedAsset::StandardMaterial { material, .. } => !materials.contains(material),
+        });
+    }
+
+    fn update(&mut self, cell: &WorldCell, modified: &[Handle<Image>]) {
+        let mut images = cell
+            .get_resource_mut::<Assets<Image>>()
+            .expect("Cannot get resource Assets<Image>");
+        let mut atlases = cell.get_resource_mut::<Assets<TextureAtlas>>();
+        let mut materials = cell.get_resource_mut::<Assets<StandardMaterial>>();
+        // derived assets that were removed are not tracked anymore
+        self.derived.retain(|derived| match derived {
+            DerivedAsset::TextureAtlasGrid { atlas, grid } => {
+                let atlases = match atlases.as_mut() {
+                    Some(atlases) => atlases,
+                    None => return false,
+                };
+                let texture = match atlases.get(atlas) {
+                    Some(texture_atlas) => texture_atlas.texture.clone(),
+                    None => return false,
+                };
+                if modified.contains(&texture) {
+                    let rebuilt = grid.build(texture, &images);
+                    if let Some(texture_atlas) = atlases.get_mut(atlas) {
+                        *texture_atlas = rebuilt;
+                    }
+                }
+                true
+            }
+            DerivedAsset::StitchedTextureAtlas { atlas } => {
+                let atlases = match atlases.as_mut() {
+                    Some(atlases) => atlases,
+                    None => return false,
+                };
+                let texture_atlas = match atlases.get(atlas) {
+                    Some(texture_atlas) => texture_atlas,
+                    None => return false,
+                };
+                let stitched_from_modified = texture_atlas
+                    .texture_handles
+                    .iter()
+                    .flat_map(|handles| handles.keys())
+                    .any(|handle| modified.contains(handle));
+                if stitched_from_modified {
+                    match NamedTextureAtlas::restitch(texture_atlas, &mut images) {
+                        Ok(rebuilt) => {
+                            if let Some(texture_atlas) = atlases.get_mut(atlas) {
+                                *texture_atlas = rebuilt;
+                            }
+                        }
+                        Err(error) => error!("Failed to stitch a texture atlas again: {}", error),
+                    }
+                }
+                true
+            }
+            DerivedAsset::StandardMaterial { material, image } => {
+                let materials = match materials.as_mut() {
+                    Some(materials) => materials,
+                    None => return false,
+                };
+                if !materials.contains(material) {
+                    return false;
+                }
+                if modified.contains(image) {
+                    // mutable access marks the material as modified, so it is prepared with the new image
+                    materials.get_mut(material);
+                }
+                true
+            }
+        });
+    }
+}
+
+/// Marks apps that update their [`DerivedAssets`]
+#[derive(Default)]
+struct DerivedAssetsSystem;
+
+pub(crate) fn init_derived_assets(app: &mut App) {
+    if app.world.contains_resource::<DerivedAssetsSystem>() {
+        return;
+    }
+    let mut image_events = ManualEventReader::<AssetEvent<Image>>::default();
+    let mut atlas_events = ManualEventReader::<AssetEvent<TextureAtlas>>::default();
+    let mut material_events = ManualEventReader::<AssetEvent<StandardMaterial>>::default();
+    app.init_resource::<DerivedAssetsSystem>()
+        .init_resource::<DerivedAssets>()
+        .add_system(
+            (move |world: &mut World| {
+                let removed_atlases = removed(world, &mut atlas_events);
+                let removed_materials = removed(world, &mut material_events);
+                let modified: Vec<Handle<Image>> =
+                    match world.get_resource::<Events<AssetEvent<Image>>>() {
+                        Some(events) => image_events
+                            .iter(events)
+                            .filter_map(|event| match event {
+                                AssetEvent::Modified { handle } => Some(handle.clone_weak()),
+                                _ => None,
+                            })
+                            .collect(),
+                        None => Vec::new(),
+                    };
+                if modified.is_empty() && removed_atlases.is_empty() && removed_materials.is_empty()
+                {
+                    return;
+                }
+                let cell = world.cell();
+                let mut derived_assets = cell
+                    .get_resource_mut::<DerivedAssets>()
+                    .expect("Cannot get resource DerivedAssets");
+                derived_assets.remove(&removed_atlases, &removed_materials);
+                if !modified.is_empty() {
+                    derived_assets.update(&cell, &modified);
+                }
+            })
+            .exclusive_system(),
+        );
+}
+
+fn removed<T: Asset>(
+    world: &World,
+    reader: &mut ManualEventReader<AssetEvent<T>>,
+) -> Vec<Handle<T>> {
+    match world.get_resource::<Events<AssetEvent<T>>>() {
+        Some(events) => reader
+            .iter(events)
+            .filter_map(|event| match event {
+                AssetEvent::Removed { handle } => Some(handle.clone_weak()),
+                _ => None,
+            })
+            .collect(),
+        None => Vec::new(),
+    }
+}
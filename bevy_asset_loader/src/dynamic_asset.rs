@@ -1,17 +1,74 @@
 #[cfg(feature = "dynamic_assets")]
-use bevy::asset::AssetServer;
+use std::any::TypeId;
+#[cfg(feature = "dynamic_assets")]
+use std::collections::hash_map::DefaultHasher;
+#[cfg(feature = "dynamic_assets")]
+use std::collections::BTreeSet;
+#[cfg(feature = "dynamic_assets")]
+use std::fmt;
+#[cfg(feature = "dynamic_assets")]
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+#[cfg(feature = "dynamic_assets")]
+use std::sync::{Arc, RwLock};
+
+#[cfg(feature = "dynamic_assets")]
+use bevy::app::App;
+#[cfg(any(feature = "dynamic_assets", feature = "render"))]
+use bevy::asset::Assets;
+#[cfg(feature = "dynamic_assets")]
+use bevy::asset::{AddAsset, AssetEvent, AssetLoader, LoadContext, LoadState, LoadedAsset};
+use bevy::asset::{Asset, AssetPath, AssetServer, Handle, HandleId, HandleUntyped};
+#[cfg(feature = "dynamic_assets")]
+use bevy::ecs::event::{Events, ManualEventReader};
 #[cfg(feature = "dynamic_assets")]
 use bevy::ecs::prelude::World;
 #[cfg(feature = "dynamic_assets")]
 use bevy::ecs::schedule::{State, StateData};
+use bevy::ecs::world::WorldCell;
+#[cfg(feature = "dynamic_assets")]
+use bevy::log::error;
+#[cfg(feature = "dynamic_assets")]
+use bevy::utils::{BoxedFuture, HashMap, HashSet};
+#[cfg(any(feature = "cli", feature = "asset_pack"))]
+use serde::de::value::StrDeserializer;
+#[cfg(feature = "dynamic_assets")]
+use serde::de::value::{MapAccessDeserializer, StringDeserializer};
+#[cfg(any(feature = "cli", feature = "asset_pack"))]
+use serde::de::IgnoredAny;
+#[cfg(feature = "dynamic_assets")]
+use serde::de::{
+    DeserializeOwned, DeserializeSeed, EnumAccess, Error, IntoDeserializer, MapAccess, Unexpected,
+    VariantAccess, Visitor,
+};
 #[cfg(feature = "dynamic_assets")]
-use bevy::utils::HashMap;
+use serde::{forward_to_deserialize_any, Deserialize, Deserializer};
 
 #[cfg(feature = "dynamic_assets")]
 use bevy::reflect::TypeUuid;
 
+#[cfg(feature = "render")]
+use crate::derived_assets::{DerivedAsset, DerivedAssets};
 #[cfg(feature = "dynamic_assets")]
-use crate::{AssetKeys, AssetLoaderConfiguration, LoadingStatePhase};
+use crate::systems::LoadingProgress;
+#[cfg(feature = "render")]
+use crate::texture_atlas::{
+    AnimationClips, NamedTextureAtlas, PackedTextureAtlasLayout, TextureAtlasGrid,
+};
+use crate::{AssetCollection, AssetVariables};
+#[cfg(feature = "dynamic_assets")]
+use crate::{
+    AssetKeyProvenance, AssetKeys, AssetLoaderConfiguration, CollectionBuilder, CurrentLocale,
+    KeyedAsset, LoadingStatePhase,
+};
+#[cfg(feature = "render")]
+use bevy::math::Vec2;
+#[cfg(feature = "render")]
+use bevy::pbr::StandardMaterial;
+#[cfg(feature = "render")]
+use bevy::render::texture::Image;
+#[cfg(feature = "render")]
+use bevy::sprite::TextureAtlas;
 
 /// These asset variants can be loaded from configuration files. They will then replace
 /// a dynamic asset based on their keys.
@@ -23,6 +80,15 @@ pub enum DynamicAsset {
         /// Asset file path
         path: String,
     },
+    /// A dynamic asset that is a labeled sub asset of a file (`path#label`)
+    ///
+    /// Only the file at `path` is loaded. The created asset is the labeled sub asset.
+    LabeledFile {
+        /// Asset file path
+        path: String,
+        /// Label of the sub asset in the file
+        label: String,
+    },
     /// A dynamic standard material asset directly loaded from an image file
     #[cfg(feature = "render")]
     StandardMaterial {
@@ -46,6 +112,29 @@ pub enum DynamicAsset {
         padding_x: Option<f32>,
         /// Padding between rows in pixels
         padding_y: Option<f32>,
+        /// Horizontal position of the first column in pixels
+        offset_x: Option<f32>,
+        /// Vertical position of the first row in pixels
+        offset_y: Option<f32>,
+        /// Number of tiles on a partially filled sprite sheet
+        count: Option<usize>,
+        /// Named animation clips
+        #[cfg_attr(feature = "dynamic_assets", serde(default))]
+        animations: AnimationClips,
+    },
+    /// A dynamic texture atlas asset loaded from a sprite sheet with a packed layout
+    ///
+    /// The layout is a TexturePacker or Aseprite JSON file and requires the
+    /// [`PackedTextureAtlasPlugin`](crate::PackedTextureAtlasPlugin).
+    #[cfg(feature = "render")]
+    PackedTextureAtlas {
+        /// Asset file path
+        path: String,
+        /// Path to the layout file
+        layout: String,
+        /// Named animation clips
+        #[cfg_attr(feature = "dynamic_assets", serde(default))]
+        animations: AnimationClips,
     },
 }
 
@@ -54,14 +143,441 @@ impl DynamicAsset {
     pub fn get_file_path(&self) -> &str {
         match self {
             DynamicAsset::File { path } => path,
+            DynamicAsset::LabeledFile { path, .. } => path,
             #[cfg(feature = "render")]
             DynamicAsset::StandardMaterial { path } => path,
             #[cfg(feature = "render")]
             DynamicAsset::TextureAtlas { path, .. } => path,
+            #[cfg(feature = "render")]
+            DynamicAsset::PackedTextureAtlas { path, .. } => path,
+        }
+    }
+
+    /// Paths to all files that need to be loaded before the dynamic asset can be created
+    pub fn get_file_paths(&self) -> Vec<&str> {
+        match self {
+            #[cfg(feature = "render")]
+            DynamicAsset::PackedTextureAtlas { path, layout, .. } => vec![path, layout],
+            _ => vec![self.get_file_path()],
+        }
+    }
+}
+
+/// Write an asset collection file with a key for every field of the collection that has a path
+///
+/// The keys are the field names. This gives a template of everything a collection file can
+/// override and helps moving a collection from `path` attributes to `key` attributes.
+/// Fields that already use keys are not part of the file. Texture atlases stitched from a folder
+/// are written as their folder and glTF sub assets as their glTF file, since they are selected
+/// by name instead of a label.
+/// ```edition2021
+/// # use bevy::prelude::*;
+/// # use bevy_asset_loader::{export_asset_collection_file, AssetCollection};
+/// #[derive(AssetCollection)]
+/// struct AudioAssets {
+///     #[asset(path = "audio/background.ogg")]
+///     background: Handle<AudioSource>,
+/// }
+///
+/// # fn main() {
+/// assert_eq!(
+///     export_asset_collection_file::<AudioAssets>(),
+///     r#"({
+///     "background": File (
+///         path: "audio/background.ogg",
+///     ),
+/// })
+/// "#
+/// );
+/// # }
+/// ```
+pub fn export_asset_collection_file<A: AssetCollection>() -> String {
+    let mut file = String::from("({\n");
+    for (key, asset) in A::dynamic_assets() {
+        asset.write_ron(&key, &mut file);
+    }
+    file.push_str("})\n");
+
+    file
+}
+
+impl DynamicAsset {
+    /// Fails if an animation clip of a texture atlas grid ends after its last tile
+    ///
+    /// The textures of packed atlases are only known once their layout is loaded.
+    #[cfg(feature = "dynamic_assets")]
+    fn check_animations(&self) -> Result<(), String> {
+        #[cfg(feature = "render")]
+        if let DynamicAsset::TextureAtlas {
+            columns,
+            rows,
+            count,
+            animations,
+            ..
+        } = self
+        {
+            let len = count.unwrap_or(usize::MAX).min(columns * rows);
+            if let Some((name, clip)) = animations.iter().find(|(_, clip)| clip.end > len) {
+                return Err(format!(
+                    "The animation '{}' with frames {:?} does not fit a texture atlas with {} textures",
+                    name,
+                    clip.frames(),
+                    len
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn write_ron(&self, key: &str, file: &mut String) {
+        let mut fields = vec![];
+        let tag = match self {
+            DynamicAsset::File { path } => {
+                fields.push(("path", format!("{:?}", path)));
+                "File"
+            }
+            DynamicAsset::LabeledFile { path, label } => {
+                fields.push(("path", format!("{:?}", path)));
+                fields.push(("label", format!("{:?}", label)));
+                "LabeledFile"
+            }
+            #[cfg(feature = "render")]
+            DynamicAsset::StandardMaterial { path } => {
+                fields.push(("path", format!("{:?}", path)));
+                "StandardMaterial"
+            }
+            #[cfg(feature = "render")]
+            DynamicAsset::TextureAtlas {
+                path,
+                tile_size_x,
+                tile_size_y,
+                columns,
+                rows,
+                padding_x,
+                padding_y,
+                offset_x,
+                offset_y,
+                count,
+                animations,
+            } => {
+                fields.push(("path", format!("{:?}", path)));
+                fields.push(("tile_size_x", format!("{:?}", tile_size_x)));
+                fields.push(("tile_size_y", format!("{:?}", tile_size_y)));
+                fields.push(("columns", columns.to_string()));
+                fields.push(("rows", rows.to_string()));
+                let optional_fields = [
+                    ("padding_x", padding_x),
+                    ("padding_y", padding_y),
+                    ("offset_x", offset_x),
+                    ("offset_y", offset_y),
+                ];
+                for (name, value) in optional_fields {
+                    if let Some(value) = value {
+                        fields.push((name, format!("{:?}", value)));
+                    }
+                }
+                if let Some(count) = count {
+                    fields.push(("count", count.to_string()));
+                }
+                if !animations.is_empty() {
+                    fields.push(("animations", animations_ron(animations)));
+                }
+                "TextureAtlas"
+            }
+            #[cfg(feature = "render")]
+            DynamicAsset::PackedTextureAtlas {
+                path,
+                layout,
+                animations,
+            } => {
+                fields.push(("path", format!("{:?}", path)));
+                fields.push(("layout", format!("{:?}", layout)));
+                if !animations.is_empty() {
+                    fields.push(("animations", animations_ron(animations)));
+                }
+                "PackedTextureAtlas"
+            }
+        };
+        file.push_str(&format!("    {:?}: {} (\n", key, tag));
+        for (name, value) in fields {
+            file.push_str(&format!("        {}: {},\n", name, value));
         }
+        file.push_str("    ),\n");
     }
 }
 
+#[cfg(feature = "render")]
+fn animations_ron(animations: &AnimationClips) -> String {
+    let mut clips: Vec<_> = animations.iter().collect();
+    clips.sort_by_key(|(name, _)| *name);
+    let mut ron = String::from("{\n");
+    for (name, clip) in clips {
+        ron.push_str(&format!(
+            "            {:?}: (start: {}, end: {}, fps: {:?}),\n",
+            name, clip.start, clip.end, clip.fps
+        ));
+    }
+    ron.push_str("        }");
+
+    ron
+}
+
+/// Types that can be used as dynamic assets
+///
+/// [`DynamicAsset`] implements this trait for all built-in asset types. Implement it for your own
+/// types to register them with [`AssetKeys`](crate::AssetKeys) or, with the `dynamic_assets` feature,
+/// to use them in asset collection files (see [`DynamicAssetApp::register_dynamic_asset`]).
+pub trait DynamicAssetType: Send + Sync + 'static {
+    /// Start loading all files that are required to create the dynamic asset
+    ///
+    /// Paths can contain [asset variables](crate::AssetVariables). Resolve them with
+    /// [`AssetVariables::resolve_path`](crate::AssetVariables::resolve_path).
+    fn load(&self, cell: &WorldCell) -> Vec<HandleUntyped>;
+
+    /// Create the dynamic asset
+    ///
+    /// All handles returned by [`load`](DynamicAssetType::load) are loaded at this point.
+    fn create(&self, cell: &WorldCell) -> DynamicAssetValue;
+
+    /// Path to the folder if the dynamic asset can be loaded as a folder
+    ///
+    /// Asset variables in the path are resolved by the caller.
+    fn folder_path(&self) -> Option<&str> {
+        None
+    }
+
+    /// Tag of the type in asset collection files
+    ///
+    /// The tag needs to be unique among all registered types, e.g. `"Tilemap"` for
+    /// `Tilemap ( tileset: "tiles.png" )`.
+    fn tag() -> &'static str
+    where
+        Self: Sized;
+}
+
+impl DynamicAssetType for DynamicAsset {
+    /// Built-in dynamic assets are tagged with the name of their variant instead
+    fn tag() -> &'static str {
+        "DynamicAsset"
+    }
+
+    fn load(&self, cell: &WorldCell) -> Vec<HandleUntyped> {
+        let asset_server = cell
+            .get_resource::<AssetServer>()
+            .expect("Cannot get AssetServer");
+        self.get_file_paths()
+            .drain(..)
+            .map(|path| {
+                asset_server.load_untyped(AssetVariables::resolve_path(cell, path).as_str())
+            })
+            .collect()
+    }
+
+    fn create(&self, cell: &WorldCell) -> DynamicAssetValue {
+        let asset_server = cell
+            .get_resource::<AssetServer>()
+            .expect("Cannot get AssetServer");
+        let path = AssetVariables::resolve_path(cell, self.get_file_path());
+        match self {
+            DynamicAsset::File { .. } => {
+                DynamicAssetValue::Handle(asset_server.get_handle_untyped(path.as_str()))
+            }
+            DynamicAsset::LabeledFile { label, .. } => DynamicAssetValue::Handle(
+                asset_server.get_handle_untyped(AssetPath::new_ref(Path::new(&path), Some(label))),
+            ),
+            #[cfg(feature = "render")]
+            DynamicAsset::StandardMaterial { .. } => {
+                let mut materials = cell
+                    .get_resource_mut::<Assets<StandardMaterial>>()
+                    .expect("Cannot get resource Assets<StandardMaterial>");
+                let image = asset_server.get_handle::<Image, _>(path.as_str());
+                let handle = materials.add(image.clone().into());
+                DerivedAssets::track(
+                    cell,
+                    DerivedAsset::StandardMaterial {
+                        material: handle.clone(),
+                        image,
+                    },
+                );
+                DynamicAssetValue::Handle(handle.clone_untyped())
+            }
+            #[cfg(feature = "render")]
+            DynamicAsset::TextureAtlas {
+                tile_size_x,
+                tile_size_y,
+                columns,
+                rows,
+                padding_x,
+                padding_y,
+                offset_x,
+                offset_y,
+                count,
+                animations,
+                ..
+            } => {
+                let images = cell
+                    .get_resource::<Assets<Image>>()
+                    .expect("Cannot get resource Assets<Image>");
+                let mut atlases = cell
+                    .get_resource_mut::<Assets<TextureAtlas>>()
+                    .expect("Cannot get resource Assets<TextureAtlas>");
+                let grid = TextureAtlasGrid {
+                    tile_size: Vec2::new(*tile_size_x, *tile_size_y),
+                    columns: *columns,
+                    rows: *rows,
+                    padding: Vec2::new(padding_x.unwrap_or(0.), padding_y.unwrap_or(0.)),
+                    offset: Vec2::new(offset_x.unwrap_or(0.), offset_y.unwrap_or(0.)),
+                    count: *count,
+                };
+                let atlas =
+                    atlases.add(grid.build(asset_server.get_handle(path.as_str()), &images));
+                DerivedAssets::track(
+                    cell,
+                    DerivedAsset::TextureAtlasGrid {
+                        atlas: atlas.clone(),
+                        grid,
+                    },
+                );
+                DynamicAssetValue::TextureAtlas(
+                    NamedTextureAtlas {
+                        atlas,
+                        ..Default::default()
+                    }
+                    .with_animations(animations.clone(), &atlases)
+                    .unwrap_or_else(|error| {
+                        panic!(
+                            "Failed to add the animations of the texture atlas '{}': {}",
+                            path, error
+                        )
+                    }),
+                )
+            }
+            #[cfg(feature = "render")]
+            DynamicAsset::PackedTextureAtlas {
+                layout, animations, ..
+            } => {
+                let layout = AssetVariables::resolve_path(cell, layout);
+                let images = cell
+                    .get_resource::<Assets<Image>>()
+                    .expect("Cannot get resource Assets<Image>");
+                let mut atlases = cell
+                    .get_resource_mut::<Assets<TextureAtlas>>()
+                    .expect("Cannot get resource Assets<TextureAtlas>");
+                let layouts = cell
+                    .get_resource::<Assets<PackedTextureAtlasLayout>>()
+                    .expect("Cannot get resource Assets<PackedTextureAtlasLayout>. Did you add the PackedTextureAtlasPlugin?");
+                let packed_layout = layouts
+                    .get(layout.as_str())
+                    .unwrap_or_else(|| panic!("Texture atlas layout '{}' is not loaded", layout));
+                DynamicAssetValue::TextureAtlas(
+                    NamedTextureAtlas::from_layout(
+                        asset_server.get_handle(path.as_str()),
+                        packed_layout,
+                        &images,
+                        &mut atlases,
+                    )
+                    .unwrap_or_else(|error| {
+                        panic!(
+                            "Failed to create a texture atlas from the layout '{}': {}",
+                            layout, error
+                        )
+                    })
+                    .with_animations(animations.clone(), &atlases)
+                    .unwrap_or_else(|error| {
+                        panic!(
+                            "The animations do not fit the texture atlas layout '{}': {}",
+                            layout, error
+                        )
+                    }),
+                )
+            }
+        }
+    }
+
+    fn folder_path(&self) -> Option<&str> {
+        match self {
+            DynamicAsset::File { path } => Some(path),
+            _ => None,
+        }
+    }
+}
+
+/// A created [dynamic asset](DynamicAssetType)
+///
+/// The value can be converted into the type of a collection field
+/// with [`FromDynamicAssetValue`].
+pub enum DynamicAssetValue {
+    /// Handle to a single asset
+    Handle(HandleUntyped),
+    /// A texture atlas with named textures
+    #[cfg(feature = "render")]
+    TextureAtlas(NamedTextureAtlas),
+}
+
+/// Types that a [`DynamicAssetValue`] can be converted into
+///
+/// Fields of asset collections with a `key` attribute need to implement this trait.
+pub trait FromDynamicAssetValue: Sized {
+    /// Convert the value or return `None` if the value has a different type
+    fn from_dynamic_asset_value(value: DynamicAssetValue) -> Option<Self>;
+}
+
+/// Texture atlases with animation clips only fit a [`NamedTextureAtlas`], since a handle would lose the clips
+impl<T: Asset> FromDynamicAssetValue for Handle<T> {
+    #[cfg_attr(not(feature = "render"), allow(clippy::infallible_destructuring_match))]
+    fn from_dynamic_asset_value(value: DynamicAssetValue) -> Option<Self> {
+        let handle = match value {
+            DynamicAssetValue::Handle(handle) => handle,
+            #[cfg(feature = "render")]
+            DynamicAssetValue::TextureAtlas(named_atlas) => {
+                if !named_atlas.animations.is_empty() {
+                    return None;
+                }
+                named_atlas.atlas.clone_untyped()
+            }
+        };
+        if let HandleId::Id(type_uuid, _) = handle.id {
+            if type_uuid != T::TYPE_UUID {
+                return None;
+            }
+        }
+        Some(handle.typed())
+    }
+}
+
+#[cfg(feature = "render")]
+impl FromDynamicAssetValue for NamedTextureAtlas {
+    fn from_dynamic_asset_value(value: DynamicAssetValue) -> Option<Self> {
+        match value {
+            DynamicAssetValue::TextureAtlas(named_atlas) => Some(named_atlas),
+            _ => None,
+        }
+    }
+}
+
+/// An asset collection file of a loading state
+#[cfg(feature = "dynamic_assets")]
+#[derive(Clone)]
+pub(crate) struct AssetCollectionFile {
+    pub(crate) path: String,
+    /// Keys of global files stay available after the loading state
+    pub(crate) global: bool,
+    /// Keys of files with a higher priority replace keys of files with a lower one
+    pub(crate) priority: i32,
+}
+
+/// Identifies the loading state that scopes the keys of its asset collection files
+#[cfg(feature = "dynamic_assets")]
+pub(crate) type AssetKeyScope = u64;
+
+#[cfg(feature = "dynamic_assets")]
+pub(crate) fn asset_key_scope<S: StateData>(state: &S) -> AssetKeyScope {
+    let mut hasher = DefaultHasher::new();
+    TypeId::of::<S>().hash(&mut hasher);
+    state.hash(&mut hasher);
+    hasher.finish()
+}
+
 #[cfg(feature = "dynamic_assets")]
 pub(crate) fn prepare_asset_keys<S: StateData>(world: &mut World) {
     let cell = world.cell();
@@ -72,6 +588,10 @@ pub(crate) fn prepare_asset_keys<S: StateData>(world: &mut World) {
     let state = cell
         .get_resource::<State<S>>()
         .expect("Cannot get State resource");
+    let mut asset_keys = cell
+        .get_resource_mut::<AssetKeys>()
+        .expect("Cannot get AssetKeys resource");
+    asset_keys.enter_scope(asset_key_scope(state.current()));
 
     let files = asset_loader_config.get_asset_collection_files(state.current());
     if files.is_empty() {
@@ -80,27 +600,1283 @@ pub(crate) fn prepare_asset_keys<S: StateData>(world: &mut World) {
             .insert(state.current().clone(), LoadingStatePhase::StartLoading);
         return;
     }
-    for file in files {
-        asset_loader_config
-            .asset_collection_handles
-            .push(asset_server.load(&file));
+    for file in files.iter() {
+        asset_loader_config.asset_collection_handles.insert(
+            file.path.clone(),
+            LoadingAssetCollection {
+                handle: asset_server.load(file.path.as_str()),
+            },
+        );
     }
+    asset_loader_config.loading_asset_collection_files = files;
     asset_loader_config.phase.insert(
         state.current().clone(),
         LoadingStatePhase::PreparingAssetKeys,
     );
 }
 
-#[derive(serde::Deserialize, TypeUuid)]
+/// A loading asset collection file
+#[cfg(feature = "dynamic_assets")]
+pub(crate) struct LoadingAssetCollection {
+    pub(crate) handle: Handle<DynamicAssetCollection>,
+}
+
+/// Wait for all asset collection files and their includes, then resolve their keys
+///
+/// Once the keys of all files are added to the [`AssetKeys`], the loading state continues
+/// with loading the asset collections.
+#[cfg(feature = "dynamic_assets")]
+pub(crate) fn resolve_asset_keys<S: StateData>(world: &mut World) {
+    let cell = world.cell();
+    let mut asset_loader_configuration = cell
+        .get_resource_mut::<AssetLoaderConfiguration<S>>()
+        .expect("Cannot get AssetLoaderConfiguration");
+    let asset_loader_configuration = &mut *asset_loader_configuration;
+    let scoped_keys = match resolve_loaded_asset_collection_files(
+        &cell,
+        asset_loader_configuration
+            .loading_asset_collection_files
+            .clone(),
+        &mut asset_loader_configuration.asset_collection_handles,
+    ) {
+        Some(scoped_keys) => scoped_keys,
+        None => return,
+    };
+    asset_loader_configuration
+        .loading_asset_collection_files
+        .clear();
+
+    let state = cell.get_resource::<State<S>>().expect("Cannot get state");
+    cell.get_resource_mut::<AssetKeys>()
+        .expect("Cannot get AssetKeys resource")
+        .scoped_key_asset_maps
+        .entry(asset_key_scope(state.current()))
+        .or_default()
+        .extend(scoped_keys);
+
+    let mut dynamic_asset_collections = cell
+        .get_resource_mut::<Assets<DynamicAssetCollection>>()
+        .expect("Cannot get resource Assets<DynamicAssetCollection>");
+    let handles: HashMap<String, LoadingAssetCollection> = asset_loader_configuration
+        .asset_collection_handles
+        .drain()
+        .collect();
+    if asset_loader_configuration
+        .hot_reload_states
+        .contains(state.current())
+    {
+        // keep the files alive, so changes to them can be hot reloaded
+        asset_loader_configuration
+            .hot_reload_handles
+            .insert(state.current().clone(), handles);
+    } else {
+        for (_, collection) in handles {
+            dynamic_asset_collections.remove(collection.handle);
+        }
+    }
+    asset_loader_configuration
+        .phase
+        .insert(state.current().clone(), LoadingStatePhase::StartLoading);
+}
+
+/// Check the given asset collection files and start loading their includes
+///
+/// Returns `true` once all files and their includes are loaded. Files that failed to load are
+/// added to `failed` and keep the loading state from continuing.
+#[cfg(feature = "dynamic_assets")]
+fn load_asset_collection_files(
+    asset_server: &AssetServer,
+    handles: &mut HashMap<String, LoadingAssetCollection>,
+    collections: &Assets<DynamicAssetCollection>,
+    failed: &mut BTreeSet<String>,
+) -> bool {
+    let mut loaded = true;
+    for (path, collection) in handles.iter() {
+        match asset_server.get_load_state(&collection.handle) {
+            LoadState::Loaded => (),
+            LoadState::Failed => {
+                failed.insert(path.clone());
+                loaded = false;
+            }
+            _ => loaded = false,
+        }
+    }
+    if !loaded {
+        return false;
+    }
+
+    let mut includes = vec![];
+    for collection in handles.values() {
+        let collection = collections.get(&collection.handle).unwrap();
+        for include in collection.includes.iter() {
+            if !handles.contains_key(include) {
+                includes.push(include.clone());
+            }
+        }
+    }
+    if includes.is_empty() {
+        if let Some(cycle) = find_include_cycle(handles, collections) {
+            let mut newly_failed = false;
+            for path in cycle.iter() {
+                newly_failed |= failed.insert(path.clone());
+            }
+            if newly_failed {
+                error!(
+                    "Asset collection files include each other in a cycle: {}",
+                    cycle.join(" -> ")
+                );
+            }
+            return false;
+        }
+        return true;
+    }
+    for include in includes {
+        let handle = asset_server.load(include.as_str());
+        handles.insert(include, LoadingAssetCollection { handle });
+    }
+    false
+}
+
+/// Find asset collection files that include each other
+///
+/// Returns the chain of includes from the first file of the cycle back to itself.
+#[cfg(feature = "dynamic_assets")]
+fn find_include_cycle(
+    handles: &HashMap<String, LoadingAssetCollection>,
+    collections: &Assets<DynamicAssetCollection>,
+) -> Option<Vec<String>> {
+    fn visit(
+        path: &str,
+        handles: &HashMap<String, LoadingAssetCollection>,
+        collections: &Assets<DynamicAssetCollection>,
+        include_stack: &mut Vec<String>,
+        done: &mut HashSet<String>,
+    ) -> Option<Vec<String>> {
+        if let Some(start) = include_stack.iter().position(|included| included == path) {
+            let mut cycle = include_stack[start..].to_vec();
+            cycle.push(path.to_owned());
+            return Some(cycle);
+        }
+        if done.contains(path) {
+            return None;
+        }
+        include_stack.push(path.to_owned());
+        let collection = collections.get(&handles.get(path)?.handle)?;
+        for include in collection.includes.iter() {
+            if let Some(cycle) = visit(include, handles, collections, include_stack, done) {
+                return Some(cycle);
+            }
+        }
+        include_stack.pop();
+        done.insert(path.to_owned());
+        None
+    }
+
+    let mut paths: Vec<&String> = handles.keys().collect();
+    paths.sort();
+    let mut done = HashSet::default();
+    paths
+        .into_iter()
+        .find_map(|path| visit(path, handles, collections, &mut vec![], &mut done))
+}
+
+/// Replace the global keys defined by the asset collection files at the given paths
+///
+/// Keys that are no longer defined by the files are removed.
+#[cfg(feature = "dynamic_assets")]
+fn replace_global_keys(
+    asset_keys: &mut AssetKeys,
+    paths: &[String],
+    keys: HashMap<String, KeyedAsset>,
+) {
+    asset_keys.key_asset_map.retain(|_, keyed_asset| {
+        !matches!(
+            &keyed_asset.provenance,
+            AssetKeyProvenance::Layer { path, .. } if paths.contains(path)
+        )
+    });
+    asset_keys.key_asset_map.extend(keys);
+}
+
+/// Keys of asset collection files
+#[cfg(feature = "dynamic_assets")]
+struct ResolvedAssetKeys {
+    global: HashMap<String, KeyedAsset>,
+    scoped: HashMap<String, KeyedAsset>,
+}
+
+/// Resolve the keys of the given files in the order of their priority
+#[cfg(feature = "dynamic_assets")]
+fn resolve_asset_collection_files(
+    mut files: Vec<AssetCollectionFile>,
+    handles: &HashMap<String, LoadingAssetCollection>,
+    collections: &Assets<DynamicAssetCollection>,
+    locales: &[String],
+) -> ResolvedAssetKeys {
+    let mut resolved_keys = ResolvedAssetKeys {
+        global: HashMap::default(),
+        scoped: HashMap::default(),
+    };
+    // stable sort: files with the same priority keep the order they were added in
+    files.sort_by_key(|file| file.priority);
+    for file in files {
+        let assets = resolve_includes(&file.path, handles, collections, locales, &mut vec![]);
+        let key_asset_map = if file.global {
+            &mut resolved_keys.global
+        } else {
+            &mut resolved_keys.scoped
+        };
+        key_asset_map.extend(assets.into_iter().map(|(key, (asset, defined_in))| {
+            (
+                key,
+                KeyedAsset {
+                    asset,
+                    provenance: AssetKeyProvenance::Layer {
+                        path: file.path.clone(),
+                        file: defined_in,
+                        priority: file.priority,
+                    },
+                },
+            )
+        }));
+    }
+
+    resolved_keys
+}
+
+/// Resolve the keys of the given files once they and their includes are loaded
+///
+/// The global keys of the files are replaced right away, the keys of the loading state are returned.
+/// Returns `None` while files are still loading.
+#[cfg(feature = "dynamic_assets")]
+pub(crate) fn resolve_loaded_asset_collection_files(
+    cell: &WorldCell,
+    files: Vec<AssetCollectionFile>,
+    handles: &mut HashMap<String, LoadingAssetCollection>,
+) -> Option<HashMap<String, KeyedAsset>> {
+    let asset_server = cell
+        .get_resource::<AssetServer>()
+        .expect("Cannot get AssetServer resource");
+    let dynamic_asset_collections = cell
+        .get_resource::<Assets<DynamicAssetCollection>>()
+        .expect("Cannot get resource Assets<DynamicAssetCollection>");
+    let mut progress = cell
+        .get_resource_mut::<LoadingProgress>()
+        .expect("Cannot get LoadingProgress resource");
+    if !load_asset_collection_files(
+        &asset_server,
+        handles,
+        &dynamic_asset_collections,
+        &mut progress.failed,
+    ) {
+        return None;
+    }
+    let locales = cell
+        .get_resource::<CurrentLocale>()
+        .map(|locale| locale.fallback_chain())
+        .unwrap_or_default();
+    let paths: Vec<String> = files.iter().map(|file| file.path.clone()).collect();
+    let resolved_keys =
+        resolve_asset_collection_files(files, handles, &dynamic_asset_collections, &locales);
+    replace_global_keys(
+        &mut cell
+            .get_resource_mut::<AssetKeys>()
+            .expect("Cannot get AssetKeys resource"),
+        &paths,
+        resolved_keys.global,
+    );
+
+    Some(resolved_keys.scoped)
+}
+
+/// Progress of hot reloading the asset collection files of a loading state
+#[cfg(feature = "dynamic_assets")]
+enum HotReload {
+    Watching,
+    ResolvingAssetKeys,
+    LoadingCollections {
+        keys: HashMap<String, KeyedAsset>,
+        handles: Vec<HandleUntyped>,
+    },
+}
+
+/// Rebuild the collections of a loading state when one of its asset collection files changes
+///
+/// Changes during the loading state are picked up once the loading state has been left.
+#[cfg(feature = "dynamic_assets")]
+pub(crate) fn hot_reload_asset_collection_files<S: StateData>(
+    loading_state: S,
+    collections: Arc<Vec<CollectionBuilder>>,
+) -> impl FnMut(&mut World) {
+    let scope = asset_key_scope(&loading_state);
+    let mut events = ManualEventReader::<AssetEvent<DynamicAssetCollection>>::default();
+    let mut hot_reload = HotReload::Watching;
+    // files modified since the last check, including changes during the loading state
+    let mut modified: HashSet<HandleId> = HashSet::default();
+    move |world: &mut World| {
+        {
+            let asset_events = world
+                .get_resource::<Events<AssetEvent<DynamicAssetCollection>>>()
+                .expect("Cannot get resource Events<AssetEvent<DynamicAssetCollection>>");
+            modified.extend(events.iter(asset_events).filter_map(|event| match event {
+                AssetEvent::Modified { handle } => Some(handle.id),
+                _ => None,
+            }));
+        }
+        {
+            let state = world
+                .get_resource::<State<S>>()
+                .expect("Cannot get State resource");
+            if *state.current() == loading_state {
+                hot_reload = HotReload::Watching;
+                return;
+            }
+        }
+        if !modified.is_empty() {
+            let asset_loader_configuration = world
+                .get_resource::<AssetLoaderConfiguration<S>>()
+                .expect("Cannot get AssetLoaderConfiguration");
+            if let Some(handles) = asset_loader_configuration
+                .hot_reload_handles
+                .get(&loading_state)
+            {
+                if handles
+                    .values()
+                    .any(|collection| modified.contains(&collection.handle.id))
+                {
+                    hot_reload = HotReload::ResolvingAssetKeys;
+                }
+            }
+            modified.clear();
+        }
+
+        match &mut hot_reload {
+            HotReload::Watching => (),
+            HotReload::ResolvingAssetKeys => {
+                let keys = {
+                    let cell = world.cell();
+                    let mut asset_loader_configuration = cell
+                        .get_resource_mut::<AssetLoaderConfiguration<S>>()
+                        .expect("Cannot get AssetLoaderConfiguration");
+                    let files =
+                        asset_loader_configuration.get_asset_collection_files(&loading_state);
+                    let handles = asset_loader_configuration
+                        .hot_reload_handles
+                        .get_mut(&loading_state)
+                        .unwrap();
+                    match resolve_loaded_asset_collection_files(&cell, files, handles) {
+                        Some(keys) => keys,
+                        None => return,
+                    }
+                };
+                let handles = with_scoped_keys(world, scope, &keys, |world| {
+                    collections
+                        .iter()
+                        .flat_map(|collection| (collection.load)(world))
+                        .collect()
+                });
+                hot_reload = HotReload::LoadingCollections { keys, handles };
+            }
+            HotReload::LoadingCollections { keys, handles } => {
+                let load_state = world
+                    .get_resource::<AssetServer>()
+                    .expect("Cannot get AssetServer resource")
+                    .get_group_load_state(handles.iter().map(|handle| handle.id));
+                if load_state != LoadState::Loaded {
+                    return;
+                }
+                with_scoped_keys(world, scope, keys, |world| {
+                    for collection in collections.iter() {
+                        (collection.insert)(world);
+                    }
+                });
+                hot_reload = HotReload::Watching;
+            }
+        }
+    }
+}
+
+/// Make the given keys available as keys of the loading state while calling `f`
+#[cfg(feature = "dynamic_assets")]
+pub(crate) fn with_scoped_keys<T>(
+    world: &mut World,
+    scope: AssetKeyScope,
+    keys: &HashMap<String, KeyedAsset>,
+    f: impl FnOnce(&mut World) -> T,
+) -> T {
+    let previous_scope = {
+        let mut asset_keys = world
+            .get_resource_mut::<AssetKeys>()
+            .expect("Cannot get AssetKeys resource");
+        asset_keys.scoped_key_asset_maps.insert(scope, keys.clone());
+        asset_keys.current_scope.replace(scope)
+    };
+    let result = f(world);
+    let mut asset_keys = world
+        .get_resource_mut::<AssetKeys>()
+        .expect("Cannot get AssetKeys resource");
+    asset_keys.scoped_key_asset_maps.remove(&scope);
+    asset_keys.current_scope = previous_scope;
+    result
+}
+
+/// Collect the assets of a file and all its includes together with the file defining them
+///
+/// Includes are applied in order, so later includes override earlier ones.
+/// The assets of the file itself override all of its includes. Assets of the given locales
+/// override the assets of the file.
+#[cfg(feature = "dynamic_assets")]
+fn resolve_includes(
+    path: &str,
+    handles: &HashMap<String, LoadingAssetCollection>,
+    collections: &Assets<DynamicAssetCollection>,
+    locales: &[String],
+    include_stack: &mut Vec<String>,
+) -> HashMap<String, (Arc<dyn DynamicAssetType>, String)> {
+    // cycles are reported while loading the files
+    if include_stack.iter().any(|included| included == path) {
+        return HashMap::default();
+    }
+    include_stack.push(path.to_owned());
+    let collection = collections.get(&handles.get(path).unwrap().handle).unwrap();
+    let mut assets = HashMap::default();
+    for include in collection.includes.iter() {
+        assets.extend(resolve_includes(
+            include,
+            handles,
+            collections,
+            locales,
+            include_stack,
+        ));
+    }
+    assets.extend(
+        collection
+            .assets
+            .iter()
+            .map(|(key, asset)| (key.clone(), (asset.clone(), path.to_owned()))),
+    );
+    // the least specific locale first, so more specific locales override it
+    for locale in locales.iter().rev() {
+        if let Some(localized_assets) = collection.localized.get(locale) {
+            assets.extend(
+                localized_assets
+                    .iter()
+                    .map(|(key, asset)| (key.clone(), (asset.clone(), path.to_owned()))),
+            );
+        }
+    }
+    include_stack.pop();
+
+    assets
+}
+
+/// The content of an asset collection file
+///
+/// Besides the dynamic assets by key, a file can list other asset collection files in `#includes`
+/// and define assets per locale in `#localized` (see [`CurrentLocale`](crate::CurrentLocale)).
+#[derive(TypeUuid)]
 #[uuid = "2df82c01-9c71-4aa8-adc4-71c5824768f1"]
 #[cfg(feature = "dynamic_assets")]
-pub struct DynamicAssetCollection(HashMap<String, DynamicAsset>);
+pub struct DynamicAssetCollection {
+    includes: Vec<String>,
+    assets: HashMap<String, Arc<dyn DynamicAssetType>>,
+    localized: HashMap<String, HashMap<String, Arc<dyn DynamicAssetType>>>,
+}
+
+/// Extension trait for [`App`] enabling the registration of custom [dynamic asset types](DynamicAssetType)
+#[cfg(feature = "dynamic_assets")]
+pub trait DynamicAssetApp {
+    /// Register a [`DynamicAssetType`] to be deserialized from asset collection files
+    ///
+    /// In the files, the type is referred to by its [tag](DynamicAssetType::tag). Registered
+    /// types need to deserialize from a struct and take precedence over the built-in variants
+    /// of [`DynamicAsset`] with the same tag.
+    /// ```edition2021
+    /// # use bevy::prelude::*;
+    /// # use bevy::ecs::world::WorldCell;
+    /// # use bevy_asset_loader::{DynamicAssetApp, DynamicAssetType, DynamicAssetValue};
+    /// #[derive(serde::Deserialize)]
+    /// struct Tilemap {
+    ///     tileset: String,
+    /// }
+    ///
+    /// impl DynamicAssetType for Tilemap {
+    ///     fn load(&self, cell: &WorldCell) -> Vec<HandleUntyped> {
+    ///         let asset_server = cell.get_resource::<AssetServer>().unwrap();
+    ///         vec![asset_server.load_untyped(self.tileset.as_str())]
+    ///     }
+    ///
+    ///     fn create(&self, cell: &WorldCell) -> DynamicAssetValue {
+    ///         let asset_server = cell.get_resource::<AssetServer>().unwrap();
+    ///         DynamicAssetValue::Handle(asset_server.get_handle_untyped(self.tileset.as_str()))
+    ///     }
+    ///
+    ///     fn tag() -> &'static str {
+    ///         "Tilemap"
+    ///     }
+    /// }
+    ///
+    /// # fn main() {
+    /// App::new().register_dynamic_asset::<Tilemap>();
+    /// # }
+    /// ```
+    fn register_dynamic_asset<T: DynamicAssetType + DeserializeOwned>(&mut self) -> &mut Self;
+}
 
 #[cfg(feature = "dynamic_assets")]
-impl DynamicAssetCollection {
-    pub fn apply(self, keys: &mut AssetKeys) {
-        for (key, asset) in self.0 {
-            keys.key_asset_map.insert(key, asset);
+impl DynamicAssetApp for App {
+    fn register_dynamic_asset<T: DynamicAssetType + DeserializeOwned>(&mut self) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(DynamicAssetTypes::default)
+            .register::<T>();
+        self
+    }
+}
+
+#[cfg(feature = "dynamic_assets")]
+type DeserializeDynamicAsset = fn(
+    &mut dyn erased_serde::Deserializer,
+) -> Result<Box<dyn DynamicAssetType>, erased_serde::Error>;
+
+/// Custom dynamic asset types by their tag
+///
+/// The map is shared with the asset collection loaders, so types can be
+/// registered before or after the loader is added.
+#[cfg(feature = "dynamic_assets")]
+#[derive(Clone, Default)]
+pub(crate) struct DynamicAssetTypes(Arc<RwLock<HashMap<String, DeserializeDynamicAsset>>>);
+
+#[cfg(feature = "dynamic_assets")]
+impl DynamicAssetTypes {
+    fn register<T: DynamicAssetType + DeserializeOwned>(&self) {
+        self.0
+            .write()
+            .unwrap()
+            .insert(T::tag().to_owned(), |deserializer| {
+                Ok(Box::new(erased_serde::deserialize::<T>(deserializer)?))
+            });
+    }
+}
+
+/// File formats of asset collection files
+///
+/// All formats are deserialized into the same collection of dynamic assets.
+/// Formats other than RON require their own cargo feature.
+#[cfg(feature = "dynamic_assets")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DynamicAssetFormat {
+    /// RON files with the default file ending `assets`
+    Ron,
+    /// JSON files with the default file ending `assets.json` (feature `dynamic_assets_json`)
+    #[cfg(feature = "dynamic_assets_json")]
+    Json,
+    /// TOML files with the default file ending `assets.toml` (feature `dynamic_assets_toml`)
+    #[cfg(feature = "dynamic_assets_toml")]
+    Toml,
+    /// YAML files with the default file ending `assets.yaml` (feature `dynamic_assets_yaml`)
+    #[cfg(feature = "dynamic_assets_yaml")]
+    Yaml,
+}
+
+#[cfg(feature = "dynamic_assets")]
+impl DynamicAssetFormat {
+    /// All formats enabled through cargo features
+    pub fn enabled() -> Vec<DynamicAssetFormat> {
+        vec![
+            DynamicAssetFormat::Ron,
+            #[cfg(feature = "dynamic_assets_json")]
+            DynamicAssetFormat::Json,
+            #[cfg(feature = "dynamic_assets_toml")]
+            DynamicAssetFormat::Toml,
+            #[cfg(feature = "dynamic_assets_yaml")]
+            DynamicAssetFormat::Yaml,
+        ]
+    }
+
+    /// The file ending used for the format if no other ending is configured
+    pub fn default_file_ending(&self) -> &'static str {
+        match self {
+            DynamicAssetFormat::Ron => "assets",
+            #[cfg(feature = "dynamic_assets_json")]
+            DynamicAssetFormat::Json => "assets.json",
+            #[cfg(feature = "dynamic_assets_toml")]
+            DynamicAssetFormat::Toml => "assets.toml",
+            #[cfg(feature = "dynamic_assets_yaml")]
+            DynamicAssetFormat::Yaml => "assets.yaml",
         }
     }
 }
+
+/// File endings that already have a [`DynamicAssetCollectionLoader`]
+#[cfg(feature = "dynamic_assets")]
+#[derive(Default)]
+struct AssetCollectionFileEndings(HashSet<&'static str>);
+
+/// Add the asset and loaders for asset collection files with the given endings
+///
+/// Loaders are only added for endings that are not yet known.
+#[cfg(feature = "dynamic_assets")]
+pub(crate) fn add_asset_collection_loaders(
+    app: &mut App,
+    file_endings: &HashMap<DynamicAssetFormat, &'static str>,
+) {
+    if !app
+        .world
+        .contains_resource::<Assets<DynamicAssetCollection>>()
+    {
+        app.add_asset::<DynamicAssetCollection>();
+    }
+    let types = app
+        .world
+        .get_resource_or_insert_with(DynamicAssetTypes::default)
+        .clone();
+    let loaders: Vec<DynamicAssetCollectionLoader> = {
+        let mut known_endings = app
+            .world
+            .get_resource_or_insert_with(AssetCollectionFileEndings::default);
+        file_endings
+            .iter()
+            .filter(|(_, ending)| known_endings.0.insert(ending))
+            .map(|(format, ending)| DynamicAssetCollectionLoader {
+                types: types.clone(),
+                format: *format,
+                extensions: vec![ending],
+            })
+            .collect()
+    };
+    for loader in loaders {
+        app.add_asset_loader(loader);
+    }
+}
+
+#[cfg(feature = "dynamic_assets")]
+struct DynamicAssetCollectionLoader {
+    types: DynamicAssetTypes,
+    format: DynamicAssetFormat,
+    extensions: Vec<&'static str>,
+}
+
+#[cfg(feature = "dynamic_assets")]
+impl AssetLoader for DynamicAssetCollectionLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let types = self.types.0.read().unwrap();
+            let collection =
+                deserialize_asset_collection_file(self.format, bytes, RegisteredTypes(&types))?;
+            load_context
+                .set_default_asset(LoadedAsset::new(DynamicAssetCollection::from(collection)));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &self.extensions
+    }
+}
+
+/// Deserialize an asset collection file of the given format
+///
+/// The mode decides how dynamic assets are created from their tag and fields.
+#[cfg(feature = "dynamic_assets")]
+pub(crate) fn deserialize_asset_collection_file<M: DynamicAssetMode>(
+    format: DynamicAssetFormat,
+    bytes: &[u8],
+    mode: M,
+) -> Result<ParsedAssetCollection<M::Asset>, anyhow::Error> {
+    let seed = DynamicAssetCollectionSeed {
+        mode,
+        struct_variants: format == DynamicAssetFormat::Ron,
+    };
+    let collection = match format {
+        DynamicAssetFormat::Ron => {
+            let mut deserializer = ron::de::Deserializer::from_bytes(bytes)?;
+            let collection = seed.deserialize(&mut deserializer)?;
+            deserializer.end()?;
+            collection
+        }
+        #[cfg(feature = "dynamic_assets_json")]
+        DynamicAssetFormat::Json => {
+            let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+            let collection = seed.deserialize(&mut deserializer)?;
+            deserializer.end()?;
+            collection
+        }
+        #[cfg(feature = "dynamic_assets_toml")]
+        DynamicAssetFormat::Toml => seed.deserialize(&mut toml::de::Deserializer::new(
+            std::str::from_utf8(bytes)?,
+        ))?,
+        #[cfg(feature = "dynamic_assets_yaml")]
+        DynamicAssetFormat::Yaml => {
+            seed.deserialize(serde_yaml::Deserializer::from_slice(bytes))?
+        }
+    };
+
+    Ok(collection)
+}
+
+/// The content of an asset collection file
+///
+/// Keys are in the order of the file.
+#[cfg(feature = "dynamic_assets")]
+pub(crate) struct ParsedAssetCollection<T> {
+    pub(crate) includes: Vec<String>,
+    pub(crate) assets: Vec<(String, T)>,
+    pub(crate) localized: Vec<(String, Vec<(String, T)>)>,
+}
+
+#[cfg(feature = "dynamic_assets")]
+impl From<ParsedAssetCollection<Box<dyn DynamicAssetType>>> for DynamicAssetCollection {
+    fn from(collection: ParsedAssetCollection<Box<dyn DynamicAssetType>>) -> Self {
+        let assets = |assets: Vec<(String, Box<dyn DynamicAssetType>)>| {
+            assets
+                .into_iter()
+                .map(|(key, asset)| (key, Arc::from(asset)))
+                .collect()
+        };
+        DynamicAssetCollection {
+            includes: collection.includes,
+            assets: assets(collection.assets),
+            localized: collection
+                .localized
+                .into_iter()
+                .map(|(locale, localized)| (locale, assets(localized)))
+                .collect(),
+        }
+    }
+}
+
+/// Creates dynamic assets from their tag and fields while deserializing asset collection files
+#[cfg(feature = "dynamic_assets")]
+pub(crate) trait DynamicAssetMode: Copy {
+    type Asset;
+
+    fn deserialize<'de, A: MapAccess<'de>>(
+        self,
+        tag: String,
+        map: A,
+    ) -> Result<Self::Asset, A::Error>;
+}
+
+/// Registered types are looked up first, all other tags are deserialized as [`DynamicAsset`]
+///
+/// This is how asset collection files are loaded.
+#[cfg(feature = "dynamic_assets")]
+#[derive(Clone, Copy)]
+struct RegisteredTypes<'a>(&'a HashMap<String, DeserializeDynamicAsset>);
+
+#[cfg(feature = "dynamic_assets")]
+impl<'a> DynamicAssetMode for RegisteredTypes<'a> {
+    type Asset = Box<dyn DynamicAssetType>;
+
+    fn deserialize<'de, A: MapAccess<'de>>(
+        self,
+        tag: String,
+        map: A,
+    ) -> Result<Self::Asset, A::Error> {
+        let deserialize = self.0.get(&tag).copied();
+        let deserializer = TaggedMap { tag, map };
+        match deserialize {
+            Some(deserialize) => {
+                deserialize(&mut <dyn erased_serde::Deserializer>::erase(deserializer))
+                    .map_err(A::Error::custom)
+            }
+            None => deserialize_built_in(deserializer)
+                .map(|asset| Box::new(asset) as Box<dyn DynamicAssetType>),
+        }
+    }
+}
+
+/// Tags of [`DynamicAsset`] are deserialized, the fields of all other tags are skipped
+///
+/// This reads asset collection files without knowing the registered types.
+/// Skipped assets are `None`.
+#[cfg(any(feature = "cli", feature = "asset_pack"))]
+#[derive(Clone, Copy)]
+pub(crate) struct BuiltInTypes;
+
+#[cfg(any(feature = "cli", feature = "asset_pack"))]
+impl DynamicAssetMode for BuiltInTypes {
+    type Asset = Option<DynamicAsset>;
+
+    fn deserialize<'de, A: MapAccess<'de>>(
+        self,
+        tag: String,
+        mut map: A,
+    ) -> Result<Self::Asset, A::Error> {
+        if !is_built_in_tag(&tag) {
+            while map.next_entry::<IgnoredAny, IgnoredAny>()?.is_some() {}
+            return Ok(None);
+        }
+        deserialize_built_in(TaggedMap { tag, map }).map(Some)
+    }
+}
+
+/// Deserialize a [`DynamicAsset`] and check its animation clips
+#[cfg(feature = "dynamic_assets")]
+fn deserialize_built_in<'de, A: MapAccess<'de>>(
+    deserializer: TaggedMap<A>,
+) -> Result<DynamicAsset, A::Error> {
+    let asset = DynamicAsset::deserialize(deserializer)?;
+    asset.check_animations().map_err(A::Error::custom)?;
+    Ok(asset)
+}
+
+/// Returns `true` if the tag is a variant of [`DynamicAsset`] with the enabled features
+#[cfg(any(feature = "cli", feature = "asset_pack"))]
+fn is_built_in_tag(tag: &str) -> bool {
+    !matches!(
+        DynamicAsset::deserialize(TagProbe(tag)),
+        Err(TagProbeError::UnknownTag)
+    )
+}
+
+/// Deserializer offering only a tag to find out if it is a known variant
+#[cfg(any(feature = "cli", feature = "asset_pack"))]
+struct TagProbe<'a>(&'a str);
+
+#[cfg(any(feature = "cli", feature = "asset_pack"))]
+#[derive(Debug)]
+enum TagProbeError {
+    UnknownTag,
+    /// The tag is known, but the probe has no fields to deserialize
+    Other,
+}
+
+#[cfg(any(feature = "cli", feature = "asset_pack"))]
+impl fmt::Display for TagProbeError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("tag probe")
+    }
+}
+
+#[cfg(any(feature = "cli", feature = "asset_pack"))]
+impl std::error::Error for TagProbeError {}
+
+#[cfg(any(feature = "cli", feature = "asset_pack"))]
+impl Error for TagProbeError {
+    fn custom<T: fmt::Display>(_message: T) -> Self {
+        TagProbeError::Other
+    }
+
+    fn unknown_variant(_variant: &str, _expected: &'static [&'static str]) -> Self {
+        TagProbeError::UnknownTag
+    }
+}
+
+#[cfg(any(feature = "cli", feature = "asset_pack"))]
+impl<'de, 'a> Deserializer<'de> for TagProbe<'a> {
+    type Error = TagProbeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_enum(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+#[cfg(any(feature = "cli", feature = "asset_pack"))]
+impl<'de, 'a> EnumAccess<'de> for TagProbe<'a> {
+    type Error = TagProbeError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Self::Error> {
+        let tag: StrDeserializer<TagProbeError> = self.0.into_deserializer();
+        Ok((seed.deserialize(tag)?, self))
+    }
+}
+
+#[cfg(any(feature = "cli", feature = "asset_pack"))]
+impl<'de, 'a> VariantAccess<'de> for TagProbe<'a> {
+    type Error = TagProbeError;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        Err(TagProbeError::Other)
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        _seed: T,
+    ) -> Result<T::Value, Self::Error> {
+        Err(TagProbeError::Other)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        Err(TagProbeError::Other)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        Err(TagProbeError::Other)
+    }
+}
+
+/// Reserved key for the list of included asset collection files
+#[cfg(feature = "dynamic_assets")]
+const INCLUDES: &str = "#includes";
+
+/// Reserved key for the dynamic assets by locale
+#[cfg(feature = "dynamic_assets")]
+const LOCALIZED: &str = "#localized";
+
+#[cfg(feature = "dynamic_assets")]
+#[derive(Clone, Copy)]
+struct DynamicAssetCollectionSeed<M> {
+    mode: M,
+    /// Read the fields of dynamic assets as struct variants instead of newtype variants
+    ///
+    /// RON requires struct variants, while e.g. TOML checks struct variant fields against a fixed list.
+    struct_variants: bool,
+}
+
+#[cfg(feature = "dynamic_assets")]
+impl<'de, M: DynamicAssetMode> DeserializeSeed<'de> for DynamicAssetCollectionSeed<M> {
+    type Value = ParsedAssetCollection<M::Asset>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_newtype_struct("DynamicAssetCollection", self)
+    }
+}
+
+#[cfg(feature = "dynamic_assets")]
+impl<'de, M: DynamicAssetMode> Visitor<'de> for DynamicAssetCollectionSeed<M> {
+    type Value = ParsedAssetCollection<M::Asset>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map of asset keys to dynamic assets")
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut includes = vec![];
+        let mut localized = vec![];
+        let mut assets = vec![];
+        let seed = DynamicAssetSeed {
+            mode: self.mode,
+            struct_variants: self.struct_variants,
+        };
+        while let Some(key) = map.next_key::<String>()? {
+            if key == INCLUDES {
+                includes = map.next_value()?;
+                continue;
+            }
+            if key == LOCALIZED {
+                localized = map.next_value_seed(LocalizedDynamicAssetsSeed(seed))?;
+                continue;
+            }
+            let asset = map.next_value_seed(seed)?;
+            assets.push((key, asset));
+        }
+        Ok(ParsedAssetCollection {
+            includes,
+            assets,
+            localized,
+        })
+    }
+}
+
+/// Deserializes the dynamic assets of each locale
+#[cfg(feature = "dynamic_assets")]
+struct LocalizedDynamicAssetsSeed<M>(DynamicAssetSeed<M>);
+
+#[cfg(feature = "dynamic_assets")]
+impl<'de, M: DynamicAssetMode> DeserializeSeed<'de> for LocalizedDynamicAssetsSeed<M> {
+    type Value = Vec<(String, Vec<(String, M::Asset)>)>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+#[cfg(feature = "dynamic_assets")]
+impl<'de, M: DynamicAssetMode> Visitor<'de> for LocalizedDynamicAssetsSeed<M> {
+    type Value = Vec<(String, Vec<(String, M::Asset)>)>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map of locales to maps of asset keys to dynamic assets")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut localized = vec![];
+        while let Some(locale) = map.next_key::<String>()? {
+            let assets = map.next_value_seed(DynamicAssetsSeed(self.0))?;
+            localized.push((locale, assets));
+        }
+        Ok(localized)
+    }
+}
+
+/// Deserializes a map of asset keys to dynamic assets
+#[cfg(feature = "dynamic_assets")]
+struct DynamicAssetsSeed<M>(DynamicAssetSeed<M>);
+
+#[cfg(feature = "dynamic_assets")]
+impl<'de, M: DynamicAssetMode> DeserializeSeed<'de> for DynamicAssetsSeed<M> {
+    type Value = Vec<(String, M::Asset)>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+#[cfg(feature = "dynamic_assets")]
+impl<'de, M: DynamicAssetMode> Visitor<'de> for DynamicAssetsSeed<M> {
+    type Value = Vec<(String, M::Asset)>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map of asset keys to dynamic assets")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut assets = vec![];
+        while let Some(key) = map.next_key::<String>()? {
+            let asset = map.next_value_seed(self.0)?;
+            assets.push((key, asset));
+        }
+        Ok(assets)
+    }
+}
+
+/// Deserializes a dynamic asset by its tag
+#[cfg(feature = "dynamic_assets")]
+#[derive(Clone, Copy)]
+struct DynamicAssetSeed<M> {
+    mode: M,
+    struct_variants: bool,
+}
+
+#[cfg(feature = "dynamic_assets")]
+impl<'de, M: DynamicAssetMode> DeserializeSeed<'de> for DynamicAssetSeed<M> {
+    type Value = M::Asset;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_enum("DynamicAsset", &[], self)
+    }
+}
+
+#[cfg(feature = "dynamic_assets")]
+impl<'de, M: DynamicAssetMode> Visitor<'de> for DynamicAssetSeed<M> {
+    type Value = M::Asset;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a tagged dynamic asset")
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        let (Tag(tag), variant) = data.variant()?;
+        let asset = TaggedDynamicAsset {
+            tag,
+            mode: self.mode,
+        };
+        if self.struct_variants {
+            variant.struct_variant(&[], asset)
+        } else {
+            variant.newtype_variant_seed(asset)
+        }
+    }
+}
+
+/// Variant identifier of a dynamic asset
+#[cfg(feature = "dynamic_assets")]
+struct Tag(String);
+
+#[cfg(feature = "dynamic_assets")]
+impl<'de> Deserialize<'de> for Tag {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_identifier(TagVisitor)
+    }
+}
+
+#[cfg(feature = "dynamic_assets")]
+struct TagVisitor;
+
+#[cfg(feature = "dynamic_assets")]
+impl<'de> Visitor<'de> for TagVisitor {
+    type Value = Tag;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("the tag of a dynamic asset")
+    }
+
+    fn visit_str<E: Error>(self, value: &str) -> Result<Self::Value, E> {
+        Ok(Tag(value.to_owned()))
+    }
+}
+
+#[cfg(feature = "dynamic_assets")]
+struct TaggedDynamicAsset<M> {
+    tag: String,
+    mode: M,
+}
+
+#[cfg(feature = "dynamic_assets")]
+impl<'de, M: DynamicAssetMode> DeserializeSeed<'de> for TaggedDynamicAsset<M> {
+    type Value = M::Asset;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+#[cfg(feature = "dynamic_assets")]
+impl<'de, M: DynamicAssetMode> Visitor<'de> for TaggedDynamicAsset<M> {
+    type Value = M::Asset;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "the fields of the dynamic asset '{}'", self.tag)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        self.mode.deserialize(self.tag, map)
+    }
+}
+
+/// The fields of a dynamic asset together with its tag
+///
+/// Deserializes into a struct from the fields or into an enum using the tag as variant.
+#[cfg(feature = "dynamic_assets")]
+struct TaggedMap<A> {
+    tag: String,
+    map: A,
+}
+
+#[cfg(feature = "dynamic_assets")]
+impl<'de, A: MapAccess<'de>> Deserializer<'de> for TaggedMap<A> {
+    type Error = A::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_map(self.map)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_enum(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+#[cfg(feature = "dynamic_assets")]
+impl<'de, A: MapAccess<'de>> EnumAccess<'de> for TaggedMap<A> {
+    type Error = A::Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Self::Error> {
+        let tag: StringDeserializer<A::Error> = self.tag.clone().into_deserializer();
+        Ok((seed.deserialize(tag)?, self))
+    }
+}
+
+#[cfg(feature = "dynamic_assets")]
+impl<'de, A: MapAccess<'de>> VariantAccess<'de> for TaggedMap<A> {
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        Err(A::Error::invalid_type(Unexpected::Map, &"unit variant"))
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, Self::Error> {
+        seed.deserialize(MapAccessDeserializer::new(self.map))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        Err(A::Error::invalid_type(Unexpected::Map, &"tuple variant"))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_map(self.map)
+    }
+}
+
+#[cfg(all(test, any(feature = "cli", feature = "asset_pack")))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn skips_unregistered_tags() {
+        let collection = deserialize_asset_collection_file(
+            DynamicAssetFormat::Ron,
+            br#"({
+                "music": File ( path: "audio/background.ogg" ),
+                "images": Folder ( path: "images" ),
+                "map": Tilemap ( tileset: "tiles.png", layers: [1, 2] ),
+            })"#,
+            BuiltInTypes,
+        )
+        .unwrap();
+        let paths: Vec<(&str, Option<&str>)> = collection
+            .assets
+            .iter()
+            .map(|(key, asset)| {
+                (
+                    key.as_str(),
+                    asset.as_ref().map(|asset| asset.get_file_path()),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            paths,
+            vec![
+                ("music", Some("audio/background.ogg")),
+                ("images", Some("images")),
+                ("map", None)
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_invalid_built_in_assets() {
+        assert!(deserialize_asset_collection_file(
+            DynamicAssetFormat::Ron,
+            br#"({ "music": File ( file: "audio/background.ogg" ) })"#,
+            BuiltInTypes,
+        )
+        .is_err());
+    }
+}
@@ -62,28 +62,79 @@
 #![forbid(unsafe_code)]
 #![warn(unused_imports, missing_docs)]
 
+use std::any::{type_name, TypeId};
+use std::borrow::Borrow;
+use std::fmt;
+use std::hash::Hash;
 use std::marker::PhantomData;
+use std::sync::Arc;
 
-use bevy::app::App;
-#[cfg(feature = "dynamic_assets")]
-use bevy::asset::Handle;
-use bevy::asset::HandleUntyped;
+use bevy::app::{App, Plugin};
+use bevy::asset::{
+    update_asset_storage_system, Asset, AssetEvent, AssetServer, Assets, HandleUntyped, LoadState,
+};
+use bevy::ecs::component::{Component, SparseStorage};
+use bevy::ecs::entity::Entity;
+use bevy::ecs::event::Events;
 use bevy::ecs::prelude::IntoExclusiveSystem;
 use bevy::ecs::schedule::ExclusiveSystemDescriptorCoercion;
 use bevy::ecs::schedule::StateData;
+use bevy::ecs::system::{Command, EntityCommands, IntoSystem, System};
 use bevy::prelude::{FromWorld, SystemSet, World};
 use bevy::utils::HashMap;
-#[cfg(feature = "dynamic_assets")]
-use bevy_asset_ron::RonAssetPlugin;
+use bevy::utils::HashSet;
 
+#[cfg(feature = "dynamic_assets")]
+pub use bevy_asset_loader_derive::asset_collection_from_file;
 pub use bevy_asset_loader_derive::AssetCollection;
 #[cfg(feature = "dynamic_assets")]
-use dynamic_asset::DynamicAssetCollection;
+use dynamic_asset::{AssetCollectionFile, AssetKeyScope, LoadingAssetCollection};
+use systems::LoadingProgress;
 
-pub use crate::dynamic_asset::DynamicAsset;
+#[cfg(feature = "asset_manifest")]
+pub use crate::asset_manifest::{
+    AssetManifest, AssetManifestMismatch, AssetManifestPlugin, ManifestEntry,
+};
+#[cfg(feature = "asset_pack")]
+pub use crate::asset_pack::{AssetPackPlugin, AssetPacker, PackAssetIo};
+pub use crate::asset_variables::AssetVariables;
+#[cfg(feature = "render")]
+pub use crate::derived_assets::{DerivedAsset, DerivedAssets};
+pub use crate::dynamic_asset::{
+    export_asset_collection_file, DynamicAsset, DynamicAssetType, DynamicAssetValue,
+    FromDynamicAssetValue,
+};
+#[cfg(feature = "dynamic_assets")]
+pub use crate::dynamic_asset::{DynamicAssetApp, DynamicAssetCollection, DynamicAssetFormat};
+pub use crate::locale::CurrentLocale;
+pub use crate::quality::{AssetQuality, QualityTier};
+#[cfg(feature = "render")]
+pub use crate::texture_atlas::{
+    AnimationClip, AnimationClips, NamedTextureAtlas, PackedTextureAtlasLayout,
+    PackedTextureAtlasPlugin, TextureAtlasGrid,
+};
+#[cfg(feature = "cli")]
+pub use crate::validation::{AssetValidator, ValidationReport};
 
+#[cfg(feature = "asset_manifest")]
+mod asset_manifest;
+#[cfg(feature = "asset_pack")]
+mod asset_pack;
+#[cfg(any(feature = "cli", feature = "asset_pack"))]
+mod asset_references;
+mod asset_variables;
+#[cfg(feature = "render")]
+mod derived_assets;
 mod dynamic_asset;
+mod locale;
+mod quality;
 mod systems;
+#[cfg(feature = "test_support")]
+pub mod test_support;
+#[cfg(feature = "render")]
+mod texture_atlas;
+#[cfg(feature = "cli")]
+mod validation;
 
 /// Trait to mark a struct as a collection of assets
 ///
@@ -104,6 +155,12 @@ pub trait AssetCollection: Send + Sync + 'static {
     fn create(world: &mut World) -> Self;
     /// Start loading all the assets in the collection
     fn load(world: &mut World) -> Vec<HandleUntyped>;
+    /// Dynamic assets equivalent to the fields with a path, keyed by field name
+    ///
+    /// Used by [`export_asset_collection_file`].
+    fn dynamic_assets() -> Vec<(String, DynamicAsset)> {
+        vec![]
+    }
 }
 
 /// Extension trait for [`App`](bevy::app::App) enabling initialisation of [asset collections](AssetCollection)
@@ -114,6 +171,50 @@ pub trait AssetCollectionApp {
     /// If you want to use a loading state, you do not need this function! Instead use an [`AssetLoader`]
     /// and add collections to it to be prepared during the loading state.
     fn init_collection<A: AssetCollection>(&mut self) -> &mut Self;
+
+    /// Load an [`AssetCollection`] and block until all its assets are loaded
+    ///
+    /// The app is updated until every handle of the collection is loaded or failed to load, but
+    /// at most `max_frames` times. On success, the collection is inserted as resource. This is
+    /// meant for headless tools, asset bakers, and tests. All systems of the app run during the
+    /// updates, so in a game you should use an [`AssetLoader`] instead.
+    ///
+    /// The updates do not wait for the asset server. With an [`AssetIo`](bevy::asset::AssetIo)
+    /// reading files in the background, the number of frames a collection needs depends on the
+    /// machine, so choose `max_frames` generously.
+    ///
+    /// To load a collection without updating the whole app, use
+    /// [`AssetCollectionWorld::load_collection_now`].
+    ///
+    /// Loading the assets of a collection requires an `AssetServer`, the `AssetPlugin`, and the
+    /// plugins of all asset types in the collection. If the collection already exists as resource,
+    /// this returns right away.
+    /// ```edition2021,no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy::asset::AssetPlugin;
+    /// # use bevy::audio::AudioPlugin;
+    /// # use bevy_asset_loader::{AssetCollection, AssetCollectionApp};
+    /// # fn main() {
+    /// let mut app = App::new();
+    /// app.add_plugins(MinimalPlugins)
+    ///     .add_plugin(AssetPlugin)
+    ///     .add_plugin(AudioPlugin);
+    ///
+    /// if let Err(error) = app.load_collection_now::<MyAudio>(10_000) {
+    ///     panic!("{}", error);
+    /// }
+    /// let audio = app.world.get_resource::<MyAudio>().unwrap();
+    /// # }
+    /// # #[derive(AssetCollection)]
+    /// # struct MyAudio {
+    /// #     #[asset(path = "audio/background.ogg")]
+    /// #     background: Handle<AudioSource>,
+    /// # }
+    /// ```
+    fn load_collection_now<A: AssetCollection>(
+        &mut self,
+        max_frames: usize,
+    ) -> Result<(), LoadCollectionError>;
 }
 
 impl AssetCollectionApp for App {
@@ -122,10 +223,7 @@ impl AssetCollectionApp for App {
         Collection: AssetCollection,
     {
         if !self.world.contains_resource::<Collection>() {
-            // This resource is required for loading a collection
-            // Since bevy_asset_loader does not have a "real" Plugin,
-            // we need to make sure the resource exists here
-            self.init_resource::<AssetKeys>();
+            init_collection_resources(self);
             // make sure the assets start to load
             let _ = Collection::load(&mut self.world);
             let resource = Collection::create(&mut self.world);
@@ -133,8 +231,214 @@ impl AssetCollectionApp for App {
         }
         self
     }
+
+    fn load_collection_now<Collection>(
+        &mut self,
+        max_frames: usize,
+    ) -> Result<(), LoadCollectionError>
+    where
+        Collection: AssetCollection,
+    {
+        load_collection_now::<Collection, _>(self, max_frames)
+    }
+}
+
+/// Something that can load assets by updating
+trait LoadAssets {
+    fn world(&mut self) -> &mut World;
+    fn init_collection_resources(&mut self);
+    fn update_assets(&mut self);
+}
+
+impl LoadAssets for App {
+    fn world(&mut self) -> &mut World {
+        &mut self.world
+    }
+
+    fn init_collection_resources(&mut self) {
+        init_collection_resources(self);
+    }
+
+    fn update_assets(&mut self) {
+        self.update();
+    }
+}
+
+impl LoadAssets for World {
+    fn world(&mut self) -> &mut World {
+        self
+    }
+
+    fn init_collection_resources(&mut self) {
+        init_collection_world_resources(self);
+    }
+
+    fn update_assets(&mut self) {
+        if let Some(asset_server) = self.get_resource::<AssetServer>() {
+            asset_server.free_unused_assets();
+            asset_server.mark_unused_assets();
+        }
+        let asset_types = match self.get_resource::<RegisteredAssetTypes>() {
+            Some(asset_types) => asset_types.update_assets.clone(),
+            None => vec![],
+        };
+        for update_assets in asset_types {
+            update_assets(self);
+        }
+        // give the background tasks of the asset server a chance to make progress
+        std::thread::yield_now();
+    }
+}
+
+fn load_collection_now<Collection: AssetCollection, L: LoadAssets>(
+    loader: &mut L,
+    max_frames: usize,
+) -> Result<(), LoadCollectionError> {
+    if loader.world().contains_resource::<Collection>() {
+        return Ok(());
+    }
+    loader.init_collection_resources();
+    let mut handles = Collection::load(loader.world());
+    let mut frames = 0;
+    loop {
+        let world = loader.world();
+        let asset_server = world
+            .get_resource::<AssetServer>()
+            .expect("Cannot get AssetServer resource");
+        let settled = handles.iter().all(|handle| {
+            matches!(
+                asset_server.get_load_state(handle),
+                LoadState::Loaded | LoadState::Failed
+            )
+        });
+        if settled {
+            let paths = failed_paths(asset_server, &handles);
+            let mut quality = world
+                .get_resource_mut::<AssetQuality>()
+                .expect("Cannot get AssetQuality resource");
+            let mut fell_back = false;
+            for path in paths.iter() {
+                fell_back |= quality.fall_back(path);
+            }
+            if fell_back {
+                handles = Collection::load(world);
+                continue;
+            }
+            if !paths.is_empty() {
+                return Err(LoadCollectionError::Failed {
+                    collection: type_name::<Collection>(),
+                    paths,
+                });
+            }
+            break;
+        }
+        if frames == max_frames {
+            return Err(LoadCollectionError::Timeout {
+                collection: type_name::<Collection>(),
+                frames,
+            });
+        }
+        loader.update_assets();
+        frames += 1;
+    }
+    let world = loader.world();
+    let resource = Collection::create(world);
+    world.insert_resource(resource);
+
+    Ok(())
+}
+
+/// Asset types whose storage [`AssetCollectionWorld::load_collection_now`] updates
+#[derive(Default)]
+struct RegisteredAssetTypes {
+    registered: HashSet<TypeId>,
+    update_assets: Vec<fn(&mut World)>,
+}
+
+/// Move loaded assets into their storage and send their events, like the systems of `AddAsset::add_asset`
+fn update_assets<T: Asset>(world: &mut World) {
+    let mut update_storage = update_asset_storage_system::<T>.system();
+    update_storage.initialize(world);
+    update_storage.run((), world);
+    let mut send_events = Assets::<T>::asset_event_system.system();
+    send_events.initialize(world);
+    send_events.run((), world);
+    if let Some(mut events) = world.get_resource_mut::<Events<AssetEvent<T>>>() {
+        events.update();
+    }
+}
+
+fn init_collection_resources(app: &mut App) {
+    init_collection_world_resources(&mut app.world);
+    #[cfg(feature = "render")]
+    derived_assets::init_derived_assets(app);
+}
+
+fn init_collection_world_resources(world: &mut World) {
+    // These resources are required for loading a collection
+    // Since bevy_asset_loader does not have a "real" Plugin,
+    // we need to make sure the resources exist here
+    world.get_resource_or_insert_with(AssetKeys::default);
+    world.get_resource_or_insert_with(AssetVariables::default);
+    world.get_resource_or_insert_with(AssetQuality::default);
+    #[cfg(feature = "render")]
+    world.get_resource_or_insert_with(DerivedAssets::default);
+}
+
+fn failed_paths(asset_server: &AssetServer, handles: &[HandleUntyped]) -> Vec<String> {
+    let mut paths: Vec<String> = handles
+        .iter()
+        .filter(|handle| asset_server.get_load_state(*handle) == LoadState::Failed)
+        .filter_map(|handle| asset_server.get_handle_path(handle))
+        .map(|asset_path| asset_path.path().to_string_lossy().replace('\\', "/"))
+        .collect();
+    paths.sort();
+    paths.dedup();
+
+    paths
+}
+
+/// Error of [`AssetCollectionApp::load_collection_now`] and [`AssetCollectionWorld::load_collection_now`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LoadCollectionError {
+    /// Files of the collection failed to load
+    Failed {
+        /// Type name of the collection
+        collection: &'static str,
+        /// Paths of all files of the collection that failed to load
+        paths: Vec<String>,
+    },
+    /// The collection was still loading after the given number of frames
+    Timeout {
+        /// Type name of the collection
+        collection: &'static str,
+        /// Number of updates of the app
+        frames: usize,
+    },
+}
+
+impl fmt::Display for LoadCollectionError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadCollectionError::Failed { collection, paths } => {
+                write!(
+                    formatter,
+                    "Failed to load {}: {}",
+                    collection,
+                    paths.join(", ")
+                )
+            }
+            LoadCollectionError::Timeout { collection, frames } => write!(
+                formatter,
+                "{} was still loading after {} frames",
+                collection, frames
+            ),
+        }
+    }
 }
 
+impl std::error::Error for LoadCollectionError {}
+
 /// Extension trait for [`World`](bevy::ecs::world::World) enabling initialisation of [asset collections](AssetCollection)
 pub trait AssetCollectionWorld {
     /// Initialise an [`AssetCollection`]
@@ -143,25 +447,292 @@ pub trait AssetCollectionWorld {
     /// If you want to use a loading state, you do not need this function! Instead use an [`AssetLoader`]
     /// and add collections to it to be prepared during the loading state.
     fn init_collection<A: AssetCollection>(&mut self);
+
+    /// Register an asset type for [`AssetCollectionWorld::load_collection_now`]
+    ///
+    /// The asset type needs to be added to the app of the world with `AddAsset::add_asset`,
+    /// which creates its [`Assets`] storage.
+    fn register_asset_type<T: Asset>(&mut self) -> &mut Self;
+
+    /// Load an [`AssetCollection`] and block until all its assets are loaded
+    ///
+    /// This is [`AssetCollectionApp::load_collection_now`] without an app. Instead of updating
+    /// the app, every frame frees unused assets, moves loaded assets into their storage and sends
+    /// their [`AssetEvent`]s. Only the storage of asset types registered with
+    /// [`AssetCollectionWorld::register_asset_type`] is updated, so register all asset types
+    /// of the collection, including the types of labeled assets in its files.
+    ///
+    /// None of the app's systems run, so this can be called from exclusive systems. If the
+    /// collection already exists as resource, this returns right away.
+    /// ```edition2021,no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy::asset::AssetPlugin;
+    /// # use bevy::audio::AudioPlugin;
+    /// # use bevy_asset_loader::{AssetCollection, AssetCollectionWorld};
+    /// # fn main() {
+    /// let mut app = App::new();
+    /// app.add_plugins(MinimalPlugins)
+    ///     .add_plugin(AssetPlugin)
+    ///     .add_plugin(AudioPlugin);
+    /// let world = &mut app.world;
+    /// world.register_asset_type::<AudioSource>();
+    ///
+    /// if let Err(error) = world.load_collection_now::<MyAudio>(10_000) {
+    ///     panic!("{}", error);
+    /// }
+    /// let audio = world.get_resource::<MyAudio>().unwrap();
+    /// # }
+    /// # #[derive(AssetCollection)]
+    /// # struct MyAudio {
+    /// #     #[asset(path = "audio/background.ogg")]
+    /// #     background: Handle<AudioSource>,
+    /// # }
+    /// ```
+    fn load_collection_now<A: AssetCollection>(
+        &mut self,
+        max_frames: usize,
+    ) -> Result<(), LoadCollectionError>;
 }
 
 impl AssetCollectionWorld for World {
     fn init_collection<A: AssetCollection>(&mut self) {
         if self.get_resource::<A>().is_none() {
-            if self.get_resource::<AssetKeys>().is_none() {
-                // This resource is required for loading a collection
-                // Since bevy_asset_loader does not have a "real" Plugin,
-                // we need to make sure the resource exists here
-                self.insert_resource(AssetKeys::default());
-            }
+            init_collection_world_resources(self);
             // make sure the assets start to load
             let _ = A::load(self);
             let collection = A::create(self);
             self.insert_resource(collection);
         }
     }
+
+    fn register_asset_type<T: Asset>(&mut self) -> &mut Self {
+        if !self.contains_resource::<Assets<T>>() {
+            panic!("Cannot get resource Assets<{}>", type_name::<T>());
+        }
+        let mut asset_types = self.get_resource_or_insert_with(RegisteredAssetTypes::default);
+        if asset_types.registered.insert(TypeId::of::<T>()) {
+            asset_types.update_assets.push(update_assets::<T>);
+        }
+
+        self
+    }
+
+    fn load_collection_now<A: AssetCollection>(
+        &mut self,
+        max_frames: usize,
+    ) -> Result<(), LoadCollectionError> {
+        load_collection_now::<A, _>(self, max_frames)
+    }
 }
 
+/// Extension trait for [`EntityCommands`] enabling loading of [asset collections](AssetCollection) as components
+pub trait AssetCollectionEntityCommands {
+    /// Load an [`AssetCollection`] and insert it as a component on this entity
+    ///
+    /// The entity has a [`Loading<A>`] component until all assets of the collection are loaded.
+    /// If the entity is despawned or the [`Loading<A>`] component is removed in the meantime,
+    /// the collection is not inserted.
+    ///
+    /// If a file of the collection fails to load, the [`Loading<A>`] component is replaced by a
+    /// [`LoadingFailed<A>`] component listing the failed files.
+    ///
+    /// The collection type needs to be a [`Component`]. Loading collections as components
+    /// requires the [`CollectionComponentsPlugin`], which every [`AssetLoader`] adds to the app.
+    /// ```edition2021
+    /// # use bevy::prelude::*;
+    /// # use bevy_asset_loader::{AssetCollection, AssetCollectionEntityCommands};
+    /// #[derive(AssetCollection, Component)]
+    /// struct SkinAssets {
+    ///     #[asset(path = "images/player.png")]
+    ///     sprite: Handle<Image>,
+    /// }
+    ///
+    /// fn spawn_player(mut commands: Commands) {
+    ///     commands.spawn().load_collection::<SkinAssets>();
+    /// }
+    /// ```
+    fn load_collection<A: AssetCollection + Component>(&mut self) -> &mut Self;
+}
+
+impl<'w, 's, 'a> AssetCollectionEntityCommands for EntityCommands<'w, 's, 'a> {
+    fn load_collection<A: AssetCollection + Component>(&mut self) -> &mut Self {
+        let entity = self.id();
+        self.commands().add(LoadCollection::<A> {
+            entity,
+            marker: PhantomData,
+        });
+        self
+    }
+}
+
+/// Marker component of entities that load the [`AssetCollection`] `A`
+///
+/// See [`AssetCollectionEntityCommands::load_collection`].
+pub struct Loading<A: AssetCollection> {
+    marker: PhantomData<A>,
+}
+
+impl<A: AssetCollection> Component for Loading<A> {
+    type Storage = SparseStorage;
+}
+
+/// Marker component of entities that failed to load the [`AssetCollection`] `A`
+///
+/// See [`AssetCollectionEntityCommands::load_collection`].
+pub struct LoadingFailed<A: AssetCollection> {
+    /// Paths of all files of the collection that failed to load
+    pub paths: Vec<String>,
+    marker: PhantomData<A>,
+}
+
+impl<A: AssetCollection> Component for LoadingFailed<A> {
+    type Storage = SparseStorage;
+}
+
+/// Plugin loading [asset collections](AssetCollection) as components
+///
+/// Every [`AssetLoader`] adds this plugin. Apps loading collections with
+/// [`AssetCollectionEntityCommands::load_collection`] without a loading state need to add it
+/// themselves.
+/// ```edition2021
+/// # use bevy::prelude::*;
+/// # use bevy::asset::AssetPlugin;
+/// # use bevy_asset_loader::CollectionComponentsPlugin;
+/// # fn main() {
+/// App::new()
+///     .add_plugins(MinimalPlugins)
+///     .add_plugin(AssetPlugin)
+///     .add_plugin(CollectionComponentsPlugin);
+/// # }
+/// ```
+pub struct CollectionComponentsPlugin;
+
+impl Plugin for CollectionComponentsPlugin {
+    fn build(&self, app: &mut App) {
+        if app.world.contains_resource::<LoadingCollectionComponents>() {
+            return;
+        }
+        init_collection_resources(app);
+        app.init_resource::<LoadingCollectionComponents>()
+            .init_resource::<LoadingProgress>()
+            .add_system(systems::load_collection_components.exclusive_system());
+    }
+}
+
+struct LoadCollection<A: AssetCollection + Component> {
+    entity: Entity,
+    marker: PhantomData<A>,
+}
+
+impl<A: AssetCollection + Component> Command for LoadCollection<A> {
+    fn write(self, world: &mut World) {
+        if world.get_entity(self.entity).is_none() {
+            return;
+        }
+        if !world.contains_resource::<LoadingCollectionComponents>() {
+            panic!("Loading collections as components requires the CollectionComponentsPlugin");
+        }
+        let handles = A::load(world);
+        world.entity_mut(self.entity).insert(Loading::<A> {
+            marker: PhantomData,
+        });
+        world
+            .get_resource_mut::<LoadingCollectionComponents>()
+            .expect("Cannot get LoadingCollectionComponents")
+            .0
+            .push(LoadingCollectionComponent {
+                entity: self.entity,
+                handles,
+                load: A::load,
+                insert: systems::insert_collection_component::<A>,
+                fail: systems::fail_collection_component::<A>,
+            });
+    }
+}
+
+/// Collections of entities that are still loading
+#[derive(Default)]
+struct LoadingCollectionComponents(Vec<LoadingCollectionComponent>);
+
+struct LoadingCollectionComponent {
+    entity: Entity,
+    handles: Vec<HandleUntyped>,
+    load: fn(&mut World) -> Vec<HandleUntyped>,
+    insert: fn(&mut World, Entity),
+    fail: fn(&mut World, Entity, Vec<String>),
+}
+
+/// Resource holding multiple instances of an [`AssetCollection`] by their ids
+///
+/// Instances are loaded with [`AssetLoader::with_collection_instance`]. Each instance
+/// resolves the keys of the collection with its own key prefix.
+/// ```edition2021
+/// # use bevy::prelude::*;
+/// # use bevy_asset_loader::{AssetCollection, Collections};
+/// #[derive(AssetCollection)]
+/// struct CharacterAssets {
+///     #[asset(key = "sprite")]
+///     sprite: Handle<Image>,
+/// }
+///
+/// fn spawn_knight(mut commands: Commands, characters: Res<Collections<CharacterAssets, String>>) {
+///     let knight = characters.get("knight").expect("The knight was not loaded");
+///     commands.spawn_bundle(SpriteBundle {
+///         texture: knight.sprite.clone(),
+///         ..Default::default()
+///     });
+/// }
+/// ```
+pub struct Collections<A: AssetCollection, Id> {
+    collections: HashMap<Id, A>,
+}
+
+impl<A: AssetCollection, Id> Default for Collections<A, Id> {
+    fn default() -> Self {
+        Collections {
+            collections: HashMap::default(),
+        }
+    }
+}
+
+impl<A: AssetCollection, Id: Hash + Eq> Collections<A, Id> {
+    /// Get the instance with the given id
+    pub fn get<Q>(&self, id: &Q) -> Option<&A>
+    where
+        Id: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.collections.get(id)
+    }
+
+    /// Iterate over all instances and their ids
+    pub fn iter(&self) -> impl Iterator<Item = (&Id, &A)> {
+        self.collections.iter()
+    }
+
+    /// Number of loaded instances
+    pub fn len(&self) -> usize {
+        self.collections.len()
+    }
+
+    /// Returns `true` if no instance is loaded
+    pub fn is_empty(&self) -> bool {
+        self.collections.is_empty()
+    }
+
+    pub(crate) fn insert(&mut self, id: Id, collection: A) {
+        self.collections.insert(id, collection);
+    }
+}
+
+/// Loads and inserts an [`AssetCollection`] added to an [`AssetLoader`]
+struct CollectionBuilder {
+    load: Box<LoadCollectionFn>,
+    insert: Box<dyn Fn(&mut World) + Send + Sync>,
+}
+
+type LoadCollectionFn = dyn Fn(&mut World) -> Vec<HandleUntyped> + Send + Sync;
+
 struct LoadingAssetHandles<A: AssetCollection> {
     handles: Vec<HandleUntyped>,
     marker: PhantomData<A>,
@@ -171,9 +742,15 @@ struct AssetLoaderConfiguration<State> {
     configuration: HashMap<State, LoadingConfiguration<State>>,
     phase: HashMap<State, LoadingStatePhase>,
     #[cfg(feature = "dynamic_assets")]
-    asset_collection_handles: Vec<Handle<DynamicAssetCollection>>,
+    loading_asset_collection_files: Vec<AssetCollectionFile>,
+    #[cfg(feature = "dynamic_assets")]
+    asset_collection_handles: HashMap<String, LoadingAssetCollection>,
+    #[cfg(feature = "dynamic_assets")]
+    asset_collection_files: HashMap<State, Vec<AssetCollectionFile>>,
     #[cfg(feature = "dynamic_assets")]
-    asset_collection_files: HashMap<State, Vec<String>>,
+    hot_reload_states: HashSet<State>,
+    #[cfg(feature = "dynamic_assets")]
+    hot_reload_handles: HashMap<State, HashMap<String, LoadingAssetCollection>>,
 }
 
 impl<State> Default for AssetLoaderConfiguration<State> {
@@ -182,9 +759,15 @@ impl<State> Default for AssetLoaderConfiguration<State> {
             configuration: HashMap::default(),
             phase: HashMap::default(),
             #[cfg(feature = "dynamic_assets")]
-            asset_collection_handles: vec![],
+            loading_asset_collection_files: vec![],
+            #[cfg(feature = "dynamic_assets")]
+            asset_collection_handles: HashMap::default(),
             #[cfg(feature = "dynamic_assets")]
             asset_collection_files: HashMap::default(),
+            #[cfg(feature = "dynamic_assets")]
+            hot_reload_states: HashSet::default(),
+            #[cfg(feature = "dynamic_assets")]
+            hot_reload_handles: HashMap::default(),
         }
     }
 }
@@ -194,13 +777,8 @@ impl<State: StateData> AssetLoaderConfiguration<State> {
     ///
     /// The files can be loaded as [`DynamicAssetCollection`](crate::dynamic_asset::DynamicAssetCollection) assets.
     #[cfg(feature = "dynamic_assets")]
-    pub fn get_asset_collection_files(&mut self, state: &State) -> Vec<String> {
-        self.asset_collection_files
-            .get(state)
-            .unwrap()
-            .iter()
-            .map(|file| file.to_owned())
-            .collect()
+    pub fn get_asset_collection_files(&mut self, state: &State) -> Vec<AssetCollectionFile> {
+        self.asset_collection_files.get(state).unwrap().clone()
     }
 }
 
@@ -215,6 +793,8 @@ enum LoadingStatePhase {
 struct LoadingConfiguration<T> {
     next: Option<T>,
     count: usize,
+    #[cfg(feature = "asset_manifest")]
+    manifest: Option<AssetManifest>,
 }
 
 /// Resource to dynamically resolve keys to asset paths.
@@ -222,6 +802,9 @@ struct LoadingConfiguration<T> {
 /// This resource is set by the [`AssetLoader`] and is read when entering a loading state.
 /// You should set your desired asset key and paths in a previous [`State`](bevy_ecs::schedule::State).
 ///
+/// Keys from asset collection files are only available in the loading state that loaded the file,
+/// unless the file was added with [`AssetLoader::with_global_asset_collection_file`].
+///
 /// ```edition2021
 /// # use bevy::prelude::*;
 /// # use bevy_asset_loader::{AssetKeys, AssetCollection, DynamicAsset};
@@ -266,18 +849,147 @@ struct LoadingConfiguration<T> {
 /// ```
 #[derive(Default)]
 pub struct AssetKeys {
-    key_asset_map: HashMap<String, DynamicAsset>,
+    key_asset_map: HashMap<String, KeyedAsset>,
+    #[cfg(feature = "dynamic_assets")]
+    scoped_key_asset_maps: HashMap<AssetKeyScope, HashMap<String, KeyedAsset>>,
+    #[cfg(feature = "dynamic_assets")]
+    current_scope: Option<AssetKeyScope>,
+    key_prefix: Option<KeyPrefix>,
+}
+
+/// Prefix of the keys of a collection instance (see [`AssetLoader::with_collection_instance`])
+#[derive(Clone)]
+pub(crate) struct KeyPrefix {
+    prefix: String,
+    /// Keys without a prefixed version fall back to the key itself
+    shared_keys: bool,
+}
+
+/// A dynamic asset together with its origin
+#[derive(Clone)]
+struct KeyedAsset {
+    asset: Arc<dyn DynamicAssetType>,
+    provenance: AssetKeyProvenance,
+}
+
+/// The origin of the asset behind a key (see [`AssetKeys::provenance`])
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AssetKeyProvenance {
+    /// The key was registered in code with [`AssetKeys::register_asset`] or [`AssetLoader::add_keys`]
+    Code,
+    /// The key was loaded from an asset collection file
+    #[cfg(feature = "dynamic_assets")]
+    Layer {
+        /// Path of the asset collection file added to the [`AssetLoader`]
+        path: String,
+        /// Path of the file that defined the key
+        ///
+        /// This is either the layer file itself or one of the files it includes.
+        file: String,
+        /// Priority of the file (see [`AssetLoader::with_override_layer`])
+        priority: i32,
+    },
+}
+
+impl AssetKeyProvenance {
+    /// Keys registered in code have the lowest priority
+    #[cfg(feature = "dynamic_assets")]
+    fn priority(&self) -> i32 {
+        match self {
+            AssetKeyProvenance::Code => i32::MIN,
+            AssetKeyProvenance::Layer { priority, .. } => *priority,
+        }
+    }
 }
 
 impl AssetKeys {
     /// Get the asset corresponding to the given key.
-    pub fn get_asset(&self, key: &str) -> Option<&DynamicAsset> {
-        self.key_asset_map.get(key)
+    ///
+    /// If both the current loading state and a global file define the key, the file with the
+    /// higher priority wins. For the same priority, keys of the current loading state take
+    /// precedence over global keys. Keys registered in code lose against any file.
+    pub fn get_asset(&self, key: &str) -> Option<&dyn DynamicAssetType> {
+        self.get_keyed_asset(key)
+            .map(|keyed_asset| keyed_asset.asset.as_ref())
+    }
+
+    /// Get the origin of the asset corresponding to the given key.
+    ///
+    /// For keys defined in multiple override layers, this is the layer that won.
+    pub fn provenance(&self, key: &str) -> Option<&AssetKeyProvenance> {
+        self.get_keyed_asset(key)
+            .map(|keyed_asset| &keyed_asset.provenance)
+    }
+
+    /// All keys with an asset, in alphabetical order
+    ///
+    /// This includes the global keys and the keys of the current loading state.
+    pub fn keys(&self) -> Vec<&str> {
+        let mut keys: Vec<&str> = self.key_asset_map.keys().map(String::as_str).collect();
+        #[cfg(feature = "dynamic_assets")]
+        if let Some(scoped) = self
+            .current_scope
+            .and_then(|scope| self.scoped_key_asset_maps.get(&scope))
+        {
+            keys.extend(scoped.keys().map(String::as_str));
+        }
+        keys.sort_unstable();
+        keys.dedup();
+
+        keys
+    }
+
+    fn get_keyed_asset(&self, key: &str) -> Option<&KeyedAsset> {
+        if let Some(key_prefix) = self.key_prefix.as_ref() {
+            let prefixed_key = format!("{}{}", key_prefix.prefix, key);
+            let keyed_asset = self.get_unprefixed_keyed_asset(&prefixed_key);
+            if keyed_asset.is_some() || !key_prefix.shared_keys {
+                return keyed_asset;
+            }
+        }
+        self.get_unprefixed_keyed_asset(key)
+    }
+
+    fn get_unprefixed_keyed_asset(&self, key: &str) -> Option<&KeyedAsset> {
+        let global = self.key_asset_map.get(key);
+        #[cfg(feature = "dynamic_assets")]
+        if let Some(scoped) = self
+            .current_scope
+            .and_then(|scope| self.scoped_key_asset_maps.get(&scope))
+            .and_then(|key_asset_map| key_asset_map.get(key))
+        {
+            return match global {
+                Some(global) if global.provenance.priority() > scoped.provenance.priority() => {
+                    Some(global)
+                }
+                _ => Some(scoped),
+            };
+        }
+        global
+    }
+
+    pub(crate) fn set_key_prefix(&mut self, key_prefix: Option<KeyPrefix>) {
+        self.key_prefix = key_prefix;
+    }
+
+    #[cfg(feature = "dynamic_assets")]
+    pub(crate) fn enter_scope(&mut self, scope: AssetKeyScope) {
+        self.current_scope = Some(scope);
+        self.scoped_key_asset_maps.remove(&scope);
+    }
+
+    #[cfg(feature = "dynamic_assets")]
+    pub(crate) fn leave_scope(&mut self, scope: AssetKeyScope) {
+        if self.current_scope == Some(scope) {
+            self.current_scope = None;
+        }
+        self.scoped_key_asset_maps.remove(&scope);
     }
 
     /// Set the corresponding dynamic asset for the given key.
     ///
     /// In case the key is already known, its value will be overwritten.
+    /// Any [`DynamicAssetType`] can be registered, including your own types.
     /// ```edition2021
     /// # use bevy::prelude::*;
     /// # use bevy_asset_loader::{AssetKeys, AssetCollection, DynamicAsset};
@@ -310,8 +1022,14 @@ impl AssetKeys {
     /// #     Menu
     /// # }
     /// ```
-    pub fn register_asset<K: Into<String>>(&mut self, key: K, asset: DynamicAsset) {
-        self.key_asset_map.insert(key.into(), asset);
+    pub fn register_asset<K: Into<String>, A: DynamicAssetType>(&mut self, key: K, asset: A) {
+        self.key_asset_map.insert(
+            key.into(),
+            KeyedAsset {
+                asset: Arc::new(asset),
+                provenance: AssetKeyProvenance::Code,
+            },
+        );
     }
 }
 
@@ -367,15 +1085,21 @@ impl AssetKeys {
 pub struct AssetLoader<State> {
     next_state: Option<State>,
     loading_state: State,
-    keys: HashMap<String, DynamicAsset>,
+    keys: HashMap<String, Arc<dyn DynamicAssetType>>,
     on_enter: SystemSet,
     on_update: SystemSet,
     on_exit: SystemSet,
     collection_count: usize,
+    collections: Vec<CollectionBuilder>,
+    reload_on_locale_change: bool,
+    #[cfg(feature = "dynamic_assets")]
+    hot_reload_asset_collection_files: bool,
     #[cfg(feature = "dynamic_assets")]
-    asset_collection_file_ending: &'static str,
+    asset_collection_file_endings: HashMap<DynamicAssetFormat, &'static str>,
     #[cfg(feature = "dynamic_assets")]
-    asset_collection_files: Vec<String>,
+    asset_collection_files: Vec<AssetCollectionFile>,
+    #[cfg(feature = "asset_manifest")]
+    manifest: Option<AssetManifest>,
 }
 
 impl<State> AssetLoader<State>
@@ -432,10 +1156,19 @@ where
             on_update: SystemSet::on_update(load.clone()),
             on_exit: SystemSet::on_exit(load),
             collection_count: 0,
+            collections: vec![],
+            reload_on_locale_change: false,
             #[cfg(feature = "dynamic_assets")]
-            asset_collection_file_ending: "assets",
+            hot_reload_asset_collection_files: false,
+            #[cfg(feature = "dynamic_assets")]
+            asset_collection_file_endings: DynamicAssetFormat::enabled()
+                .drain(..)
+                .map(|format| (format, format.default_file_ending()))
+                .collect(),
             #[cfg(feature = "dynamic_assets")]
             asset_collection_files: vec![],
+            #[cfg(feature = "asset_manifest")]
+            manifest: None,
         }
     }
 
@@ -488,12 +1221,115 @@ where
     ///
     /// The file will be loaded as [`DynamicAssetCollection`](crate::dynamic_asset::DynamicAssetCollection).
     /// It's mapping of asset keys to asset configurations can be used for dynamic assets.
+    /// The keys are only available during this loading state.
     ///
     /// See the `dynamic_asset_ron` example.
     #[cfg(feature = "dynamic_assets")]
     pub fn with_asset_collection_file(mut self, asset_collection_file_path: &str) -> Self {
-        self.asset_collection_files
-            .push(asset_collection_file_path.to_owned());
+        self.asset_collection_files.push(AssetCollectionFile {
+            path: asset_collection_file_path.to_owned(),
+            global: false,
+            priority: 0,
+        });
+
+        self
+    }
+
+    /// Register an asset collection file with global keys
+    ///
+    /// The file is loaded during this loading state like files added with
+    /// [`with_asset_collection_file`](Self::with_asset_collection_file), but its keys stay
+    /// available in all following states.
+    #[cfg(feature = "dynamic_assets")]
+    pub fn with_global_asset_collection_file(mut self, asset_collection_file_path: &str) -> Self {
+        self.asset_collection_files.push(AssetCollectionFile {
+            path: asset_collection_file_path.to_owned(),
+            global: true,
+            priority: 0,
+        });
+
+        self
+    }
+
+    /// Register an asset collection file as override layer with the given priority
+    ///
+    /// Keys of layers with a higher priority replace the same keys of layers with a lower priority.
+    /// Files added with [`with_asset_collection_file`](Self::with_asset_collection_file) have the
+    /// priority `0`. For layers with the same priority, the layer added last wins.
+    /// Use [`AssetKeys::provenance`] to find out which layer defined a key.
+    ///
+    /// Like files added with [`with_asset_collection_file`](Self::with_asset_collection_file),
+    /// the keys are only available during this loading state.
+    /// ```edition2021
+    /// # use bevy_asset_loader::{AssetLoader, AssetCollection};
+    /// # use bevy::prelude::*;
+    /// # use bevy::asset::AssetPlugin;
+    /// # fn main() {
+    ///     let mut app = App::new();
+    /// #   app
+    /// #       .add_plugins(MinimalPlugins)
+    /// #       .add_plugin(AssetPlugin::default());
+    ///     AssetLoader::new(GameState::Loading)
+    ///         .continue_to_state(GameState::Menu)
+    ///         .with_asset_collection_file("game.assets")
+    ///         .with_override_layer("mods/high_res_textures.assets", 10)
+    ///         .build(&mut app);
+    /// #   app
+    /// #       .add_state(GameState::Loading);
+    /// # }
+    /// # #[derive(Clone, Eq, PartialEq, Debug, Hash)]
+    /// # enum GameState {
+    /// #     Loading,
+    /// #     Menu
+    /// # }
+    /// ```
+    #[cfg(feature = "dynamic_assets")]
+    pub fn with_override_layer(mut self, asset_collection_file_path: &str, priority: i32) -> Self {
+        self.asset_collection_files.push(AssetCollectionFile {
+            path: asset_collection_file_path.to_owned(),
+            global: false,
+            priority,
+        });
+
+        self
+    }
+
+    /// Set the file ending of asset collection files in the given format
+    ///
+    /// The default endings are `assets` for RON, `assets.json` for JSON, `assets.toml` for TOML
+    /// and `assets.yaml` for YAML files.
+    /// ```edition2021
+    /// # use bevy_asset_loader::{AssetLoader, DynamicAssetFormat};
+    /// # use bevy::prelude::*;
+    /// # use bevy::asset::AssetPlugin;
+    /// # fn main() {
+    ///     let mut app = App::new();
+    /// #   app
+    /// #       .add_plugins(MinimalPlugins)
+    /// #       .add_plugin(AssetPlugin::default());
+    ///     AssetLoader::new(GameState::Loading)
+    ///         .continue_to_state(GameState::Menu)
+    ///         .with_asset_collection_file_ending(DynamicAssetFormat::Ron, "ron")
+    ///         .build(&mut app);
+    /// #   app
+    /// #       .add_state(GameState::Loading)
+    /// #       .set_runner(|mut app| app.schedule.run(&mut app.world))
+    /// #       .run();
+    /// # }
+    /// # #[derive(Clone, Eq, PartialEq, Debug, Hash)]
+    /// # enum GameState {
+    /// #     Loading,
+    /// #     Menu
+    /// # }
+    /// ```
+    #[cfg(feature = "dynamic_assets")]
+    pub fn with_asset_collection_file_ending(
+        mut self,
+        format: DynamicAssetFormat,
+        file_ending: &'static str,
+    ) -> Self {
+        self.asset_collection_file_endings
+            .insert(format, file_ending);
 
         self
     }
@@ -543,6 +1379,145 @@ where
             .on_update
             .with_system(systems::loading_state::<State, A>.exclusive_system());
         self.collection_count += 1;
+        self.collections.push(CollectionBuilder {
+            load: Box::new(A::load),
+            insert: Box::new(systems::insert_collection::<A>),
+        });
+
+        self
+    }
+
+    /// Add an instance of an [`AssetCollection`] to the [`AssetLoader`]
+    ///
+    /// The instance is inserted into the [`Collections`] resource under the given id.
+    /// While loading the instance, every key of the collection is looked up with the
+    /// given prefix. To share assets between instances, use
+    /// [`with_collection_instance_sharing_keys`](Self::with_collection_instance_sharing_keys).
+    /// ```edition2021,no_run
+    /// # use bevy_asset_loader::{AssetLoader, AssetCollection};
+    /// # use bevy::prelude::*;
+    /// # use bevy::asset::AssetPlugin;
+    /// # fn main() {
+    ///     let mut app = App::new();
+    /// #   app
+    /// #       .add_plugins(MinimalPlugins)
+    /// #       .add_plugin(AssetPlugin::default());
+    ///     AssetLoader::new(GameState::Loading)
+    ///         .continue_to_state(GameState::Menu)
+    ///         .with_collection_instance::<CharacterAssets, _>("knight".to_owned(), "knight.")
+    ///         .with_collection_instance::<CharacterAssets, _>("wizard".to_owned(), "wizard.")
+    ///         .build(&mut app);
+    /// #   app
+    /// #       .add_state(GameState::Loading)
+    /// #       .set_runner(|mut app| app.schedule.run(&mut app.world))
+    /// #       .run();
+    /// # }
+    /// # #[derive(Clone, Eq, PartialEq, Debug, Hash)]
+    /// # enum GameState {
+    /// #     Loading,
+    /// #     Menu
+    /// # }
+    /// #[derive(AssetCollection)]
+    /// pub struct CharacterAssets {
+    ///     // loads "knight.sprite" or "wizard.sprite"
+    ///     #[asset(key = "sprite")]
+    ///     pub sprite: Handle<Image>,
+    /// }
+    /// ```
+    pub fn with_collection_instance<A: AssetCollection, Id>(self, id: Id, key_prefix: &str) -> Self
+    where
+        Id: Clone + Hash + Eq + Send + Sync + 'static,
+    {
+        self.add_collection_instance::<A, Id>(
+            id,
+            KeyPrefix {
+                prefix: key_prefix.to_owned(),
+                shared_keys: false,
+            },
+        )
+    }
+
+    /// Add an instance of an [`AssetCollection`] that can share keys with other instances
+    ///
+    /// Like [`with_collection_instance`](Self::with_collection_instance), but keys without a
+    /// prefixed version fall back to the key itself.
+    /// ```edition2021,no_run
+    /// # use bevy_asset_loader::{AssetLoader, AssetCollection};
+    /// # use bevy::prelude::*;
+    /// # use bevy::asset::AssetPlugin;
+    /// # fn main() {
+    ///     let mut app = App::new();
+    /// #   app
+    /// #       .add_plugins(MinimalPlugins)
+    /// #       .add_plugin(AssetPlugin::default());
+    ///     AssetLoader::new(GameState::Loading)
+    ///         .continue_to_state(GameState::Menu)
+    ///         .with_collection_instance_sharing_keys::<CharacterAssets, _>("knight".to_owned(), "knight.")
+    ///         .with_collection_instance_sharing_keys::<CharacterAssets, _>("wizard".to_owned(), "wizard.")
+    ///         .build(&mut app);
+    /// #   app
+    /// #       .add_state(GameState::Loading)
+    /// #       .set_runner(|mut app| app.schedule.run(&mut app.world))
+    /// #       .run();
+    /// # }
+    /// # #[derive(Clone, Eq, PartialEq, Debug, Hash)]
+    /// # enum GameState {
+    /// #     Loading,
+    /// #     Menu
+    /// # }
+    /// #[derive(AssetCollection)]
+    /// pub struct CharacterAssets {
+    ///     // loads "knight.sprite" or "wizard.sprite"
+    ///     #[asset(key = "sprite")]
+    ///     pub sprite: Handle<Image>,
+    ///     // loads "knight.music" or "wizard.music" if defined and "music" otherwise
+    ///     #[asset(key = "music")]
+    ///     pub music: Handle<AudioSource>,
+    /// }
+    /// ```
+    pub fn with_collection_instance_sharing_keys<A: AssetCollection, Id>(
+        self,
+        id: Id,
+        key_prefix: &str,
+    ) -> Self
+    where
+        Id: Clone + Hash + Eq + Send + Sync + 'static,
+    {
+        self.add_collection_instance::<A, Id>(
+            id,
+            KeyPrefix {
+                prefix: key_prefix.to_owned(),
+                shared_keys: true,
+            },
+        )
+    }
+
+    fn add_collection_instance<A: AssetCollection, Id>(
+        mut self,
+        id: Id,
+        key_prefix: KeyPrefix,
+    ) -> Self
+    where
+        Id: Clone + Hash + Eq + Send + Sync + 'static,
+    {
+        let load_prefix = key_prefix.clone();
+        let insert_prefix = key_prefix.clone();
+        let insert_id = id.clone();
+        self.collections.push(CollectionBuilder {
+            load: Box::new(move |world| {
+                systems::load_collection_instance::<A>(world, &load_prefix)
+            }),
+            insert: Box::new(move |world| {
+                systems::insert_collection_instance::<A, Id>(world, &insert_id, &insert_prefix)
+            }),
+        });
+        self.on_update = self.on_update.with_system(
+            (move |world: &mut World| {
+                systems::loading_collection_instance::<State, A, Id>(world, &id, &key_prefix)
+            })
+            .exclusive_system(),
+        );
+        self.collection_count += 1;
 
         self
     }
@@ -550,7 +1525,7 @@ where
     /// Insert a map of asset keys with corresponding assets
     pub fn add_keys(mut self, mut keys: HashMap<String, DynamicAsset>) -> Self {
         keys.drain().for_each(|(key, value)| {
-            self.keys.insert(key, value);
+            self.keys.insert(key, Arc::new(value));
         });
 
         self
@@ -608,6 +1583,142 @@ where
         self
     }
 
+    /// Hot reload the asset collection files of this loading state
+    ///
+    /// Whenever an asset collection file or one of its includes changes after the loading state,
+    /// its keys are updated and all collections added with [`with_collection`](Self::with_collection)
+    /// are loaded and inserted again. The game stays in its current state.
+    ///
+    /// Bevy only reloads changed files if the [`AssetServer`](bevy::asset::AssetServer) watches for changes.
+    /// ```edition2021
+    /// # use bevy_asset_loader::{AssetLoader, AssetCollection};
+    /// # use bevy::prelude::*;
+    /// # use bevy::asset::AssetPlugin;
+    /// # fn main() {
+    ///     let mut app = App::new();
+    /// #   app
+    /// #       .add_plugins(MinimalPlugins)
+    /// #       .add_plugin(AssetPlugin::default());
+    ///     app.add_startup_system(watch_for_changes);
+    ///     AssetLoader::new(GameState::Loading)
+    ///         .continue_to_state(GameState::Menu)
+    ///         .with_asset_collection_file("dynamic_asset.assets")
+    ///         .with_collection::<ImageAssets>()
+    ///         .hot_reload_asset_collection_files()
+    ///         .build(&mut app);
+    /// # }
+    /// # #[derive(Clone, Eq, PartialEq, Debug, Hash)]
+    /// # enum GameState {
+    /// #     Loading,
+    /// #     Menu
+    /// # }
+    ///
+    /// fn watch_for_changes(asset_server: Res<AssetServer>) {
+    ///     asset_server.watch_for_changes().unwrap();
+    /// }
+    /// # #[derive(AssetCollection)]
+    /// # pub struct ImageAssets {
+    /// #     #[asset(key = "image.player")]
+    /// #     pub player: Handle<Image>,
+    /// # }
+    /// ```
+    #[cfg(feature = "dynamic_assets")]
+    pub fn hot_reload_asset_collection_files(mut self) -> Self {
+        self.hot_reload_asset_collection_files = true;
+
+        self
+    }
+
+    /// Rebuild the collections of the loading state whenever the [`CurrentLocale`] changes
+    ///
+    /// After the loading state, the keys of its asset collection files are resolved again and all
+    /// collections are loaded with the new locale and inserted in place. The game stays in its
+    /// current state.
+    /// ```edition2021
+    /// # use bevy_asset_loader::{AssetLoader, AssetCollection, CurrentLocale};
+    /// # use bevy::prelude::*;
+    /// # use bevy::asset::AssetPlugin;
+    /// # fn main() {
+    ///     let mut app = App::new();
+    /// #   app
+    /// #       .add_plugins(MinimalPlugins)
+    /// #       .add_plugin(AssetPlugin::default());
+    ///     AssetLoader::new(GameState::Loading)
+    ///         .continue_to_state(GameState::Menu)
+    ///         .with_collection::<VoiceAssets>()
+    ///         .reload_on_locale_change()
+    ///         .build(&mut app);
+    /// #   app
+    /// #       .insert_resource(CurrentLocale::new("en"))
+    /// #       .add_state(GameState::Loading)
+    /// #       .set_runner(|mut app| app.schedule.run(&mut app.world))
+    /// #       .run();
+    /// # }
+    /// # #[derive(Clone, Eq, PartialEq, Debug, Hash)]
+    /// # enum GameState {
+    /// #     Loading,
+    /// #     Menu
+    /// # }
+    /// # #[derive(AssetCollection)]
+    /// # pub struct VoiceAssets {
+    /// #     #[asset(path = "audio/plop.ogg", localized)]
+    /// #     pub intro: Handle<AudioSource>,
+    /// # }
+    /// ```
+    pub fn reload_on_locale_change(mut self) -> Self {
+        self.reload_on_locale_change = true;
+
+        self
+    }
+
+    /// Verify the files of all collections against an [`AssetManifest`] before inserting them
+    ///
+    /// If a file of a collection is not listed in the manifest or its content differs,
+    /// the collection is not inserted and an [`AssetManifestMismatch`] event lists all
+    /// mismatched files. The files are hashed while loading by the [`AssetManifestPlugin`],
+    /// which needs to be added before Bevy's `AssetPlugin`.
+    /// ```edition2021,no_run
+    /// # use bevy_asset_loader::{AssetLoader, AssetCollection, AssetManifest, AssetManifestPlugin};
+    /// # use bevy::prelude::*;
+    /// # use bevy::asset::AssetPlugin;
+    /// # fn main() {
+    ///     let mut app = App::new();
+    /// #   app
+    /// #       .add_plugins(MinimalPlugins)
+    /// #       .add_plugin(AssetManifestPlugin::default())
+    /// #       .add_plugin(AssetPlugin::default());
+    ///     let manifest = std::fs::read_to_string("assets.manifest")
+    ///         .expect("Failed to read the asset manifest")
+    ///         .parse::<AssetManifest>()
+    ///         .expect("Invalid asset manifest");
+    ///     AssetLoader::new(GameState::Loading)
+    ///         .continue_to_state(GameState::Menu)
+    ///         .with_collection::<AudioAssets>()
+    ///         .with_asset_manifest(manifest)
+    ///         .build(&mut app);
+    /// #   app
+    /// #       .add_state(GameState::Loading)
+    /// #       .set_runner(|mut app| app.schedule.run(&mut app.world))
+    /// #       .run();
+    /// # }
+    /// # #[derive(Clone, Eq, PartialEq, Debug, Hash)]
+    /// # enum GameState {
+    /// #     Loading,
+    /// #     Menu
+    /// # }
+    /// # #[derive(AssetCollection)]
+    /// # pub struct AudioAssets {
+    /// #     #[asset(path = "audio/background.ogg")]
+    /// #     pub background: Handle<AudioSource>,
+    /// # }
+    /// ```
+    #[cfg(feature = "asset_manifest")]
+    pub fn with_asset_manifest(mut self, manifest: AssetManifest) -> Self {
+        self.manifest = Some(manifest);
+
+        self
+    }
+
     /// Finish configuring the [`AssetLoader`]
     ///
     /// Calling this function is required to set up the asset loading.
@@ -657,9 +1768,16 @@ where
             app.world
                 .insert_resource(AssetLoaderConfiguration::<State>::default());
         }
+        #[cfg(feature = "asset_manifest")]
+        if self.manifest.is_some() && !app.world.contains_resource::<asset_manifest::LoadedFiles>()
+        {
+            panic!("Verifying files against an asset manifest requires the AssetManifestPlugin");
+        }
         let config = LoadingConfiguration {
             next: self.next_state.clone(),
             count: 0,
+            #[cfg(feature = "asset_manifest")]
+            manifest: self.manifest,
         };
         {
             let mut asset_loader_configuration = app
@@ -676,25 +1794,73 @@ where
             asset_loader_configuration
                 .asset_collection_files
                 .insert(self.loading_state.clone(), self.asset_collection_files);
+            #[cfg(feature = "dynamic_assets")]
+            if self.hot_reload_asset_collection_files {
+                asset_loader_configuration
+                    .hot_reload_states
+                    .insert(self.loading_state.clone());
+            }
+        }
+        let collections = Arc::new(self.collections);
+        #[cfg(feature = "dynamic_assets")]
+        if self.hot_reload_asset_collection_files {
+            app.add_system(
+                dynamic_asset::hot_reload_asset_collection_files(
+                    self.loading_state.clone(),
+                    collections.clone(),
+                )
+                .exclusive_system(),
+            );
         }
         #[cfg(feature = "dynamic_assets")]
         {
-            app.add_plugin(RonAssetPlugin::<DynamicAssetCollection>::new(&[
-                self.asset_collection_file_ending
-            ]));
+            dynamic_asset::add_asset_collection_loaders(app, &self.asset_collection_file_endings);
             self.on_enter = self.on_enter.with_system(
                 dynamic_asset::prepare_asset_keys::<State>
                     .exclusive_system()
                     .at_start(),
             );
+            let scope = dynamic_asset::asset_key_scope(&self.loading_state);
+            self.on_exit = self.on_exit.with_system(
+                (move |world: &mut World| {
+                    if let Some(mut asset_keys) = world.get_resource_mut::<AssetKeys>() {
+                        asset_keys.leave_scope(scope);
+                    }
+                })
+                .exclusive_system()
+                .at_end(),
+            );
         }
+        self.on_enter = self
+            .on_enter
+            .with_system(systems::enter_loading_state.exclusive_system());
         self.on_update = self
             .on_update
             .with_system(systems::phase::<State>.exclusive_system().at_end());
-        app.insert_resource(AssetKeys {
-            key_asset_map: self.keys,
-        });
-        app.add_system_set(self.on_enter)
+        app.world
+            .get_resource_or_insert_with(AssetKeys::default)
+            .key_asset_map
+            .extend(self.keys.drain().map(|(key, asset)| {
+                (
+                    key,
+                    KeyedAsset {
+                        asset,
+                        provenance: AssetKeyProvenance::Code,
+                    },
+                )
+            }));
+        app.add_plugin(CollectionComponentsPlugin);
+        if self.reload_on_locale_change {
+            app.add_system(
+                locale::reload_on_locale_change(self.loading_state.clone(), collections)
+                    .exclusive_system(),
+            );
+        }
+        #[cfg(feature = "render")]
+        derived_assets::init_derived_assets(app);
+        app.init_resource::<AssetVariables>()
+            .init_resource::<AssetQuality>()
+            .add_system_set(self.on_enter)
             .add_system_set(self.on_update)
             .add_system_set(self.on_exit);
     }
@@ -0,0 +1,371 @@
+use std::sync::Arc;
+
+use bevy::asset::{AssetServer, HandleUntyped, LoadState};
+use bevy::ecs::prelude::{State, World};
+use bevy::ecs::schedule::StateData;
+use bevy::utils::{HashMap, HashSet};
+
+#[cfg(feature = "dynamic_assets")]
+use crate::dynamic_asset::{
+    asset_key_scope, resolve_loaded_asset_collection_files, with_scoped_keys,
+    LoadingAssetCollection,
+};
+use crate::systems::fall_back_to_other_variants;
+use crate::CollectionBuilder;
+#[cfg(feature = "dynamic_assets")]
+use crate::{AssetLoaderConfiguration, KeyedAsset};
+
+/// Name of the asset variable that is replaced with the locale of the [`CurrentLocale`]
+pub(crate) const LOCALE_VARIABLE: &str = "locale";
+
+/// The locale used for localized assets
+///
+/// Localized assets are resolved with a fallback chain. For the locale `de-AT` with the fallback
+/// locale `en`, the chain is `de-AT` -> `de` -> `en`.
+///
+/// Fields with the `localized` attribute load their file from a folder named after the locale
+/// next to the file (`#[asset(path = "vo/intro.ogg", localized)]` loads `vo/de/intro.ogg`).
+/// Such paths start with the first locale of the chain that is in the
+/// [available locales](CurrentLocale::with_available_locales). If the file fails to load, the file
+/// of the next locale in the chain is loaded instead.
+/// Paths can also use the locale directly as the asset variable `{locale}` (see [`AssetVariables`](crate::AssetVariables)).
+///
+/// Asset collection files can define localized keys in their `#localized` section. For every key,
+/// the most specific locale of the chain that defines it wins.
+/// ```edition2021
+/// # use bevy::prelude::*;
+/// # use bevy_asset_loader::{AssetCollection, CurrentLocale};
+/// #[derive(AssetCollection)]
+/// struct VoiceAssets {
+///     #[asset(path = "vo/intro.ogg", localized)]
+///     intro: Handle<AudioSource>,
+/// }
+///
+/// # fn main() {
+/// App::new().insert_resource(
+///     CurrentLocale::new("de-AT")
+///         .with_fallback("en")
+///         .with_available_locales(&["de", "en"]),
+/// );
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct CurrentLocale {
+    locale: String,
+    fallback_locales: Vec<String>,
+    available_locales: Option<Vec<String>>,
+    /// Localized paths that failed to load
+    missing_variants: HashSet<String>,
+    /// The localized path of the next locale in the chain for every resolved localized path
+    fallbacks: HashMap<String, String>,
+}
+
+impl CurrentLocale {
+    /// Create a new [`CurrentLocale`] without fallback locales
+    pub fn new<L: Into<String>>(locale: L) -> Self {
+        CurrentLocale {
+            locale: locale.into(),
+            fallback_locales: vec![],
+            available_locales: None,
+            missing_variants: HashSet::default(),
+            fallbacks: HashMap::default(),
+        }
+    }
+
+    /// Add a locale to the end of the fallback chain
+    pub fn with_fallback<L: Into<String>>(mut self, locale: L) -> Self {
+        self.fallback_locales.push(locale.into());
+
+        self
+    }
+
+    /// Set the locales that have localized files for fields with the `localized` attribute
+    ///
+    /// Without available locales, localized paths try every locale of the fallback chain.
+    pub fn with_available_locales(mut self, locales: &[&str]) -> Self {
+        self.available_locales = Some(locales.iter().map(|&locale| locale.to_owned()).collect());
+
+        self
+    }
+
+    /// The current locale
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    /// Change the current locale
+    ///
+    /// See [`AssetLoader::reload_on_locale_change`](crate::AssetLoader::reload_on_locale_change)
+    /// to load the localized assets of the new locale.
+    pub fn set_locale<L: Into<String>>(&mut self, locale: L) {
+        self.locale = locale.into();
+    }
+
+    /// All locales to look up localized assets with, from the most to the least specific
+    ///
+    /// The current locale is followed by its parent locales (`de-AT` -> `de`) and the fallback locales.
+    pub fn fallback_chain(&self) -> Vec<String> {
+        let mut chain = vec![];
+        let mut locale = self.locale.as_str();
+        loop {
+            chain.push(locale.to_owned());
+            match locale.rfind('-') {
+                Some(index) => locale = &locale[..index],
+                None => break,
+            }
+        }
+        for fallback in self.fallback_locales.iter() {
+            if !chain.contains(fallback) {
+                chain.push(fallback.clone());
+            }
+        }
+
+        chain
+    }
+
+    /// The locale localized paths start with
+    pub fn path_locale(&self) -> String {
+        self.candidate_locales().swap_remove(0)
+    }
+
+    /// Locales of the fallback chain that localized files are loaded from, in order
+    fn candidate_locales(&self) -> Vec<String> {
+        let chain = self.fallback_chain();
+        let mut locales: Vec<String> = match self.available_locales.as_ref() {
+            Some(available_locales) => chain
+                .iter()
+                .filter(|locale| available_locales.contains(locale))
+                .cloned()
+                .collect(),
+            None => chain.clone(),
+        };
+        if locales.is_empty() {
+            locales.push(chain[0].clone());
+        }
+
+        locales
+    }
+
+    /// Resolve the path of the first locale whose file did not fail to load
+    pub(crate) fn resolve_variant<F: Fn(&str) -> String>(&mut self, resolve: F) -> String {
+        let paths: Vec<String> = self
+            .candidate_locales()
+            .iter()
+            .map(|locale| resolve(locale))
+            .collect();
+        for fallback in paths.windows(2) {
+            self.fallbacks
+                .insert(fallback[0].clone(), fallback[1].clone());
+        }
+        paths
+            .iter()
+            .find(|path| !self.missing_variants.contains(*path))
+            .unwrap_or_else(|| paths.last().unwrap())
+            .clone()
+    }
+
+    /// Mark the localized path as missing
+    ///
+    /// Returns `true` if the next locale of the chain can be loaded instead.
+    pub(crate) fn fall_back(&mut self, failed_path: &str) -> bool {
+        if !self.fallbacks.contains_key(failed_path) {
+            return false;
+        }
+
+        self.missing_variants.insert(failed_path.to_owned())
+    }
+}
+
+/// Progress of reloading the collections of a loading state for a new locale
+enum LocaleReload {
+    Watching,
+    #[cfg(feature = "dynamic_assets")]
+    ResolvingAssetKeys {
+        handles: HashMap<String, LoadingAssetCollection>,
+    },
+    LoadingCollections {
+        #[cfg(feature = "dynamic_assets")]
+        keys: HashMap<String, KeyedAsset>,
+        handles: Vec<HandleUntyped>,
+    },
+}
+
+/// Rebuild the collections of a loading state whenever the [`CurrentLocale`] changes
+///
+/// The keys of the asset collection files are resolved again and the collections are loaded and
+/// inserted in place. The state of the app is not changed. Changes during the loading state are
+/// picked up by the loading state itself.
+pub(crate) fn reload_on_locale_change<S: StateData>(
+    loading_state: S,
+    collections: Arc<Vec<CollectionBuilder>>,
+) -> impl FnMut(&mut World) {
+    #[cfg(feature = "dynamic_assets")]
+    let scope = asset_key_scope(&loading_state);
+    let mut last_locale: Option<String> = None;
+    let mut reload = LocaleReload::Watching;
+    move |world: &mut World| {
+        let locale = match world.get_resource::<CurrentLocale>() {
+            Some(locale) => locale.locale().to_owned(),
+            None => return,
+        };
+        let changed = matches!(last_locale.as_ref(), Some(last_locale) if *last_locale != locale);
+        last_locale = Some(locale);
+        {
+            let state = world
+                .get_resource::<State<S>>()
+                .expect("Cannot get State resource");
+            if *state.current() == loading_state {
+                reload = LocaleReload::Watching;
+                return;
+            }
+        }
+        if changed {
+            reload = start_reloading::<S>(world, &loading_state, &collections);
+        }
+
+        match &mut reload {
+            LocaleReload::Watching => (),
+            #[cfg(feature = "dynamic_assets")]
+            LocaleReload::ResolvingAssetKeys { handles } => {
+                let keys = {
+                    let cell = world.cell();
+                    let files = cell
+                        .get_resource_mut::<AssetLoaderConfiguration<S>>()
+                        .expect("Cannot get AssetLoaderConfiguration")
+                        .get_asset_collection_files(&loading_state);
+                    match resolve_loaded_asset_collection_files(&cell, files, handles) {
+                        Some(keys) => keys,
+                        None => return,
+                    }
+                };
+                let handles =
+                    with_scoped_keys(world, scope, &keys, |world| load(world, &collections));
+                reload = LocaleReload::LoadingCollections { keys, handles };
+            }
+            LocaleReload::LoadingCollections {
+                #[cfg(feature = "dynamic_assets")]
+                keys,
+                handles,
+            } => {
+                let load_state = world
+                    .get_resource::<AssetServer>()
+                    .expect("Cannot get AssetServer resource")
+                    .get_group_load_state(handles.iter().map(|handle| handle.id));
+                if load_state == LoadState::Failed
+                    && fall_back_to_other_variants(&world.cell(), handles)
+                {
+                    #[cfg(feature = "dynamic_assets")]
+                    {
+                        *handles =
+                            with_scoped_keys(world, scope, keys, |world| load(world, &collections));
+                    }
+                    #[cfg(not(feature = "dynamic_assets"))]
+                    {
+                        *handles = load(world, &collections);
+                    }
+                    return;
+                }
+                if load_state != LoadState::Loaded {
+                    return;
+                }
+                #[cfg(feature = "dynamic_assets")]
+                with_scoped_keys(world, scope, keys, |world| insert(world, &collections));
+                #[cfg(not(feature = "dynamic_assets"))]
+                insert(world, &collections);
+                reload = LocaleReload::Watching;
+            }
+        }
+    }
+}
+
+/// Start loading the asset collection files of the loading state or, without them, its collections
+#[allow(unused_variables)]
+fn start_reloading<S: StateData>(
+    world: &mut World,
+    loading_state: &S,
+    collections: &[CollectionBuilder],
+) -> LocaleReload {
+    #[cfg(feature = "dynamic_assets")]
+    {
+        let cell = world.cell();
+        let asset_server = cell
+            .get_resource::<AssetServer>()
+            .expect("Cannot get AssetServer resource");
+        let handles = cell
+            .get_resource_mut::<AssetLoaderConfiguration<S>>()
+            .expect("Cannot get AssetLoaderConfiguration")
+            .get_asset_collection_files(loading_state)
+            .into_iter()
+            .map(|file| {
+                let handle = asset_server.load(file.path.as_str());
+                (file.path, LoadingAssetCollection { handle })
+            })
+            .collect();
+        LocaleReload::ResolvingAssetKeys { handles }
+    }
+    #[cfg(not(feature = "dynamic_assets"))]
+    LocaleReload::LoadingCollections {
+        handles: load(world, collections),
+    }
+}
+
+fn load(world: &mut World, collections: &[CollectionBuilder]) -> Vec<HandleUntyped> {
+    collections
+        .iter()
+        .flat_map(|collection| (collection.load)(world))
+        .collect()
+}
+
+fn insert(world: &mut World, collections: &[CollectionBuilder]) {
+    for collection in collections.iter() {
+        (collection.insert)(world);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::CurrentLocale;
+
+    #[test]
+    fn fallback_chain() {
+        let locale = CurrentLocale::new("de-AT").with_fallback("en");
+
+        assert_eq!(locale.fallback_chain(), vec!["de-AT", "de", "en"]);
+        assert_eq!(
+            CurrentLocale::new("en-US")
+                .with_fallback("en")
+                .fallback_chain(),
+            vec!["en-US", "en"]
+        );
+    }
+
+    #[test]
+    fn path_locale_uses_available_locales() {
+        let locale = CurrentLocale::new("de-AT").with_fallback("en");
+        assert_eq!(locale.path_locale(), "de-AT");
+
+        let locale = locale.with_available_locales(&["en", "de"]);
+        assert_eq!(locale.path_locale(), "de");
+
+        let locale = CurrentLocale::new("fr")
+            .with_fallback("en")
+            .with_available_locales(&["en", "de"]);
+        assert_eq!(locale.path_locale(), "en");
+    }
+
+    #[test]
+    fn falls_back_along_the_chain_of_missing_files() {
+        let mut locale = CurrentLocale::new("de-AT").with_fallback("en");
+        let resolve = |locale: &str| format!("vo/{}/intro.ogg", locale);
+        assert_eq!(locale.resolve_variant(resolve), "vo/de-AT/intro.ogg");
+
+        assert!(locale.fall_back("vo/de-AT/intro.ogg"));
+        assert_eq!(locale.resolve_variant(resolve), "vo/de/intro.ogg");
+        assert!(locale.fall_back("vo/de/intro.ogg"));
+        assert_eq!(locale.resolve_variant(resolve), "vo/en/intro.ogg");
+
+        // there is no locale left to fall back to
+        assert!(!locale.fall_back("vo/en/intro.ogg"));
+        assert!(!locale.fall_back("vo/other.ogg"));
+    }
+}
@@ -0,0 +1,185 @@
+use bevy::utils::{HashMap, HashSet};
+
+/// Name of the asset variable that is replaced with the tier of the [`AssetQuality`]
+pub(crate) const QUALITY_VARIABLE: &str = "quality";
+
+/// Quality tiers of asset variants, from the lowest to the highest
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum QualityTier {
+    /// Variants in the folder `low`
+    Low,
+    /// Variants in the folder `medium`
+    Medium,
+    /// Variants in the folder `high`
+    High,
+}
+
+impl QualityTier {
+    /// Name of the folder containing the variants of this tier
+    pub fn folder_name(&self) -> &'static str {
+        match self {
+            QualityTier::Low => "low",
+            QualityTier::Medium => "medium",
+            QualityTier::High => "high",
+        }
+    }
+}
+
+/// The quality tier used for asset variants
+///
+/// Fields with the `variants` attribute load their file from a folder named after the tier
+/// next to the file (`#[asset(path = "tex/rock.png", variants)]` loads `tex/high/rock.png`).
+/// Paths in asset collection files and `path` attributes can use the tier directly as the asset
+/// variable `{quality}` (see [`AssetVariables`](crate::AssetVariables)).
+///
+/// If the file of the requested tier fails to load, the file of the next lower tier is loaded
+/// instead. Tiers without any variants can be skipped with
+/// [`with_available_tiers`](AssetQuality::with_available_tiers). A variant is never loaded
+/// from a higher tier than the requested one. Without an [`AssetQuality`] resource, the
+/// [`AssetLoader`](crate::AssetLoader) requests [`QualityTier::High`].
+/// ```edition2021
+/// # use bevy::prelude::*;
+/// # use bevy_asset_loader::{AssetCollection, AssetQuality, QualityTier};
+/// #[derive(AssetCollection)]
+/// struct TextureAssets {
+///     #[asset(path = "tex/rock.png", variants)]
+///     rock: Handle<Image>,
+/// }
+///
+/// # fn main() {
+/// // loads "tex/medium/rock.png"
+/// App::new().insert_resource(
+///     AssetQuality::new(QualityTier::High)
+///         .with_available_tiers(&[QualityTier::Low, QualityTier::Medium]),
+/// );
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct AssetQuality {
+    tier: QualityTier,
+    available_tiers: Vec<QualityTier>,
+    /// Variant paths that failed to load
+    missing_variants: HashSet<String>,
+    /// The variant path of the next lower tier for every resolved variant path
+    fallbacks: HashMap<String, String>,
+}
+
+impl Default for AssetQuality {
+    fn default() -> Self {
+        AssetQuality::new(QualityTier::High)
+    }
+}
+
+impl AssetQuality {
+    /// Create a new [`AssetQuality`] with all tiers available
+    pub fn new(tier: QualityTier) -> Self {
+        AssetQuality {
+            tier,
+            available_tiers: vec![QualityTier::Low, QualityTier::Medium, QualityTier::High],
+            missing_variants: HashSet::default(),
+            fallbacks: HashMap::default(),
+        }
+    }
+
+    /// Set the tiers that have variants
+    pub fn with_available_tiers(mut self, tiers: &[QualityTier]) -> Self {
+        self.available_tiers = tiers.to_vec();
+
+        self
+    }
+
+    /// The requested tier
+    pub fn tier(&self) -> QualityTier {
+        self.tier
+    }
+
+    /// Change the requested tier
+    ///
+    /// Collections that are already loaded keep their variants.
+    pub fn set_tier(&mut self, tier: QualityTier) {
+        self.tier = tier;
+    }
+
+    /// The tier used in paths of variants, before falling back to lower tiers
+    ///
+    /// This is the highest available tier that is not higher than the requested one.
+    /// If all available tiers are higher, the requested tier is used.
+    pub fn path_tier(&self) -> QualityTier {
+        self.candidate_tiers()[0]
+    }
+
+    /// Available tiers that are not higher than the requested one, from the highest to the lowest
+    fn candidate_tiers(&self) -> Vec<QualityTier> {
+        let mut tiers: Vec<QualityTier> = self
+            .available_tiers
+            .iter()
+            .copied()
+            .filter(|&tier| tier <= self.tier)
+            .collect();
+        tiers.sort_unstable_by(|a, b| b.cmp(a));
+        tiers.dedup();
+        if tiers.is_empty() {
+            tiers.push(self.tier);
+        }
+
+        tiers
+    }
+
+    /// Resolve a variant path with the highest tier whose file did not fail to load
+    pub(crate) fn resolve_variant<F: Fn(QualityTier) -> String>(&mut self, resolve: F) -> String {
+        let paths: Vec<String> = self.candidate_tiers().into_iter().map(resolve).collect();
+        for fallback in paths.windows(2) {
+            self.fallbacks
+                .insert(fallback[0].clone(), fallback[1].clone());
+        }
+        paths
+            .iter()
+            .find(|path| !self.missing_variants.contains(*path))
+            .unwrap_or_else(|| paths.last().unwrap())
+            .clone()
+    }
+
+    /// Remember that the given path failed to load
+    ///
+    /// Returns `true` if the path is a variant with a lower tier to fall back to.
+    pub(crate) fn fall_back(&mut self, failed_path: &str) -> bool {
+        if !self.fallbacks.contains_key(failed_path) {
+            return false;
+        }
+
+        self.missing_variants.insert(failed_path.to_owned())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{AssetQuality, QualityTier};
+
+    #[test]
+    fn uses_the_highest_available_tier() {
+        let quality = AssetQuality::new(QualityTier::High);
+        assert_eq!(quality.path_tier(), QualityTier::High);
+
+        let quality = quality.with_available_tiers(&[QualityTier::Low, QualityTier::Medium]);
+        assert_eq!(quality.path_tier(), QualityTier::Medium);
+
+        let quality = AssetQuality::new(QualityTier::Low)
+            .with_available_tiers(&[QualityTier::High, QualityTier::Medium]);
+        assert_eq!(quality.path_tier(), QualityTier::Low);
+    }
+
+    #[test]
+    fn falls_back_to_lower_tiers_of_missing_variants() {
+        let mut quality = AssetQuality::new(QualityTier::Medium);
+        let resolve = |tier: QualityTier| format!("tex/{}/rock.png", tier.folder_name());
+        assert_eq!(quality.resolve_variant(resolve), "tex/medium/rock.png");
+
+        assert!(quality.fall_back("tex/medium/rock.png"));
+        assert_eq!(quality.resolve_variant(resolve), "tex/low/rock.png");
+
+        // there is nothing lower to fall back to
+        assert!(!quality.fall_back("tex/low/rock.png"));
+        assert_eq!(quality.resolve_variant(resolve), "tex/low/rock.png");
+        assert!(!quality.fall_back("tex/other.png"));
+    }
+}
@@ -1,15 +1,89 @@
-#[cfg(feature = "dynamic_assets")]
-use bevy::asset::Assets;
-use bevy::asset::{AssetServer, LoadState};
+use bevy::asset::{AssetServer, HandleUntyped, LoadState};
+use bevy::ecs::component::Component;
+use bevy::ecs::entity::Entity;
 use bevy::ecs::prelude::{FromWorld, State, World};
 use bevy::ecs::schedule::StateData;
+use bevy::ecs::world::WorldCell;
+use bevy::utils::HashMap;
+use std::collections::BTreeSet;
+use std::hash::Hash;
 use std::marker::PhantomData;
 
 #[cfg(feature = "dynamic_assets")]
-use crate::dynamic_asset::DynamicAssetCollection;
-#[cfg(feature = "dynamic_assets")]
-use crate::AssetKeys;
-use crate::{AssetCollection, AssetLoaderConfiguration, LoadingAssetHandles, LoadingStatePhase};
+use crate::dynamic_asset::resolve_asset_keys;
+use crate::{
+    failed_paths, AssetCollection, AssetKeys, AssetLoaderConfiguration, AssetQuality, Collections,
+    CurrentLocale, KeyPrefix, Loading, LoadingAssetHandles, LoadingCollectionComponents,
+    LoadingFailed, LoadingStatePhase,
+};
+
+/// Loading states that did not finish yet and files that failed to load
+#[derive(Default)]
+pub(crate) struct LoadingProgress {
+    pub(crate) loading_states: usize,
+    pub(crate) failed: BTreeSet<String>,
+}
+
+/// Count an entered loading state until all its collections are loaded
+pub(crate) fn enter_loading_state(world: &mut World) {
+    world
+        .get_resource_or_insert_with(LoadingProgress::default)
+        .loading_states += 1;
+}
+
+/// Fall back to lower quality tiers and other locales for the variants that failed to load
+///
+/// Returns `true` if the collection of the handles needs to be loaded again.
+pub(crate) fn fall_back_to_other_variants(cell: &WorldCell, handles: &[HandleUntyped]) -> bool {
+    let mut quality = cell.get_resource_mut::<AssetQuality>();
+    let mut locale = cell.get_resource_mut::<CurrentLocale>();
+    if quality.is_none() && locale.is_none() {
+        return false;
+    }
+    let asset_server = cell
+        .get_resource::<AssetServer>()
+        .expect("Cannot get AssetServer resource");
+    let mut fell_back = false;
+    for handle in handles {
+        if asset_server.get_load_state(handle) != LoadState::Failed {
+            continue;
+        }
+        if let Some(path) = asset_server.get_handle_path(handle) {
+            let path = path.path().to_string_lossy().replace('\\', "/");
+            // lower tiers of the same locale are tried before other locales
+            fell_back |= quality
+                .as_mut()
+                .map(|quality| quality.fall_back(&path))
+                .unwrap_or(false)
+                || locale
+                    .as_mut()
+                    .map(|locale| locale.fall_back(&path))
+                    .unwrap_or(false);
+        }
+    }
+
+    fell_back
+}
+
+/// Remember the files of the handles that failed to load
+fn record_failures(cell: &WorldCell, handles: &[HandleUntyped]) {
+    let mut progress = cell
+        .get_resource_mut::<LoadingProgress>()
+        .expect("Cannot get LoadingProgress resource");
+    let asset_server = cell
+        .get_resource::<AssetServer>()
+        .expect("Cannot get AssetServer resource");
+    for handle in handles {
+        if asset_server.get_load_state(handle) != LoadState::Failed {
+            continue;
+        }
+        if let Some(path) = asset_server.get_handle_path(handle) {
+            progress
+                .failed
+                .insert(path.path().to_string_lossy().replace('\\', "/"));
+        }
+    }
+}
 
 pub(crate) fn init_resource<Asset: FromWorld + Send + Sync + 'static>(world: &mut World) {
     let asset = Asset::from_world(world);
@@ -39,23 +113,7 @@ pub(crate) fn loading_state<S: StateData, Assets: AssetCollection>(world: &mut W
 }
 
 fn start_loading_collections<S: StateData, Assets: AssetCollection>(world: &mut World) {
-    {
-        let cell = world.cell();
-        let mut asset_loader_configuration = cell
-            .get_resource_mut::<AssetLoaderConfiguration<S>>()
-            .expect("Cannot get AssetLoaderConfiguration");
-        let state = cell.get_resource::<State<S>>().expect("Cannot get state");
-        let mut config = asset_loader_configuration
-            .configuration
-            .get_mut(state.current())
-            .unwrap_or_else(|| {
-                panic!(
-                    "Could not find a loading configuration for state {:?}",
-                    state.current()
-                )
-            });
-        config.count += 1;
-    }
+    count_loading_collection::<S>(world);
     let handles = LoadingAssetHandles {
         handles: Assets::load(world),
         marker: PhantomData::<Assets>,
@@ -63,8 +121,13 @@ fn start_loading_collections<S: StateData, Assets: AssetCollection>(world: &mut
     world.insert_resource(handles);
 }
 
+pub(crate) fn insert_collection<Assets: AssetCollection>(world: &mut World) {
+    let asset_collection = Assets::create(world);
+    world.insert_resource(asset_collection);
+}
+
 fn check_loading_state<S: StateData, Assets: AssetCollection>(world: &mut World) {
-    {
+    let reload = {
         let cell = world.cell();
 
         let loading_asset_handles = cell.get_resource::<LoadingAssetHandles<Assets>>();
@@ -78,34 +141,55 @@ fn check_loading_state<S: StateData, Assets: AssetCollection>(world: &mut World)
             .expect("Cannot get AssetServer resource");
         let load_state = asset_server
             .get_group_load_state(loading_asset_handles.handles.iter().map(|handle| handle.id));
-        if load_state != LoadState::Loaded {
-            return;
-        }
-
-        let mut state = cell
-            .get_resource_mut::<State<S>>()
-            .expect("Cannot get State resource");
-        let mut asset_loader_configuration = cell
-            .get_resource_mut::<AssetLoaderConfiguration<S>>()
-            .expect("Cannot get AssetLoaderConfiguration resource");
-        if let Some(mut config) = asset_loader_configuration
-            .configuration
-            .get_mut(state.current())
-        {
-            config.count -= 1;
-            if config.count == 0 {
-                if let Some(next) = config.next.as_ref() {
-                    state.set(next.clone()).expect("Failed to set next State");
+        match load_state {
+            LoadState::Loaded => {
+                #[cfg(feature = "asset_manifest")]
+                if !crate::asset_manifest::verify_loaded_files::<S>(
+                    &cell,
+                    &loading_asset_handles.handles,
+                    std::any::type_name::<Assets>(),
+                ) {
+                    return;
                 }
+                false
+            }
+            LoadState::Failed
+                if fall_back_to_other_variants(&cell, &loading_asset_handles.handles) =>
+            {
+                true
+            }
+            LoadState::Failed => {
+                record_failures(&cell, &loading_asset_handles.handles);
+                return;
             }
+            _ => return,
         }
+    };
+    if reload {
+        let handles = LoadingAssetHandles {
+            handles: Assets::load(world),
+            marker: PhantomData::<Assets>,
+        };
+        world.insert_resource(handles);
+        return;
     }
-    let asset_collection = Assets::create(world);
-    world.insert_resource(asset_collection);
+    finish_loading_collection::<S>(world);
+    insert_collection::<Assets>(world);
     world.remove_resource::<LoadingAssetHandles<Assets>>();
 }
 
-pub(crate) fn phase<S: StateData>(world: &mut World) {
+struct LoadingCollectionInstances<A: AssetCollection, Id> {
+    handles: HashMap<Id, Vec<HandleUntyped>>,
+    marker: PhantomData<A>,
+}
+
+pub(crate) fn loading_collection_instance<S: StateData, Assets: AssetCollection, Id>(
+    world: &mut World,
+    id: &Id,
+    key_prefix: &KeyPrefix,
+) where
+    Id: Clone + Hash + Eq + Send + Sync + 'static,
+{
     let phase = {
         let cell = world.cell();
         let asset_loader_configuration = cell
@@ -119,51 +203,281 @@ pub(crate) fn phase<S: StateData>(world: &mut World) {
             .clone()
     };
 
+    #[allow(unreachable_patterns)]
     match phase {
-        #[cfg(feature = "dynamic_assets")]
-        LoadingStatePhase::PreparingAssetKeys => {
-            let cell = world.cell();
-            let asset_server = cell
-                .get_resource::<AssetServer>()
-                .expect("Cannot get AssetServer resource");
-            let mut asset_loader_configuration = cell
-                .get_resource_mut::<AssetLoaderConfiguration<S>>()
-                .expect("Cannot get AssetLoaderConfiguration");
-            let load_state = asset_server.get_group_load_state(
-                asset_loader_configuration
-                    .asset_collection_handles
-                    .iter()
-                    .map(|handle| handle.id),
-            );
-            if load_state == LoadState::Loaded {
-                let mut dynamic_asset_collections = cell
-                    .get_resource_mut::<Assets<DynamicAssetCollection>>()
+        LoadingStatePhase::StartLoading => {
+            count_loading_collection::<S>(world);
+            let handles = load_collection_instance::<Assets>(world, key_prefix);
+            world
+                .get_resource_or_insert_with(|| LoadingCollectionInstances::<Assets, Id> {
+                    handles: HashMap::default(),
+                    marker: PhantomData,
+                })
+                .handles
+                .insert(id.clone(), handles);
+        }
+        LoadingStatePhase::Loading => {
+            let reload = {
+                let cell = world.cell();
+                let loading_instances =
+                    cell.get_resource::<LoadingCollectionInstances<Assets, Id>>();
+                let handles = match loading_instances
+                    .as_ref()
+                    .and_then(|loading_instances| loading_instances.handles.get(id))
+                {
+                    Some(handles) => handles,
+                    None => return,
+                };
+                let asset_server = cell
+                    .get_resource::<AssetServer>()
                     .expect("Cannot get AssetServer resource");
-                let state = cell.get_resource::<State<S>>().expect("Cannot get state");
-
-                let mut asset_keys = cell.get_resource_mut::<AssetKeys>().unwrap();
-                // Todo: why add the manual dynamic assets to all loaded collections?
-                for collection in asset_loader_configuration
-                    .asset_collection_handles
-                    .drain(..)
+                let load_state =
+                    asset_server.get_group_load_state(handles.iter().map(|handle| handle.id));
+                match load_state {
+                    LoadState::Loaded => {
+                        #[cfg(feature = "asset_manifest")]
+                        if !crate::asset_manifest::verify_loaded_files::<S>(
+                            &cell,
+                            handles,
+                            std::any::type_name::<Assets>(),
+                        ) {
+                            return;
+                        }
+                        false
+                    }
+                    LoadState::Failed if fall_back_to_other_variants(&cell, handles) => true,
+                    LoadState::Failed => {
+                        record_failures(&cell, handles);
+                        return;
+                    }
+                    _ => return,
+                }
+            };
+            if reload {
+                let handles = load_collection_instance::<Assets>(world, key_prefix);
+                if let Some(mut loading_instances) =
+                    world.get_resource_mut::<LoadingCollectionInstances<Assets, Id>>()
                 {
-                    let collection = dynamic_asset_collections.remove(collection).unwrap();
-                    collection.apply(&mut asset_keys);
+                    loading_instances.handles.insert(id.clone(), handles);
                 }
-                asset_loader_configuration
-                    .phase
-                    .insert(state.current().clone(), LoadingStatePhase::StartLoading);
+                return;
+            }
+            finish_loading_collection::<S>(world);
+            insert_collection_instance::<Assets, Id>(world, id, key_prefix);
+            if let Some(mut loading_instances) =
+                world.get_resource_mut::<LoadingCollectionInstances<Assets, Id>>()
+            {
+                loading_instances.handles.remove(id);
             }
         }
+        _ => {}
+    }
+}
+
+pub(crate) fn load_collection_instance<Assets: AssetCollection>(
+    world: &mut World,
+    key_prefix: &KeyPrefix,
+) -> Vec<HandleUntyped> {
+    with_key_prefix(world, key_prefix, Assets::load)
+}
+
+pub(crate) fn insert_collection_instance<Assets: AssetCollection, Id>(
+    world: &mut World,
+    id: &Id,
+    key_prefix: &KeyPrefix,
+) where
+    Id: Clone + Hash + Eq + Send + Sync + 'static,
+{
+    let asset_collection = with_key_prefix(world, key_prefix, Assets::create);
+    world
+        .get_resource_or_insert_with(Collections::<Assets, Id>::default)
+        .insert(id.clone(), asset_collection);
+}
+
+fn with_key_prefix<T>(world: &mut World, key_prefix: &KeyPrefix, f: fn(&mut World) -> T) -> T {
+    world
+        .get_resource_mut::<AssetKeys>()
+        .expect("Cannot get AssetKeys")
+        .set_key_prefix(Some(key_prefix.clone()));
+    let result = f(world);
+    world
+        .get_resource_mut::<AssetKeys>()
+        .expect("Cannot get AssetKeys")
+        .set_key_prefix(None);
+    result
+}
+
+fn count_loading_collection<S: StateData>(world: &mut World) {
+    let cell = world.cell();
+    let mut asset_loader_configuration = cell
+        .get_resource_mut::<AssetLoaderConfiguration<S>>()
+        .expect("Cannot get AssetLoaderConfiguration");
+    let state = cell.get_resource::<State<S>>().expect("Cannot get state");
+    let config = asset_loader_configuration
+        .configuration
+        .get_mut(state.current())
+        .unwrap_or_else(|| {
+            panic!(
+                "Could not find a loading configuration for state {:?}",
+                state.current()
+            )
+        });
+    config.count += 1;
+}
+
+fn finish_loading_collection<S: StateData>(world: &mut World) {
+    let cell = world.cell();
+    let mut state = cell
+        .get_resource_mut::<State<S>>()
+        .expect("Cannot get State resource");
+    let mut asset_loader_configuration = cell
+        .get_resource_mut::<AssetLoaderConfiguration<S>>()
+        .expect("Cannot get AssetLoaderConfiguration resource");
+    if let Some(config) = asset_loader_configuration
+        .configuration
+        .get_mut(state.current())
+    {
+        config.count -= 1;
+        if config.count == 0 {
+            finish_loading_state(&cell, &mut state, config.next.as_ref());
+        }
+    }
+}
+
+/// Continue to the next state once all collections of a loading state are loaded
+fn finish_loading_state<S: StateData>(cell: &WorldCell, state: &mut State<S>, next: Option<&S>) {
+    if let Some(mut progress) = cell.get_resource_mut::<LoadingProgress>() {
+        progress.loading_states = progress.loading_states.saturating_sub(1);
+    }
+    if let Some(next) = next {
+        state.set(next.clone()).expect("Failed to set next State");
+    }
+}
+
+pub(crate) fn load_collection_components(world: &mut World) {
+    let mut loaded = vec![];
+    let mut failed = vec![];
+    let mut reload = vec![];
+    {
+        let cell = world.cell();
+        let mut loading_collections = cell
+            .get_resource_mut::<LoadingCollectionComponents>()
+            .expect("Cannot get LoadingCollectionComponents");
+        if loading_collections.0.is_empty() {
+            return;
+        }
+        let asset_server = cell
+            .get_resource::<AssetServer>()
+            .expect("Cannot get AssetServer resource");
+        let mut loading = vec![];
+        for collection in loading_collections.0.drain(..) {
+            match asset_server
+                .get_group_load_state(collection.handles.iter().map(|handle| handle.id))
+            {
+                LoadState::Loaded => loaded.push(collection),
+                LoadState::Failed if fall_back_to_other_variants(&cell, &collection.handles) => {
+                    reload.push(collection)
+                }
+                LoadState::Failed => {
+                    record_failures(&cell, &collection.handles);
+                    let paths = failed_paths(&asset_server, &collection.handles);
+                    failed.push((collection, paths));
+                }
+                _ => loading.push(collection),
+            }
+        }
+        loading_collections.0 = loading;
+    }
+    for loaded in loaded {
+        (loaded.insert)(world, loaded.entity);
+    }
+    for (failed, paths) in failed {
+        (failed.fail)(world, failed.entity, paths);
+    }
+    for mut collection in reload {
+        collection.handles = (collection.load)(world);
+        world
+            .get_resource_mut::<LoadingCollectionComponents>()
+            .expect("Cannot get LoadingCollectionComponents")
+            .0
+            .push(collection);
+    }
+}
+
+pub(crate) fn insert_collection_component<Assets: AssetCollection + Component>(
+    world: &mut World,
+    entity: Entity,
+) {
+    let is_loading = world
+        .get_entity(entity)
+        .map(|entity| entity.contains::<Loading<Assets>>())
+        .unwrap_or(false);
+    if !is_loading {
+        return;
+    }
+    let asset_collection = Assets::create(world);
+    let mut entity = world.entity_mut(entity);
+    entity.remove::<Loading<Assets>>();
+    entity.insert(asset_collection);
+}
+
+pub(crate) fn fail_collection_component<Assets: AssetCollection + Component>(
+    world: &mut World,
+    entity: Entity,
+    paths: Vec<String>,
+) {
+    let is_loading = world
+        .get_entity(entity)
+        .map(|entity| entity.contains::<Loading<Assets>>())
+        .unwrap_or(false);
+    if !is_loading {
+        return;
+    }
+    let mut entity = world.entity_mut(entity);
+    entity.remove::<Loading<Assets>>();
+    entity.insert(LoadingFailed::<Assets> {
+        paths,
+        marker: PhantomData,
+    });
+}
+
+pub(crate) fn phase<S: StateData>(world: &mut World) {
+    let phase = {
+        let cell = world.cell();
+        let asset_loader_configuration = cell
+            .get_resource::<AssetLoaderConfiguration<S>>()
+            .expect("Cannot get AssetLoaderConfiguration");
+        let state = cell.get_resource::<State<S>>().expect("Cannot get state");
+        asset_loader_configuration
+            .phase
+            .get(state.current())
+            .unwrap()
+            .clone()
+    };
+
+    match phase {
+        #[cfg(feature = "dynamic_assets")]
+        LoadingStatePhase::PreparingAssetKeys => resolve_asset_keys::<S>(world),
         LoadingStatePhase::StartLoading => {
             let cell = world.cell();
             let mut asset_loader_configuration = cell
                 .get_resource_mut::<AssetLoaderConfiguration<S>>()
                 .expect("Cannot get AssetLoaderConfiguration");
-            let state = cell.get_resource::<State<S>>().expect("Cannot get state");
+            let mut state = cell
+                .get_resource_mut::<State<S>>()
+                .expect("Cannot get state");
             asset_loader_configuration
                 .phase
                 .insert(state.current().clone(), LoadingStatePhase::Loading);
+            // without any collections, the loading state is done right away
+            let next = asset_loader_configuration
+                .configuration
+                .get(state.current())
+                .filter(|config| config.count == 0)
+                .map(|config| config.next.clone());
+            if let Some(next) = next {
+                finish_loading_state(&cell, &mut state, next.as_ref());
+            }
         }
         _ => (),
     }
@@ -0,0 +1,377 @@
+//! Helpers for testing asset collections without the file system
+//!
+//! The [`MemoryAssetIo`] serves files from memory. Every file can be delayed by a number of frames
+//! or fail to load. Together with [`run_until_loaded`], loading states, failures, and timeouts
+//! can be tested without real asset files.
+//! ```edition2021
+//! # use bevy::prelude::*;
+//! # use bevy::asset::AssetPlugin;
+//! # use bevy::audio::AudioPlugin;
+//! # use bevy_asset_loader::{AssetCollection, AssetLoader};
+//! use bevy_asset_loader::test_support::{
+//!     run_until_loaded, MemoryAssetIo, MemoryAssetIoPlugin, MemoryFile, RunUntilLoadedError,
+//! };
+//!
+//! # fn main() {
+//! let io = MemoryAssetIo::default();
+//! io.insert("audio/plop.ogg", MemoryFile::new(*b"plop").with_delay(10));
+//!
+//! let mut app = App::new();
+//! app.add_plugins(MinimalPlugins)
+//!     .add_plugin(MemoryAssetIoPlugin::new(&io))
+//!     .add_plugin(AssetPlugin)
+//!     .add_plugin(AudioPlugin);
+//! AssetLoader::new(MyStates::Load)
+//!     .continue_to_state(MyStates::Next)
+//!     .with_collection::<AudioAssets>()
+//!     .build(&mut app);
+//! app.add_state(MyStates::Load);
+//!
+//! assert_eq!(
+//!     run_until_loaded(&mut app, 5),
+//!     Err(RunUntilLoadedError::Timeout { frames: 5 })
+//! );
+//! assert!(run_until_loaded(&mut app, 100).is_ok());
+//! assert!(app.world.contains_resource::<AudioAssets>());
+//! # }
+//! # #[derive(AssetCollection)]
+//! # struct AudioAssets {
+//! #     #[asset(path = "audio/plop.ogg")]
+//! #     plop: Handle<AudioSource>,
+//! # }
+//! # #[derive(Clone, Eq, PartialEq, Debug, Hash)]
+//! # enum MyStates {
+//! #     Load,
+//! #     Next,
+//! # }
+//! ```
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex, RwLock};
+use std::task::{Context, Poll, Waker};
+
+use bevy::app::{App, CoreStage, Plugin};
+use bevy::asset::{AssetIo, AssetIoError, AssetServer};
+use bevy::tasks::{TaskPool, TaskPoolBuilder};
+use bevy::utils::{BoxedFuture, HashMap};
+
+use crate::systems::LoadingProgress;
+use crate::LoadingCollectionComponents;
+
+/// An [`AssetIo`] serving files from memory
+///
+/// Clones share the same files, so files can be changed after the
+/// [`MemoryAssetIoPlugin`] was added.
+#[derive(Clone, Default)]
+pub struct MemoryAssetIo {
+    inner: Arc<MemoryAssetIoInner>,
+}
+
+#[derive(Default)]
+struct MemoryAssetIoInner {
+    files: RwLock<HashMap<PathBuf, MemoryFile>>,
+    frame: AtomicUsize,
+    waiting: Mutex<Vec<Waker>>,
+}
+
+/// A file of a [`MemoryAssetIo`]
+#[derive(Clone, Debug)]
+pub struct MemoryFile {
+    content: Option<Vec<u8>>,
+    delay: usize,
+}
+
+impl MemoryFile {
+    /// A file with the given content
+    pub fn new<B: Into<Vec<u8>>>(bytes: B) -> Self {
+        MemoryFile {
+            content: Some(bytes.into()),
+            delay: 0,
+        }
+    }
+
+    /// A file that exists, but fails to be read
+    pub fn failing() -> Self {
+        MemoryFile {
+            content: None,
+            delay: 0,
+        }
+    }
+
+    /// Finish reading the file the given number of frames after it was requested
+    pub fn with_delay(mut self, frames: usize) -> Self {
+        self.delay = frames;
+
+        self
+    }
+}
+
+impl MemoryAssetIo {
+    /// Add a file or replace it
+    pub fn insert<P: AsRef<Path>>(&self, path: P, file: MemoryFile) {
+        self.inner
+            .files
+            .write()
+            .unwrap()
+            .insert(normalize(path.as_ref()), file);
+    }
+
+    /// Remove a file
+    pub fn remove<P: AsRef<Path>>(&self, path: P) {
+        self.inner
+            .files
+            .write()
+            .unwrap()
+            .remove(&normalize(path.as_ref()));
+    }
+
+    /// Number of frames since the [`MemoryAssetIoPlugin`] was added
+    pub fn frame(&self) -> usize {
+        self.inner.frame.load(Ordering::SeqCst)
+    }
+
+    fn advance_frame(&self) {
+        self.inner.frame.fetch_add(1, Ordering::SeqCst);
+        for waker in self.inner.waiting.lock().unwrap().drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+impl AssetIo for MemoryAssetIo {
+    fn load_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+        let file = self
+            .inner
+            .files
+            .read()
+            .unwrap()
+            .get(&normalize(path))
+            .cloned();
+        let until = self.frame() + file.as_ref().map(|file| file.delay).unwrap_or(0);
+        Box::pin(async move {
+            let file = file.ok_or_else(|| AssetIoError::NotFound(path.to_path_buf()))?;
+            FrameDelay {
+                io: self.inner.clone(),
+                until,
+            }
+            .await;
+            file.content.ok_or_else(|| {
+                AssetIoError::Io(io::Error::other(format!(
+                    "The memory file '{}' is failing",
+                    path.display()
+                )))
+            })
+        })
+    }
+
+    fn read_directory(
+        &self,
+        path: &Path,
+    ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
+        let path = normalize(path);
+        let children: BTreeSet<PathBuf> = self
+            .inner
+            .files
+            .read()
+            .unwrap()
+            .keys()
+            .filter_map(|file| {
+                let relative = file.strip_prefix(&path).ok()?;
+                relative
+                    .components()
+                    .next()
+                    .map(|child| path.join(child.as_os_str()))
+            })
+            .collect();
+        if children.is_empty() {
+            return Err(AssetIoError::NotFound(path));
+        }
+
+        Ok(Box::new(children.into_iter()))
+    }
+
+    fn is_directory(&self, path: &Path) -> bool {
+        let path = normalize(path);
+        self.inner
+            .files
+            .read()
+            .unwrap()
+            .keys()
+            .any(|file| file != &path && file.starts_with(&path))
+    }
+
+    fn watch_path_for_changes(&self, _path: &Path) -> Result<(), AssetIoError> {
+        Ok(())
+    }
+
+    fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        Ok(())
+    }
+}
+
+/// Resolves once the [`MemoryAssetIo`] reached the given frame
+struct FrameDelay {
+    io: Arc<MemoryAssetIoInner>,
+    until: usize,
+}
+
+impl Future for FrameDelay {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<()> {
+        let mut waiting = self.io.waiting.lock().unwrap();
+        if self.io.frame.load(Ordering::SeqCst) >= self.until {
+            return Poll::Ready(());
+        }
+        waiting.push(context.waker().clone());
+        Poll::Pending
+    }
+}
+
+/// Path without leading `./` components
+fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| matches!(component, Component::Normal(_)))
+        .collect()
+}
+
+/// Serve all assets of the app from a [`MemoryAssetIo`]
+///
+/// The plugin replaces the [`AssetServer`] and needs to be added before Bevy's `AssetPlugin`.
+///
+/// Assets are loaded one after the other on a single background thread. At the end of every
+/// frame, the plugin advances the frame of the [`MemoryAssetIo`] and waits for all loads that
+/// can make progress. The number of updates needed to load a file therefore only depends on its
+/// delay and not on the machine running the test.
+pub struct MemoryAssetIoPlugin {
+    io: MemoryAssetIo,
+    #[cfg(feature = "asset_manifest")]
+    hash_files: bool,
+}
+
+impl MemoryAssetIoPlugin {
+    /// Create a plugin serving the files of the given [`MemoryAssetIo`]
+    pub fn new(io: &MemoryAssetIo) -> Self {
+        MemoryAssetIoPlugin {
+            io: io.clone(),
+            #[cfg(feature = "asset_manifest")]
+            hash_files: false,
+        }
+    }
+
+    /// Hash the files while they are loaded, like the [`AssetManifestPlugin`](crate::AssetManifestPlugin)
+    #[cfg(feature = "asset_manifest")]
+    pub fn with_asset_manifest(mut self) -> Self {
+        self.hash_files = true;
+
+        self
+    }
+}
+
+impl Plugin for MemoryAssetIoPlugin {
+    fn build(&self, app: &mut App) {
+        if app.world.contains_resource::<AssetServer>() {
+            panic!("The MemoryAssetIoPlugin needs to be added before the AssetPlugin");
+        }
+        let task_pool = TaskPoolBuilder::new()
+            .num_threads(1)
+            .thread_name("MemoryAssetIo".to_owned())
+            .build();
+        #[allow(unused_mut)]
+        let mut asset_io: Box<dyn AssetIo> = Box::new(self.io.clone());
+        #[cfg(feature = "asset_manifest")]
+        if self.hash_files {
+            asset_io = crate::asset_manifest::hash_loaded_files(app, asset_io);
+        }
+        let io = self.io.clone();
+        app.insert_resource(AssetServer::with_boxed_io(asset_io, task_pool.clone()))
+            .add_system_to_stage(CoreStage::Last, move || {
+                io.advance_frame();
+                finish_ready_tasks(&task_pool);
+            });
+    }
+}
+
+/// Block until every task of the pool that can make progress was polled
+///
+/// The pool runs its tasks in order on a single thread, so once a task spawned now is done,
+/// all tasks woken before it were polled.
+fn finish_ready_tasks(task_pool: &TaskPool) {
+    let (sender, receiver) = mpsc::channel();
+    task_pool
+        .spawn(async move {
+            let _ = sender.send(());
+        })
+        .detach();
+    receiver
+        .recv()
+        .expect("The task pool of the MemoryAssetIo stopped");
+}
+
+/// Reasons for [`run_until_loaded`] to stop before everything was loaded
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RunUntilLoadedError {
+    /// Collections were still loading after the given number of frames
+    Timeout {
+        /// Number of frames the app was updated
+        frames: usize,
+    },
+    /// Files of a loading collection failed to load
+    Failed {
+        /// Paths of all failed files
+        paths: Vec<String>,
+    },
+}
+
+impl fmt::Display for RunUntilLoadedError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunUntilLoadedError::Timeout { frames } => write!(
+                formatter,
+                "Collections were still loading after {} frames",
+                frames
+            ),
+            RunUntilLoadedError::Failed { paths } => {
+                write!(formatter, "Failed to load {}", paths.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for RunUntilLoadedError {}
+
+/// Update the app until all loading states finished loading their collections
+///
+/// Collections loaded as components are waited for as well. Returns the number of updates.
+/// With the [`MemoryAssetIoPlugin`], the number of updates only depends on the delays of the
+/// [`MemoryFile`]s. Assets served by other [`AssetIo`]s load in the background and may take
+/// any number of updates.
+pub fn run_until_loaded(app: &mut App, max_frames: usize) -> Result<usize, RunUntilLoadedError> {
+    app.world
+        .get_resource_or_insert_with(LoadingProgress::default);
+    for frame in 1..=max_frames {
+        app.update();
+        let progress = app.world.get_resource::<LoadingProgress>().unwrap();
+        if !progress.failed.is_empty() {
+            return Err(RunUntilLoadedError::Failed {
+                paths: progress.failed.iter().cloned().collect(),
+            });
+        }
+        let loading_components = app
+            .world
+            .get_resource::<LoadingCollectionComponents>()
+            .map(|components| !components.0.is_empty())
+            .unwrap_or(false);
+        if progress.loading_states == 0 && !loading_components {
+            return Ok(frame);
+        }
+    }
+
+    Err(RunUntilLoadedError::Timeout { frames: max_frames })
+}
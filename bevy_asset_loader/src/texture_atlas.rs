@@ -0,0 +1,803 @@
+use std::fmt;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use bevy::app::{App, Plugin};
+use bevy::asset::{
+    AddAsset, AssetLoader, AssetServer, Assets, Handle, HandleUntyped, LoadContext, LoadedAsset,
+};
+use bevy::math::Vec2;
+use bevy::reflect::TypeUuid;
+use bevy::render::texture::Image;
+use bevy::sprite::{Rect, TextureAtlas, TextureAtlasBuilder};
+use bevy::utils::{BoxedFuture, HashMap};
+use serde::de::{MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+
+/// A texture atlas together with the indices of its named textures
+///
+/// Texture atlases stitched from a folder of images name every texture after the path of its
+/// image relative to the folder, without the file extension. Atlases built from a [`PackedTextureAtlasLayout`] use the frame names of the
+/// layout file. A field of this type can be used everywhere a `Handle<TextureAtlas>` would work.
+/// ```edition2021
+/// # use bevy_asset_loader::{AssetCollection, NamedTextureAtlas};
+/// # use bevy::prelude::*;
+/// #[derive(AssetCollection)]
+/// struct MyAssets {
+///     #[asset(texture_atlas(folder = "images/player"))]
+///     player: NamedTextureAtlas,
+/// }
+///
+/// fn spawn_player(mut commands: Commands, my_assets: Res<MyAssets>) {
+///     commands.spawn_bundle(SpriteSheetBundle {
+///         sprite: TextureAtlasSprite::new(my_assets.player.index("idle").unwrap()),
+///         texture_atlas: my_assets.player.atlas.clone(),
+///         ..Default::default()
+///     });
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct NamedTextureAtlas {
+    /// Handle to the texture atlas
+    pub atlas: Handle<TextureAtlas>,
+    /// Indices of the textures in the atlas by name
+    pub indices: HashMap<String, usize>,
+    /// Animations defined together with the atlas
+    pub animations: AnimationClips,
+}
+
+impl NamedTextureAtlas {
+    /// Get the index of the texture with the given name
+    pub fn index(&self, name: &str) -> Option<usize> {
+        self.indices.get(name).copied()
+    }
+
+    /// Get the animation clip with the given name
+    pub fn animation(&self, name: &str) -> Option<&AnimationClip> {
+        self.animations.get(name)
+    }
+
+    /// Set the animations of this atlas
+    ///
+    /// Fails if an animation clip is empty, has a frame rate of zero or less, or ends after the last
+    /// texture of the atlas.
+    pub fn with_animations(
+        mut self,
+        animations: AnimationClips,
+        atlases: &Assets<TextureAtlas>,
+    ) -> Result<Self, String> {
+        let len = atlases
+            .get(&self.atlas)
+            .map(|atlas| atlas.len())
+            .unwrap_or_default();
+        for (name, clip) in animations.iter() {
+            clip.check()
+                .map_err(|error| format!("The animation '{}' is invalid: {}", name, error))?;
+            if clip.end > len {
+                return Err(format!(
+                    "The animation '{}' with frames {:?} does not fit a texture atlas with {} textures",
+                    name,
+                    clip.frames(),
+                    len
+                ));
+            }
+        }
+        self.animations = animations;
+        Ok(self)
+    }
+
+    /// Stitch all loaded images into a new texture atlas
+    ///
+    /// Every image is named after the file stem of its asset path. Handles that do not
+    /// point to a loaded image are ignored. Fails if the images do not fit into one atlas
+    /// or two images have the same name.
+    pub fn from_images(
+        handles: Vec<HandleUntyped>,
+        asset_server: &AssetServer,
+        images: &mut Assets<Image>,
+        atlases: &mut Assets<TextureAtlas>,
+    ) -> Result<Self, String> {
+        NamedTextureAtlas::stitch(handles, asset_server, images, atlases, |path| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+        })
+    }
+
+    /// Stitch all images inside the given asset folder into a new texture atlas
+    ///
+    /// The folder is loaded with the [`AssetServer`], which returns the existing handles of
+    /// images that are already loaded. Every image is named after its path relative to the folder
+    /// without the file extension, so `walk/01.png` and `jump/01.png` are the textures
+    /// `walk/01` and `jump/01`. See [`NamedTextureAtlas::from_images`].
+    pub fn from_folder(
+        folder: &str,
+        asset_server: &AssetServer,
+        images: &mut Assets<Image>,
+        atlases: &mut Assets<TextureAtlas>,
+    ) -> Result<Self, String> {
+        let mut handles = asset_server
+            .load_folder(folder)
+            .map_err(|error| error.to_string())?;
+        handles.sort_by_key(|handle| {
+            asset_server
+                .get_handle_path(handle)
+                .map(|path| path.path().to_path_buf())
+        });
+        let folder = Path::new(folder);
+        NamedTextureAtlas::stitch(handles, asset_server, images, atlases, |path| {
+            let relative = path.strip_prefix(folder).ok()?.with_extension("");
+            Some(relative.to_string_lossy().replace('\\', "/"))
+        })
+    }
+
+    fn stitch(
+        handles: Vec<HandleUntyped>,
+        asset_server: &AssetServer,
+        images: &mut Assets<Image>,
+        atlases: &mut Assets<TextureAtlas>,
+        name: impl Fn(&Path) -> Option<String>,
+    ) -> Result<Self, String> {
+        let mut builder = TextureAtlasBuilder::default();
+        let mut names: HashMap<String, (PathBuf, Handle<Image>)> = HashMap::default();
+        for handle in handles {
+            let handle = handle.typed::<Image>();
+            let image = if let Some(image) = images.get(&handle) {
+                image
+            } else {
+                continue;
+            };
+            builder.add_texture(handle.clone(), image);
+            let path = match asset_server.get_handle_path(&handle) {
+                Some(path) => path.path().to_path_buf(),
+                None => continue,
+            };
+            if let Some(name) = name(&path) {
+                if let Some((other, _)) = names.get(&name) {
+                    return Err(format!(
+                        "The images '{}' and '{}' are both named '{}'",
+                        other.display(),
+                        path.display(),
+                        name
+                    ));
+                }
+                names.insert(name, (path, handle));
+            }
+        }
+        let atlas = builder.finish(images).map_err(|error| error.to_string())?;
+        let indices = names
+            .drain()
+            .filter_map(|(name, (_, handle))| {
+                atlas.get_texture_index(&handle).map(|index| (name, index))
+            })
+            .collect();
+
+        Ok(NamedTextureAtlas {
+            atlas: atlases.add(atlas),
+            indices,
+            ..Default::default()
+        })
+    }
+
+    /// Stitch the images of a texture atlas into a new texture atlas
+    ///
+    /// Every image keeps its index, so the indices of a [`NamedTextureAtlas`] stay valid.
+    pub(crate) fn restitch(
+        atlas: &TextureAtlas,
+        images: &mut Assets<Image>,
+    ) -> Result<TextureAtlas, String> {
+        let handles = atlas
+            .texture_handles
+            .as_ref()
+            .ok_or_else(|| "The texture atlas was not stitched from images".to_owned())?;
+        let mut builder = TextureAtlasBuilder::default();
+        for handle in handles.keys() {
+            let image = images
+                .get(handle)
+                .ok_or_else(|| format!("The image {:?} is not loaded", handle.id))?;
+            builder.add_texture(handle.clone(), image);
+        }
+        let mut rebuilt = builder.finish(images).map_err(|error| error.to_string())?;
+        let mut textures = rebuilt.textures.clone();
+        for (handle, &index) in handles {
+            if let Some(rebuilt_index) = rebuilt.get_texture_index(handle) {
+                textures[index] = rebuilt.textures[rebuilt_index];
+            }
+        }
+        rebuilt.textures = textures;
+        rebuilt.texture_handles = Some(handles.clone());
+
+        Ok(rebuilt)
+    }
+
+    /// Create a new texture atlas from the frames of a packed layout
+    ///
+    /// If the layout does not define the size of the sprite sheet, the size of the loaded image is used.
+    /// Fails if neither the layout defines the size, nor the image is loaded.
+    pub fn from_layout(
+        texture: Handle<Image>,
+        layout: &PackedTextureAtlasLayout,
+        images: &Assets<Image>,
+        atlases: &mut Assets<TextureAtlas>,
+    ) -> Result<Self, String> {
+        let size = match layout.size {
+            Some(size) => size,
+            None => {
+                let image = images.get(&texture).ok_or_else(|| {
+                    "The layout does not define the size of the sprite sheet and its image is not loaded"
+                        .to_owned()
+                })?;
+                Vec2::new(
+                    image.texture_descriptor.size.width as f32,
+                    image.texture_descriptor.size.height as f32,
+                )
+            }
+        };
+        let mut atlas = TextureAtlas::new_empty(texture, size);
+        let indices = layout
+            .frames
+            .iter()
+            .map(|(name, rect)| (name.clone(), atlas.add_texture(*rect)))
+            .collect();
+
+        Ok(NamedTextureAtlas {
+            atlas: atlases.add(atlas),
+            indices,
+            ..Default::default()
+        })
+    }
+}
+
+/// Named animation clips of a texture atlas
+///
+/// The clips can be defined in the `animations` section of a `texture_atlas` attribute
+/// ```edition2021
+/// # use bevy_asset_loader::{AssetCollection, NamedTextureAtlas};
+/// # use bevy::prelude::*;
+/// #[derive(AssetCollection)]
+/// struct MyAssets {
+///     #[asset(texture_atlas(
+///         tile_size_x = 96., tile_size_y = 99., columns = 8, rows = 2,
+///         animations(walk(start = 0, end = 8, fps = 10.), jump(start = 8, end = 12, fps = 12.))
+///     ))]
+///     #[asset(path = "images/player_sheet.png")]
+///     player: NamedTextureAtlas,
+/// }
+///
+/// fn start_walking(my_assets: Res<MyAssets>, mut sprites: Query<&mut TextureAtlasSprite>) {
+///     let walk = my_assets.player.animation("walk").unwrap();
+///     for mut sprite in sprites.iter_mut() {
+///         sprite.index = walk.start;
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "dynamic_assets",
+    derive(serde::Deserialize),
+    serde(transparent)
+)]
+pub struct AnimationClips(HashMap<String, AnimationClip>);
+
+impl AnimationClips {
+    /// Get the animation clip with the given name
+    pub fn get(&self, name: &str) -> Option<&AnimationClip> {
+        self.0.get(name)
+    }
+
+    /// Add an animation clip
+    pub fn insert(&mut self, name: impl Into<String>, clip: AnimationClip) {
+        self.0.insert(name.into(), clip);
+    }
+
+    /// Iterate over all animation clips and their names
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &AnimationClip)> {
+        self.0.iter()
+    }
+
+    /// Returns `true` if there are no animation clips
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// A range of frames in a texture atlas played with a fixed frame rate
+///
+/// Clips deserialized from asset collection files are checked to have frames and a positive frame rate.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "dynamic_assets",
+    derive(serde::Deserialize),
+    serde(try_from = "AnimationClipFields")
+)]
+pub struct AnimationClip {
+    /// Index of the first frame
+    pub start: usize,
+    /// Index after the last frame
+    pub end: usize,
+    /// Frames per second
+    pub fps: f32,
+}
+
+impl AnimationClip {
+    /// Indices of all frames of the clip
+    pub fn frames(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// Number of frames in the clip
+    pub fn len(&self) -> usize {
+        self.end.saturating_sub(self.start)
+    }
+
+    /// Returns `true` if the clip has no frames
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Duration of a single frame in seconds
+    pub fn frame_duration(&self) -> f32 {
+        1. / self.fps
+    }
+
+    fn check(&self) -> Result<(), String> {
+        if self.is_empty() {
+            return Err(format!(
+                "An animation clip needs to end after its start, but has the frames {:?}",
+                self.frames()
+            ));
+        }
+        if self.fps <= 0. {
+            return Err(format!(
+                "The frame rate of an animation clip needs to be larger than zero, but is {}",
+                self.fps
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// The fields of an [`AnimationClip`] before they are checked
+#[cfg(feature = "dynamic_assets")]
+#[derive(serde::Deserialize)]
+struct AnimationClipFields {
+    start: usize,
+    end: usize,
+    fps: f32,
+}
+
+#[cfg(feature = "dynamic_assets")]
+impl TryFrom<AnimationClipFields> for AnimationClip {
+    type Error = String;
+
+    fn try_from(fields: AnimationClipFields) -> Result<Self, Self::Error> {
+        let clip = AnimationClip {
+            start: fields.start,
+            end: fields.end,
+            fps: fields.fps,
+        };
+        clip.check()?;
+        Ok(clip)
+    }
+}
+
+/// Grid of equally sized tiles on a sprite sheet
+///
+/// Compared to [`TextureAtlas::from_grid_with_padding`], the grid can start at an offset
+/// from the top left corner of the sprite sheet and can be partially filled.
+#[derive(Clone, Debug)]
+pub struct TextureAtlasGrid {
+    /// Size of a single tile in pixels
+    pub tile_size: Vec2,
+    /// Columns on the sprite sheet
+    pub columns: usize,
+    /// Rows on the sprite sheet
+    pub rows: usize,
+    /// Padding between columns and rows in pixels
+    pub padding: Vec2,
+    /// Position of the first tile in pixels
+    pub offset: Vec2,
+    /// Number of tiles in the grid
+    ///
+    /// Tiles are counted row by row. If `None`, all `columns * rows` tiles are part of the atlas.
+    pub count: Option<usize>,
+}
+
+impl TextureAtlasGrid {
+    /// Create a new texture atlas with the tiles of this grid
+    ///
+    /// The size of the atlas is the size of the loaded image. If the image is not loaded,
+    /// the sprite sheet is assumed to end with the last column and row of the grid.
+    pub fn build(&self, texture: Handle<Image>, images: &Assets<Image>) -> TextureAtlas {
+        let size = images
+            .get(&texture)
+            .map(|image| {
+                Vec2::new(
+                    image.texture_descriptor.size.width as f32,
+                    image.texture_descriptor.size.height as f32,
+                )
+            })
+            .unwrap_or_else(|| {
+                self.offset
+                    + Vec2::new(
+                        (self.tile_size.x + self.padding.x) * self.columns as f32 - self.padding.x,
+                        (self.tile_size.y + self.padding.y) * self.rows as f32 - self.padding.y,
+                    )
+            });
+        let mut atlas = TextureAtlas::new_empty(texture, size);
+        let count = self
+            .count
+            .unwrap_or(usize::MAX)
+            .min(self.columns * self.rows);
+        for index in 0..count {
+            let min = self.offset
+                + Vec2::new(
+                    (index % self.columns) as f32 * (self.tile_size.x + self.padding.x),
+                    (index / self.columns) as f32 * (self.tile_size.y + self.padding.y),
+                );
+            atlas.add_texture(Rect {
+                min,
+                max: min + self.tile_size,
+            });
+        }
+
+        atlas
+    }
+}
+
+impl From<NamedTextureAtlas> for Handle<TextureAtlas> {
+    fn from(named_atlas: NamedTextureAtlas) -> Self {
+        named_atlas.atlas
+    }
+}
+
+/// Plugin adding support for [packed texture atlas layouts](PackedTextureAtlasLayout)
+///
+/// The plugin registers an asset loader for `.atlas.json` files containing frame rectangles
+/// in the format exported by TexturePacker and Aseprite. It requires the `AssetPlugin`.
+/// ```edition2021
+/// # use bevy_asset_loader::{AssetCollection, AssetLoader, NamedTextureAtlas, PackedTextureAtlasPlugin};
+/// # use bevy::prelude::*;
+/// # use bevy::asset::AssetPlugin;
+/// # fn main() {
+///     let mut app = App::new();
+///     app
+/// #       .add_plugins(MinimalPlugins)
+/// #       .add_plugin(AssetPlugin::default())
+/// # /*
+///         .add_plugins(DefaultPlugins)
+/// # */
+///         .add_plugin(PackedTextureAtlasPlugin);
+///     AssetLoader::new(GameState::Loading)
+///         .continue_to_state(GameState::Menu)
+///         .with_collection::<MyAssets>()
+///         .build(&mut app);
+/// #   app
+/// #       .add_state(GameState::Loading)
+/// #       .set_runner(|mut app| app.schedule.run(&mut app.world))
+/// #       .run();
+/// # }
+/// # #[derive(Clone, Eq, PartialEq, Debug, Hash)]
+/// # enum GameState {
+/// #     Loading,
+/// #     Menu
+/// # }
+///
+/// #[derive(AssetCollection)]
+/// struct MyAssets {
+///     #[asset(texture_atlas(layout = "images/character.atlas.json"))]
+///     #[asset(path = "images/character.png")]
+///     character: NamedTextureAtlas,
+/// }
+/// ```
+pub struct PackedTextureAtlasPlugin;
+
+impl Plugin for PackedTextureAtlasPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<PackedTextureAtlasLayout>()
+            .add_asset_loader(PackedTextureAtlasLayoutLoader);
+    }
+}
+
+/// Named frames of a sprite sheet loaded from a TexturePacker or Aseprite JSON file
+///
+/// Both the "hash" and the "array" variant of the JSON format are supported.
+/// The frames keep the order of the layout file.
+#[derive(Debug, Clone, TypeUuid)]
+#[uuid = "b5ae8b33-9fce-4a1b-8ec7-8f4e6a7c1a9d"]
+pub struct PackedTextureAtlasLayout {
+    /// Names and areas of all frames on the sprite sheet
+    pub frames: Vec<(String, Rect)>,
+    /// Size of the sprite sheet, if defined in the layout file
+    pub size: Option<Vec2>,
+}
+
+impl PackedTextureAtlasLayout {
+    /// Read a layout from a TexturePacker or Aseprite JSON file
+    pub(crate) fn from_json(bytes: &[u8]) -> Result<Self, String> {
+        serde_json::from_slice::<LayoutFile>(bytes)
+            .map_err(|error| error.to_string())?
+            .into_layout()
+    }
+}
+
+struct PackedTextureAtlasLayoutLoader;
+
+impl AssetLoader for PackedTextureAtlasLayoutLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let layout = PackedTextureAtlasLayout::from_json(bytes)
+                .map_err(|error| anyhow::anyhow!("{} in '{:?}'", error, load_context.path()))?;
+            load_context.set_default_asset(LoadedAsset::new(layout));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["atlas.json"]
+    }
+}
+
+#[derive(Deserialize)]
+struct LayoutFile {
+    frames: Frames,
+    meta: Option<Meta>,
+}
+
+impl LayoutFile {
+    fn into_layout(self) -> Result<PackedTextureAtlasLayout, String> {
+        let mut frames = vec![];
+        for (name, frame) in self.frames.0 {
+            if frame.rotated {
+                return Err(format!(
+                    "The frame '{}' is rotated, which is not supported",
+                    name
+                ));
+            }
+            let min = Vec2::new(frame.frame.x, frame.frame.y);
+            frames.push((
+                name,
+                Rect {
+                    min,
+                    max: min + Vec2::new(frame.frame.w, frame.frame.h),
+                },
+            ));
+        }
+
+        Ok(PackedTextureAtlasLayout {
+            frames,
+            size: self
+                .meta
+                .and_then(|meta| meta.size)
+                .map(|size| Vec2::new(size.w, size.h)),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct Frame {
+    filename: Option<String>,
+    frame: FrameRect,
+    #[serde(default)]
+    rotated: bool,
+}
+
+#[derive(Deserialize)]
+struct FrameRect {
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+}
+
+#[derive(Deserialize)]
+struct Meta {
+    size: Option<Size>,
+}
+
+#[derive(Deserialize)]
+struct Size {
+    w: f32,
+    h: f32,
+}
+
+/// Frames of a layout file in the order they are listed
+///
+/// The frames are either a map from frame name to frame data ("hash" format),
+/// or a list of frames that each contain a `filename` ("array" format).
+struct Frames(Vec<(String, Frame)>);
+
+impl<'de> Deserialize<'de> for Frames {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(FramesVisitor)
+    }
+}
+
+struct FramesVisitor;
+
+impl<'de> Visitor<'de> for FramesVisitor {
+    type Value = Frames;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map of named frames or a list of frames with file names")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut frames = vec![];
+        while let Some(frame) = seq.next_element::<Frame>()? {
+            let name = frame
+                .filename
+                .clone()
+                .ok_or_else(|| serde::de::Error::missing_field("filename"))?;
+            frames.push((name, frame));
+        }
+        Ok(Frames(frames))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut frames = vec![];
+        while let Some((name, frame)) = map.next_entry::<String, Frame>()? {
+            frames.push((name, frame));
+        }
+        Ok(Frames(frames))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::asset::AssetPlugin;
+    use bevy::MinimalPlugins;
+
+    #[test]
+    fn reads_hash_layouts() {
+        let layout = PackedTextureAtlasLayout::from_json(
+            br#"{
+                "frames": {
+                    "walk_0": { "frame": { "x": 0, "y": 0, "w": 16, "h": 32 } },
+                    "jump_0": { "frame": { "x": 16, "y": 0, "w": 24, "h": 32 }, "rotated": false }
+                },
+                "meta": { "size": { "w": 64, "h": 32 } }
+            }"#,
+        )
+        .unwrap();
+
+        let frames: Vec<_> = layout
+            .frames
+            .iter()
+            .map(|(name, rect)| (name.as_str(), rect.min, rect.max))
+            .collect();
+        assert_eq!(
+            frames,
+            vec![
+                ("walk_0", Vec2::new(0., 0.), Vec2::new(16., 32.)),
+                ("jump_0", Vec2::new(16., 0.), Vec2::new(40., 32.))
+            ]
+        );
+        assert_eq!(layout.size, Some(Vec2::new(64., 32.)));
+    }
+
+    #[test]
+    fn reads_array_layouts() {
+        let layout = PackedTextureAtlasLayout::from_json(
+            br#"{
+                "frames": [
+                    { "filename": "walk_0", "frame": { "x": 0, "y": 0, "w": 16, "h": 32 } },
+                    { "filename": "jump_0", "frame": { "x": 16, "y": 0, "w": 24, "h": 32 } }
+                ],
+                "meta": { "size": { "w": 64, "h": 32 } }
+            }"#,
+        )
+        .unwrap();
+
+        let names: Vec<_> = layout
+            .frames
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        assert_eq!(names, vec!["walk_0", "jump_0"]);
+        assert_eq!(layout.size, Some(Vec2::new(64., 32.)));
+    }
+
+    #[test]
+    fn array_frames_need_a_file_name() {
+        assert!(PackedTextureAtlasLayout::from_json(
+            br#"{ "frames": [ { "frame": { "x": 0, "y": 0, "w": 16, "h": 32 } } ] }"#,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn rejects_rotated_frames() {
+        let error = PackedTextureAtlasLayout::from_json(
+            br#"{
+                "frames": {
+                    "walk_0": { "frame": { "x": 0, "y": 0, "w": 16, "h": 32 }, "rotated": true }
+                }
+            }"#,
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            error,
+            "The frame 'walk_0' is rotated, which is not supported"
+        );
+    }
+
+    #[test]
+    fn size_is_optional() {
+        let without_meta = PackedTextureAtlasLayout::from_json(
+            br#"{ "frames": { "walk_0": { "frame": { "x": 0, "y": 0, "w": 16, "h": 32 } } } }"#,
+        )
+        .unwrap();
+        let without_size = PackedTextureAtlasLayout::from_json(
+            br#"{
+                "frames": { "walk_0": { "frame": { "x": 0, "y": 0, "w": 16, "h": 32 } } },
+                "meta": { "app": "https://www.aseprite.org/" }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(without_meta.size, None);
+        assert_eq!(without_size.size, None);
+    }
+
+    #[test]
+    fn atlas_without_size_needs_a_loaded_image() {
+        let layout = PackedTextureAtlasLayout {
+            frames: vec![],
+            size: None,
+        };
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(AssetPlugin)
+            .add_asset::<Image>()
+            .add_asset::<TextureAtlas>();
+        let cell = app.world.cell();
+        let images = cell.get_resource::<Assets<Image>>().unwrap();
+        let mut atlases = cell.get_resource_mut::<Assets<TextureAtlas>>().unwrap();
+
+        assert!(
+            NamedTextureAtlas::from_layout(Handle::default(), &layout, &images, &mut atlases)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn grid_with_offset_padding_and_count() {
+        let grid = TextureAtlasGrid {
+            tile_size: Vec2::new(16., 8.),
+            columns: 3,
+            rows: 2,
+            padding: Vec2::new(2., 1.),
+            offset: Vec2::new(4., 3.),
+            count: Some(4),
+        };
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(AssetPlugin)
+            .add_asset::<Image>();
+        let images = app.world.get_resource::<Assets<Image>>().unwrap();
+
+        let atlas = grid.build(Handle::default(), images);
+
+        let rects: Vec<_> = atlas
+            .textures
+            .iter()
+            .map(|rect| (rect.min, rect.max))
+            .collect();
+        assert_eq!(
+            rects,
+            vec![
+                (Vec2::new(4., 3.), Vec2::new(20., 11.)),
+                (Vec2::new(22., 3.), Vec2::new(38., 11.)),
+                (Vec2::new(40., 3.), Vec2::new(56., 11.)),
+                (Vec2::new(4., 12.), Vec2::new(20., 20.)),
+            ]
+        );
+        assert_eq!(atlas.size, Vec2::new(56., 20.));
+    }
+}
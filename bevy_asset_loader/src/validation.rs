@@ -0,0 +1,298 @@
+use std::any::type_name;
+use std::collections::{BTreeSet, VecDeque};
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+use image::ImageError;
+
+use crate::asset_references::{collect_files, file_format, read_asset_collection_file};
+use crate::{AnimationClips, AssetCollection, DynamicAsset, PackedTextureAtlasLayout};
+
+/// Checks asset collection files and asset collections against the files of an asset folder
+///
+/// The validator reports
+/// * asset collection files that cannot be parsed or include missing files
+/// * dynamic assets that reference files which do not exist
+/// * texture atlases that do not fit the dimensions of their image
+/// * files in the asset folder that are not used by any of the checked files or collections
+///
+/// Paths with [asset variables](crate::AssetVariables) and custom dynamic asset types are not checked.
+/// This is what the `bevy_asset_loader` command line tool (feature `cli`) runs.
+/// ```edition2021,no_run
+/// # use bevy_asset_loader::AssetValidator;
+/// let report = AssetValidator::new("assets")
+///     .with_all_asset_collection_files()
+///     .validate();
+/// assert!(report.is_ok(), "{}", report);
+/// ```
+pub struct AssetValidator {
+    asset_folder: PathBuf,
+    asset_collection_files: Vec<String>,
+    collections: Vec<(&'static str, Vec<(String, DynamicAsset)>)>,
+}
+
+/// Problems found by an [`AssetValidator`]
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    /// Descriptions of all errors
+    pub errors: Vec<String>,
+    /// Files in the asset folder that are not used by any checked file or collection
+    ///
+    /// The paths are relative to the asset folder.
+    pub unused_files: Vec<String>,
+}
+
+impl ValidationReport {
+    /// Returns `true` if no errors were found
+    ///
+    /// Unused files are not considered errors.
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for error in self.errors.iter() {
+            writeln!(formatter, "error: {}", error)?;
+        }
+        for file in self.unused_files.iter() {
+            writeln!(formatter, "unused: {}", file)?;
+        }
+        write!(
+            formatter,
+            "{} error(s), {} unused file(s)",
+            self.errors.len(),
+            self.unused_files.len()
+        )
+    }
+}
+
+impl AssetValidator {
+    /// Create a validator for the given asset folder
+    pub fn new<P: Into<PathBuf>>(asset_folder: P) -> Self {
+        AssetValidator {
+            asset_folder: asset_folder.into(),
+            asset_collection_files: vec![],
+            collections: vec![],
+        }
+    }
+
+    /// Check the asset collection file at the given path relative to the asset folder
+    ///
+    /// Included files are checked as well.
+    pub fn with_asset_collection_file(mut self, path: &str) -> Self {
+        self.asset_collection_files.push(path.to_owned());
+
+        self
+    }
+
+    /// Check all asset collection files in the asset folder
+    pub fn with_all_asset_collection_files(mut self) -> Self {
+        let mut files = vec![];
+        collect_files(&self.asset_folder, &self.asset_folder, &mut files);
+        self.asset_collection_files
+            .extend(files.drain(..).filter(|file| file_format(file).is_some()));
+
+        self
+    }
+
+    /// Check the fields with a path of a derived [`AssetCollection`]
+    ///
+    /// See [`AssetCollection::dynamic_assets`].
+    pub fn with_collection<A: AssetCollection>(mut self) -> Self {
+        self.collections
+            .push((type_name::<A>(), A::dynamic_assets()));
+
+        self
+    }
+
+    /// Run all checks
+    pub fn validate(&self) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        let mut used_files = BTreeSet::new();
+
+        let mut queue: VecDeque<String> = self.asset_collection_files.iter().cloned().collect();
+        let mut checked_files = BTreeSet::new();
+        while let Some(file) = queue.pop_front() {
+            if !checked_files.insert(file.clone()) {
+                continue;
+            }
+            used_files.insert(file.clone());
+            let collection = match read_asset_collection_file(&self.asset_folder, &file) {
+                Ok(collection) => collection,
+                Err(error) => {
+                    report.errors.push(format!("{}: {}", file, error));
+                    continue;
+                }
+            };
+            for include in collection.includes {
+                if self.asset_folder.join(&include).is_file() {
+                    queue.push_back(include);
+                } else {
+                    report.errors.push(format!(
+                        "{}: the included file '{}' does not exist",
+                        file, include
+                    ));
+                }
+            }
+            let localized = collection
+                .localized
+                .into_iter()
+                .flat_map(|(_, assets)| assets);
+            for (key, asset) in collection.assets.into_iter().chain(localized) {
+                if let Some(asset) = asset {
+                    self.validate_asset(&file, &key, &asset, &mut used_files, &mut report);
+                }
+            }
+        }
+        for (collection, assets) in self.collections.iter() {
+            for (field, asset) in assets.iter() {
+                self.validate_asset(collection, field, asset, &mut used_files, &mut report);
+            }
+        }
+
+        let mut files = vec![];
+        collect_files(&self.asset_folder, &self.asset_folder, &mut files);
+        report.unused_files = files
+            .drain(..)
+            .filter(|file| {
+                !used_files
+                    .iter()
+                    .any(|used| file == used || file.starts_with(&format!("{}/", used)))
+            })
+            .collect();
+
+        report
+    }
+
+    fn validate_asset(
+        &self,
+        source: &str,
+        key: &str,
+        asset: &DynamicAsset,
+        used_files: &mut BTreeSet<String>,
+        report: &mut ValidationReport,
+    ) {
+        let mut files_exist = true;
+        for path in asset.get_file_paths() {
+            // labels are not part of the file path
+            let path = path.split('#').next().unwrap_or_default();
+            if path.contains('{') {
+                files_exist = false;
+                continue;
+            }
+            used_files.insert(path.trim_end_matches('/').to_owned());
+            if !self.asset_folder.join(path).exists() {
+                files_exist = false;
+                report.errors.push(format!(
+                    "{}: '{}' references the missing file '{}'",
+                    source, key, path
+                ));
+            }
+        }
+        if !files_exist {
+            return;
+        }
+        if let Err(error) = self.validate_texture_atlas(asset) {
+            report
+                .errors
+                .push(format!("{}: '{}' {}", source, key, error));
+        }
+    }
+
+    fn validate_texture_atlas(&self, asset: &DynamicAsset) -> Result<(), String> {
+        match asset {
+            DynamicAsset::TextureAtlas {
+                path,
+                tile_size_x,
+                tile_size_y,
+                columns,
+                rows,
+                padding_x,
+                padding_y,
+                offset_x,
+                offset_y,
+                count,
+                animations,
+            } => {
+                let tiles = columns * rows;
+                if let Some(count) = count {
+                    if *count > tiles {
+                        return Err(format!(
+                            "has {} tiles, but its grid only has {}",
+                            count, tiles
+                        ));
+                    }
+                }
+                let (width, height) = match self.image_dimensions(path)? {
+                    Some(dimensions) => dimensions,
+                    None => return Ok(()),
+                };
+                let needed_width = offset_x.unwrap_or(0.)
+                    + (*tile_size_x + padding_x.unwrap_or(0.)) * *columns as f32
+                    - padding_x.unwrap_or(0.);
+                let needed_height = offset_y.unwrap_or(0.)
+                    + (*tile_size_y + padding_y.unwrap_or(0.)) * *rows as f32
+                    - padding_y.unwrap_or(0.);
+                if needed_width > width || needed_height > height {
+                    return Err(format!(
+                        "needs {}x{} pixels for {} columns and {} rows, but '{}' has {}x{} pixels",
+                        needed_width, needed_height, columns, rows, path, width, height
+                    ));
+                }
+                validate_animations(animations, count.unwrap_or(tiles).min(tiles))
+            }
+            DynamicAsset::PackedTextureAtlas {
+                path,
+                layout,
+                animations,
+            } => {
+                let bytes = fs::read(self.asset_folder.join(layout)).map_err(|error| {
+                    format!("failed to read the layout '{}': {}", layout, error)
+                })?;
+                let layout = PackedTextureAtlasLayout::from_json(&bytes)
+                    .map_err(|error| format!("has an invalid layout: {}", error))?;
+                if let Some((width, height)) = self.image_dimensions(path)? {
+                    if let Some((name, _)) = layout
+                        .frames
+                        .iter()
+                        .find(|(_, rect)| rect.max.x > width || rect.max.y > height)
+                    {
+                        return Err(format!(
+                            "has the frame '{}' outside of '{}' with {}x{} pixels",
+                            name, path, width, height
+                        ));
+                    }
+                }
+                validate_animations(animations, layout.frames.len())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Dimensions of an image or `None` if the image format is not supported
+    fn image_dimensions(&self, path: &str) -> Result<Option<(f32, f32)>, String> {
+        match image::image_dimensions(self.asset_folder.join(path)) {
+            Ok((width, height)) => Ok(Some((width as f32, height as f32))),
+            Err(ImageError::Unsupported(_)) => Ok(None),
+            Err(error) => Err(format!("cannot read the image '{}': {}", path, error)),
+        }
+    }
+}
+
+fn validate_animations(animations: &AnimationClips, frames: usize) -> Result<(), String> {
+    for (name, clip) in animations.iter() {
+        if clip.start >= clip.end || clip.end > frames {
+            return Err(format!(
+                "has the animation '{}' with frames {:?}, but only {} frames",
+                name,
+                clip.frames(),
+                frames
+            ));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+#![allow(dead_code)]
+
+use bevy::app::AppExit;
+use bevy::asset::AssetPlugin;
+use bevy::audio::AudioPlugin;
+use bevy::prelude::*;
+use bevy_asset_loader::{asset_collection_from_file, AssetCollection, AssetKeys, AssetLoader};
+
+asset_collection_from_file!("assets/includes/level.assets");
+asset_collection_from_file!("assets/includes/desktop.assets", pub struct DesktopSounds);
+
+#[test]
+fn asset_collection_from_file() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin)
+        .add_plugin(AudioPlugin);
+
+    AssetLoader::new(MyStates::Load)
+        .continue_to_state(MyStates::Next)
+        .with_asset_collection_file("includes/level.assets")
+        .with_collection::<LevelAssets>()
+        .with_collection::<DesktopSounds>()
+        .build(&mut app);
+
+    app.add_state(MyStates::Load)
+        .add_system_set(SystemSet::on_update(MyStates::Load).with_system(timeout))
+        .add_system_set(SystemSet::on_enter(MyStates::Next).with_system(expect))
+        .run();
+}
+
+#[test]
+fn macro_and_runtime_agree_on_keys() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin)
+        .add_plugin(AudioPlugin);
+
+    AssetLoader::new(MyStates::Load)
+        .continue_to_state(MyStates::Next)
+        .with_global_asset_collection_file("includes/level.assets")
+        .with_collection::<LevelAssets>()
+        .build(&mut app);
+
+    app.add_state(MyStates::Load)
+        .add_system_set(SystemSet::on_update(MyStates::Load).with_system(timeout))
+        .add_system_set(SystemSet::on_enter(MyStates::Next).with_system(expect_same_keys))
+        .run();
+}
+
+fn timeout(time: Res<Time>) {
+    if time.seconds_since_startup() > 10. {
+        panic!("The asset loader did not change the state in 10 seconds");
+    }
+}
+
+fn expect(
+    level: Res<LevelAssets>,
+    desktop: Res<DesktopSounds>,
+    asset_server: Res<AssetServer>,
+    mut exit: EventWriter<AppExit>,
+) {
+    // the generated struct has fields for the keys of all includes
+    assert_eq!(level.music, asset_server.get_handle("audio/plop.ogg"));
+    assert_eq!(level.click, asset_server.get_handle("audio/plop.ogg"));
+    assert_eq!(
+        level.ambience,
+        asset_server.get_handle("audio/background.ogg")
+    );
+    assert_eq!(desktop.click, level.click);
+    exit.send(AppExit);
+}
+
+fn expect_same_keys(asset_keys: Res<AssetKeys>, mut exit: EventWriter<AppExit>) {
+    assert_eq!(asset_keys.keys(), LevelAssets::KEYS);
+    exit.send(AppExit);
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+enum MyStates {
+    Load,
+    Next,
+}
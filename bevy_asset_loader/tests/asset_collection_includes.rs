@@ -0,0 +1,110 @@
+#![allow(dead_code)]
+
+use bevy::app::AppExit;
+use bevy::asset::AssetPlugin;
+use bevy::audio::AudioPlugin;
+use bevy::prelude::*;
+#[cfg(feature = "test_support")]
+use bevy_asset_loader::test_support::{
+    run_until_loaded, MemoryAssetIo, MemoryAssetIoPlugin, MemoryFile, RunUntilLoadedError,
+};
+use bevy_asset_loader::{AssetCollection, AssetLoader};
+
+#[test]
+fn asset_collection_includes() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin)
+        .add_plugin(AudioPlugin);
+
+    AssetLoader::new(MyStates::Load)
+        .continue_to_state(MyStates::Next)
+        .with_asset_collection_file("includes/level.assets")
+        .with_collection::<MyAssets>()
+        .build(&mut app);
+
+    app.add_state(MyStates::Load)
+        .add_system_set(SystemSet::on_update(MyStates::Load).with_system(timeout))
+        .add_system_set(SystemSet::on_enter(MyStates::Next).with_system(expect))
+        .run();
+}
+
+#[cfg(feature = "test_support")]
+#[test]
+fn cyclic_includes() {
+    let io = MemoryAssetIo::default();
+    io.insert(
+        "includes/cycle_a.assets",
+        MemoryFile::new(*br##"({ "#includes": ["includes/cycle_b.assets"] })"##),
+    );
+    io.insert(
+        "includes/cycle_b.assets",
+        MemoryFile::new(*br##"({ "#includes": ["includes/cycle_a.assets"] })"##),
+    );
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(MemoryAssetIoPlugin::new(&io))
+        .add_plugin(AssetPlugin)
+        .add_plugin(AudioPlugin);
+
+    AssetLoader::new(MyStates::Load)
+        .continue_to_state(MyStates::Next)
+        .with_asset_collection_file("includes/cycle_a.assets")
+        .build(&mut app);
+    app.add_state(MyStates::Load);
+
+    assert_eq!(
+        run_until_loaded(&mut app, 100),
+        Err(RunUntilLoadedError::Failed {
+            paths: vec![
+                "includes/cycle_a.assets".to_owned(),
+                "includes/cycle_b.assets".to_owned()
+            ]
+        })
+    );
+    assert_eq!(
+        app.world
+            .get_resource::<State<MyStates>>()
+            .unwrap()
+            .current(),
+        &MyStates::Load
+    );
+}
+
+fn timeout(time: Res<Time>) {
+    if time.seconds_since_startup() > 10. {
+        panic!("The asset loader did not change the state in 10 seconds");
+    }
+}
+
+fn expect(
+    collection: Res<MyAssets>,
+    asset_server: Res<AssetServer>,
+    mut exit: EventWriter<AppExit>,
+) {
+    // the including file overrides its includes
+    assert_eq!(collection.music, asset_server.get_handle("audio/plop.ogg"));
+    // later includes override earlier ones
+    assert_eq!(collection.click, asset_server.get_handle("audio/plop.ogg"));
+    assert_eq!(
+        collection.ambience,
+        asset_server.get_handle("audio/background.ogg")
+    );
+    exit.send(AppExit);
+}
+
+#[derive(AssetCollection)]
+struct MyAssets {
+    #[asset(key = "music")]
+    music: Handle<AudioSource>,
+    #[asset(key = "click")]
+    click: Handle<AudioSource>,
+    #[asset(key = "ambience")]
+    ambience: Handle<AudioSource>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+enum MyStates {
+    Load,
+    Next,
+}
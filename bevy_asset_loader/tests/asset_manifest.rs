@@ -0,0 +1,108 @@
+#![allow(dead_code)]
+
+use bevy::app::Events;
+use bevy::asset::AssetPlugin;
+use bevy::audio::AudioPlugin;
+use bevy::prelude::*;
+use bevy_asset_loader::test_support::{
+    run_until_loaded, MemoryAssetIo, MemoryAssetIoPlugin, MemoryFile, RunUntilLoadedError,
+};
+use bevy_asset_loader::{
+    AssetCollection, AssetLoader, AssetManifest, AssetManifestMismatch, ManifestEntry,
+};
+
+#[test]
+fn inserts_collections_matching_the_manifest() {
+    let mut manifest = AssetManifest::default();
+    manifest.insert("audio/plop.ogg", ManifestEntry::from_bytes(b"plop"));
+    let mut app = app(manifest);
+
+    assert!(run_until_loaded(&mut app, 10).is_ok());
+    assert!(app.world.contains_resource::<PlopAudio>());
+}
+
+#[test]
+fn fails_for_files_not_matching_the_manifest() {
+    let mut manifest = AssetManifest::default();
+    manifest.insert("audio/plop.ogg", ManifestEntry::from_bytes(b"tampered"));
+    let mut app = app(manifest);
+
+    assert_eq!(
+        run_until_loaded(&mut app, 10),
+        Err(RunUntilLoadedError::Failed {
+            paths: vec!["audio/plop.ogg".to_owned()]
+        })
+    );
+    assert!(!app.world.contains_resource::<PlopAudio>());
+    assert_eq!(
+        mismatches(&app),
+        vec!["'audio/plop.ogg' has 4 bytes, but the manifest expects 8".to_owned()]
+    );
+}
+
+#[test]
+fn fails_for_files_missing_from_the_manifest() {
+    let mut app = app(AssetManifest::default());
+
+    assert!(run_until_loaded(&mut app, 10).is_err());
+    assert_eq!(
+        mismatches(&app),
+        vec!["'audio/plop.ogg' is not listed in the manifest".to_owned()]
+    );
+}
+
+#[test]
+#[should_panic(expected = "requires the AssetManifestPlugin")]
+fn requires_the_asset_manifest_plugin() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin)
+        .add_plugin(AudioPlugin);
+    AssetLoader::new(MyStates::Load)
+        .continue_to_state(MyStates::Next)
+        .with_collection::<PlopAudio>()
+        .with_asset_manifest(AssetManifest::default())
+        .build(&mut app);
+}
+
+fn app(manifest: AssetManifest) -> App {
+    let io = MemoryAssetIo::default();
+    io.insert("audio/plop.ogg", MemoryFile::new(*b"plop"));
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(MemoryAssetIoPlugin::new(&io).with_asset_manifest())
+        .add_plugin(AssetPlugin)
+        .add_plugin(AudioPlugin);
+    AssetLoader::new(MyStates::Load)
+        .continue_to_state(MyStates::Next)
+        .with_collection::<PlopAudio>()
+        .with_asset_manifest(manifest)
+        .build(&mut app);
+    app.add_state(MyStates::Load);
+
+    app
+}
+
+fn mismatches(app: &App) -> Vec<String> {
+    let events = app
+        .world
+        .get_resource::<Events<AssetManifestMismatch>>()
+        .unwrap();
+    events
+        .get_reader()
+        .iter(events)
+        .flat_map(|mismatch| mismatch.mismatches.clone())
+        .collect()
+}
+
+#[derive(AssetCollection)]
+struct PlopAudio {
+    #[asset(path = "audio/plop.ogg")]
+    plop: Handle<AudioSource>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+enum MyStates {
+    Load,
+    Next,
+}
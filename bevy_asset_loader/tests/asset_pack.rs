@@ -0,0 +1,223 @@
+#![allow(dead_code)]
+
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use bevy::app::AppExit;
+use bevy::asset::{AssetIo, AssetPlugin};
+use bevy::audio::AudioPlugin;
+use bevy::prelude::*;
+use bevy::tasks::TaskPool;
+use bevy_asset_loader::{AssetCollection, AssetLoader, AssetPackPlugin, AssetPacker, PackAssetIo};
+
+#[test]
+fn packs_referenced_files() {
+    let packer = AssetPacker::new("assets")
+        .with_asset_collection_file("includes/level.assets")
+        .with_collection::<BackgroundAudio>()
+        .with_file("images");
+
+    assert_eq!(
+        packer.files().unwrap(),
+        vec![
+            "audio/background.ogg",
+            "audio/plop.ogg",
+            "images/background.png",
+            "images/female_adventurer.png",
+            "images/female_adventurer_sheet.atlas.json",
+            "images/female_adventurer_sheet.png",
+            "images/player.png",
+            "images/tree.png",
+            "images/zombie.png",
+            "includes/common.assets",
+            "includes/desktop.assets",
+            "includes/level.assets",
+        ]
+    );
+
+    let pack = temp_path("packs_referenced_files.tar");
+    packer.pack(&pack).unwrap();
+    let first = fs::read(&pack).unwrap();
+    packer.pack(&pack).unwrap();
+    assert_eq!(
+        first,
+        fs::read(&pack).unwrap(),
+        "Packs are not reproducible"
+    );
+
+    let pack_io = PackAssetIo::open(&pack).unwrap();
+    assert!(pack_io.contains(Path::new("includes/desktop.assets")));
+    assert!(!pack_io.contains(Path::new("models/crate.gltf")));
+    drop(pack_io);
+    fs::remove_file(&pack).unwrap();
+}
+
+#[test]
+fn fails_for_missing_files() {
+    assert_eq!(
+        AssetPacker::new("assets")
+            .with_file("audio/missing.ogg")
+            .files(),
+        Err("audio/missing.ogg: the file 'audio/missing.ogg' does not exist".to_owned())
+    );
+}
+
+#[cfg(feature = "gltf")]
+#[test]
+fn packs_the_files_of_gltf_sub_assets() {
+    assert_eq!(
+        AssetPacker::new("assets")
+            .with_collection::<CrateAssets>()
+            .files(),
+        Ok(vec!["models/crate.gltf".to_owned()])
+    );
+}
+
+#[test]
+fn packs_files_referenced_by_gltf_files() {
+    let asset_folder = temp_path("packs_files_referenced_by_gltf_files");
+    fs::create_dir_all(asset_folder.join("models")).unwrap();
+    fs::create_dir_all(asset_folder.join("images")).unwrap();
+    fs::write(
+        asset_folder.join("models/ship.gltf"),
+        r#"{
+            "buffers": [{ "byteLength": 4, "uri": "ship.bin" }],
+            "images": [
+                { "uri": "../images/ship%20hull.png" },
+                { "uri": "data:image/png;base64,AAAA" },
+                { "bufferView": 0, "mimeType": "image/png" }
+            ]
+        }"#,
+    )
+    .unwrap();
+    fs::write(asset_folder.join("models/ship.bin"), b"ship").unwrap();
+    fs::write(asset_folder.join("images/ship hull.png"), b"hull").unwrap();
+
+    let files = AssetPacker::new(&asset_folder)
+        .with_file("models/ship.gltf")
+        .files();
+    fs::remove_file(asset_folder.join("models/ship.bin")).unwrap();
+    let missing_buffer = AssetPacker::new(&asset_folder)
+        .with_file("models/ship.gltf")
+        .files();
+    fs::remove_dir_all(&asset_folder).unwrap();
+
+    assert_eq!(
+        files,
+        Ok(vec![
+            "images/ship hull.png".to_owned(),
+            "models/ship.bin".to_owned(),
+            "models/ship.gltf".to_owned(),
+        ])
+    );
+    assert_eq!(
+        missing_buffer,
+        Err("models/ship.gltf: the file 'models/ship.bin' does not exist".to_owned())
+    );
+}
+
+#[test]
+fn reads_packed_files_on_demand() {
+    let pack = temp_path("reads_packed_files_on_demand.tar");
+    AssetPacker::new("assets")
+        .with_file("audio")
+        .pack(&pack)
+        .unwrap();
+    let bytes = fs::read(&pack).unwrap();
+    fs::remove_file(&pack).unwrap();
+
+    let pack_io = PackAssetIo::from_reader(Cursor::new(bytes)).unwrap();
+    let loaded = TaskPool::new().scope(|scope| {
+        for path in ["audio/plop.ogg", "audio/background.ogg", "audio/plop.ogg"] {
+            let pack_io = &pack_io;
+            scope.spawn(async move { pack_io.load_path(Path::new(path)).await.unwrap() });
+        }
+    });
+
+    let plop = fs::read("assets/audio/plop.ogg").unwrap();
+    let background = fs::read("assets/audio/background.ogg").unwrap();
+    assert_eq!(loaded, vec![plop.clone(), background, plop]);
+}
+
+#[test]
+fn loads_collections_from_packs_layered_over_the_file_system() {
+    // only the pack contains "packed/plop.ogg"
+    let asset_folder = temp_path("loads_collections_from_packs");
+    fs::create_dir_all(asset_folder.join("packed")).unwrap();
+    fs::copy(
+        "assets/audio/plop.ogg",
+        asset_folder.join("packed/plop.ogg"),
+    )
+    .unwrap();
+    let pack = temp_path("loads_collections_from_packs.tar");
+    AssetPacker::new(&asset_folder)
+        .with_collection::<PackedAudio>()
+        .pack(&pack)
+        .unwrap();
+    fs::remove_dir_all(&asset_folder).unwrap();
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPackPlugin::new(&pack).layered_over_file_system())
+        .add_plugin(AssetPlugin)
+        .add_plugin(AudioPlugin);
+
+    AssetLoader::new(MyStates::Load)
+        .continue_to_state(MyStates::Next)
+        .with_collection::<PackedAudio>()
+        .with_collection::<BackgroundAudio>()
+        .build(&mut app);
+
+    app.add_state(MyStates::Load)
+        .add_system(timeout)
+        .add_system_set(SystemSet::on_enter(MyStates::Next).with_system(expect))
+        .run();
+    fs::remove_file(&pack).unwrap();
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("bevy_asset_loader_{}_{}", std::process::id(), name))
+}
+
+fn timeout(time: Res<Time>) {
+    if time.seconds_since_startup() > 10. {
+        panic!("The asset loader did not change the state in 10 seconds");
+    }
+}
+
+fn expect(
+    packed: Option<Res<PackedAudio>>,
+    background: Option<Res<BackgroundAudio>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if packed.is_none() || background.is_none() {
+        panic!("At least one asset collection was not inserted");
+    }
+    exit.send(AppExit);
+}
+
+#[derive(AssetCollection)]
+struct PackedAudio {
+    #[asset(path = "packed/plop.ogg")]
+    plop: Handle<AudioSource>,
+}
+
+#[derive(AssetCollection)]
+struct BackgroundAudio {
+    #[asset(path = "audio/background.ogg")]
+    background: Handle<AudioSource>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+enum MyStates {
+    Load,
+    Next,
+}
+
+#[cfg(feature = "gltf")]
+#[derive(AssetCollection)]
+struct CrateAssets {
+    #[asset(path = "models/crate.gltf", gltf(mesh = "Crate"))]
+    crate_mesh: Handle<bevy::gltf::GltfMesh>,
+}
@@ -0,0 +1,145 @@
+#![allow(dead_code)]
+
+use std::fs;
+
+use bevy::prelude::*;
+use bevy_asset_loader::{AssetCollection, AssetValidator, NamedTextureAtlas};
+
+#[test]
+fn accepts_valid_asset_collection_files() {
+    let report = AssetValidator::new("assets")
+        .with_asset_collection_file("dynamic_asset_ron.assets")
+        .validate();
+
+    assert!(report.is_ok(), "{}", report);
+    assert!(!report
+        .unused_files
+        .contains(&"images/female_adventurer_sheet.png".to_owned()));
+    assert!(report
+        .unused_files
+        .contains(&"images/zombie.png".to_owned()));
+}
+
+#[test]
+fn reports_missing_files_invalid_atlases_and_unused_files() {
+    let asset_folder = std::env::temp_dir().join(format!(
+        "bevy_asset_loader_validation_{}",
+        std::process::id()
+    ));
+    fs::create_dir_all(asset_folder.join("images")).unwrap();
+    fs::copy(
+        "assets/images/player.png",
+        asset_folder.join("images/player.png"),
+    )
+    .unwrap();
+    fs::write(asset_folder.join("images/unused.txt"), "").unwrap();
+    fs::write(
+        asset_folder.join("broken.assets"),
+        r##"({
+    "#includes": ["missing.assets"],
+    "player": TextureAtlas (
+        path: "images/player.png",
+        tile_size_x: 32.,
+        tile_size_y: 32.,
+        columns: 3,
+        rows: 2,
+    ),
+    "music": File (
+        path: "audio/missing.ogg",
+    ),
+    "custom": SomethingCustom (
+        anything: [1, 2],
+    ),
+})"##,
+    )
+    .unwrap();
+
+    let report = AssetValidator::new(&asset_folder)
+        .with_all_asset_collection_files()
+        .validate();
+    fs::remove_dir_all(&asset_folder).unwrap();
+
+    assert_eq!(
+        report.errors,
+        vec![
+            "broken.assets: the included file 'missing.assets' does not exist".to_owned(),
+            "broken.assets: 'player' needs 96x64 pixels for 3 columns and 2 rows, but 'images/player.png' has 64x64 pixels".to_owned(),
+            "broken.assets: 'music' references the missing file 'audio/missing.ogg'".to_owned(),
+        ]
+    );
+    assert_eq!(report.unused_files, vec!["images/unused.txt".to_owned()]);
+}
+
+#[test]
+fn checks_derived_asset_collections() {
+    let report = AssetValidator::new("assets")
+        .with_collection::<MyAssets>()
+        .validate();
+
+    assert_eq!(
+        report.errors,
+        vec![format!(
+            "{}: 'player' has the animation 'walk' with frames 1..9, but only 8 frames",
+            std::any::type_name::<MyAssets>()
+        )]
+    );
+}
+
+#[test]
+fn checks_the_folders_of_stitched_atlases() {
+    let report = AssetValidator::new("assets")
+        .with_collection::<MissingFolderAssets>()
+        .validate();
+
+    assert_eq!(
+        report.errors,
+        vec![format!(
+            "{}: 'tiles' references the missing file 'images/tiles'",
+            std::any::type_name::<MissingFolderAssets>()
+        )]
+    );
+}
+
+#[cfg(feature = "gltf")]
+#[test]
+fn checks_the_files_of_gltf_sub_assets() {
+    let report = AssetValidator::new("assets")
+        .with_collection::<MissingGltfAssets>()
+        .validate();
+
+    assert_eq!(
+        report.errors,
+        vec![format!(
+            "{}: 'ship' references the missing file 'models/ship.gltf'",
+            std::any::type_name::<MissingGltfAssets>()
+        )]
+    );
+}
+
+#[derive(AssetCollection)]
+struct MyAssets {
+    #[asset(path = "images/tree.png")]
+    tree: Handle<Image>,
+    #[asset(texture_atlas(
+        tile_size_x = 96.,
+        tile_size_y = 99.,
+        columns = 8,
+        rows = 1,
+        animations(walk(start = 1, end = 9, fps = 10.))
+    ))]
+    #[asset(path = "images/female_adventurer_sheet.png")]
+    player: NamedTextureAtlas,
+}
+
+#[derive(AssetCollection)]
+struct MissingFolderAssets {
+    #[asset(texture_atlas(folder = "images/tiles"))]
+    tiles: NamedTextureAtlas,
+}
+
+#[cfg(feature = "gltf")]
+#[derive(AssetCollection)]
+struct MissingGltfAssets {
+    #[asset(path = "models/ship.gltf", gltf(scene = "Ship"))]
+    ship: Handle<Scene>,
+}
@@ -0,0 +1,75 @@
+#![allow(dead_code)]
+
+use bevy::app::AppExit;
+use bevy::asset::AssetPlugin;
+use bevy::audio::AudioPlugin;
+use bevy::prelude::*;
+use bevy_asset_loader::{AssetCollection, AssetLoader, AssetVariables};
+
+#[test]
+fn asset_variables() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin)
+        .add_plugin(AudioPlugin);
+
+    AssetLoader::new(MyStates::LevelOne)
+        .continue_to_state(MyStates::LevelTwo)
+        .with_global_asset_collection_file("variables/level.assets")
+        .with_collection::<LevelAssets>()
+        .build(&mut app);
+    AssetLoader::new(MyStates::LevelTwo)
+        .continue_to_state(MyStates::Next)
+        .with_collection::<LevelAssets>()
+        .build(&mut app);
+
+    let mut variables = AssetVariables::default();
+    variables.set("sound", "background");
+    variables.set("music", "plop");
+
+    app.insert_resource(variables)
+        .add_state(MyStates::LevelOne)
+        .add_system_set(SystemSet::on_update(MyStates::LevelOne).with_system(timeout))
+        .add_system_set(SystemSet::on_exit(MyStates::LevelOne).with_system(next_level))
+        .add_system_set(SystemSet::on_update(MyStates::LevelTwo).with_system(timeout))
+        .add_system_set(SystemSet::on_enter(MyStates::Next).with_system(expect))
+        .run();
+}
+
+fn timeout(time: Res<Time>) {
+    if time.seconds_since_startup() > 10. {
+        panic!("The asset loader did not change the state in 10 seconds");
+    }
+}
+
+fn next_level(
+    level: Res<LevelAssets>,
+    asset_server: Res<AssetServer>,
+    mut variables: ResMut<AssetVariables>,
+) {
+    assert_eq!(level.sound, asset_server.get_handle("audio/background.ogg"));
+    assert_eq!(level.music, asset_server.get_handle("audio/plop.ogg"));
+    variables.set("sound", "plop");
+    variables.set("music", "background");
+}
+
+fn expect(level: Res<LevelAssets>, asset_server: Res<AssetServer>, mut exit: EventWriter<AppExit>) {
+    assert_eq!(level.sound, asset_server.get_handle("audio/plop.ogg"));
+    assert_eq!(level.music, asset_server.get_handle("audio/background.ogg"));
+    exit.send(AppExit);
+}
+
+#[derive(AssetCollection)]
+struct LevelAssets {
+    #[asset(path = "audio/{sound}.ogg")]
+    sound: Handle<AudioSource>,
+    #[asset(key = "level.music")]
+    music: Handle<AudioSource>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+enum MyStates {
+    LevelOne,
+    LevelTwo,
+    Next,
+}
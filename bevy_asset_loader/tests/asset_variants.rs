@@ -0,0 +1,124 @@
+#![allow(dead_code)]
+
+use bevy::asset::AssetPlugin;
+use bevy::audio::AudioPlugin;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_asset_loader::test_support::{
+    run_until_loaded, MemoryAssetIo, MemoryAssetIoPlugin, MemoryFile, RunUntilLoadedError,
+};
+use bevy_asset_loader::{
+    AssetCollection, AssetCollectionWorld, AssetLoader, AssetQuality, DynamicAsset, QualityTier,
+};
+
+#[test]
+fn asset_variants() {
+    let io = MemoryAssetIo::default();
+    io.insert("variants/low/plop.ogg", MemoryFile::new(*b"low"));
+    io.insert("variants/medium/plop.ogg", MemoryFile::new(*b"medium"));
+    let mut app = app(&io);
+    app.insert_resource(
+        AssetQuality::new(QualityTier::High)
+            .with_available_tiers(&[QualityTier::Low, QualityTier::Medium]),
+    );
+
+    assert!(run_until_loaded(&mut app, 100).is_ok());
+    assert_sounds(&app, "variants/medium/plop.ogg");
+}
+
+#[test]
+fn falls_back_to_lower_tiers_of_missing_files() {
+    let io = MemoryAssetIo::default();
+    io.insert("variants/medium/plop.ogg", MemoryFile::new(*b"medium"));
+    io.insert("variants/low/plop.ogg", MemoryFile::new(*b"low"));
+    // without an AssetQuality resource, the high tier is requested
+    let mut app = app(&io);
+
+    assert!(run_until_loaded(&mut app, 100).is_ok());
+    assert_sounds(&app, "variants/medium/plop.ogg");
+}
+
+#[test]
+fn never_falls_back_to_higher_tiers() {
+    let io = MemoryAssetIo::default();
+    io.insert("variants/high/plop.ogg", MemoryFile::new(*b"high"));
+    let mut app = app(&io);
+    app.insert_resource(AssetQuality::new(QualityTier::Medium));
+
+    assert_eq!(
+        run_until_loaded(&mut app, 100),
+        Err(RunUntilLoadedError::Failed {
+            paths: vec!["variants/low/plop.ogg".to_owned()]
+        })
+    );
+}
+
+#[test]
+fn collections_initialized_on_the_world_resolve_variants() {
+    let io = MemoryAssetIo::default();
+    io.insert("variants/high/plop.ogg", MemoryFile::new(*b"high"));
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(MemoryAssetIoPlugin::new(&io))
+        .add_plugin(AssetPlugin)
+        .add_plugin(AudioPlugin);
+
+    app.world.init_collection::<VariantAssets>();
+    let variant = &app.world.get_resource::<VariantAssets>().unwrap().variant;
+    let asset_server = app.world.get_resource::<AssetServer>().unwrap();
+    assert_eq!(variant, &asset_server.get_handle("variants/high/plop.ogg"));
+}
+
+fn app(io: &MemoryAssetIo) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(MemoryAssetIoPlugin::new(io))
+        .add_plugin(AssetPlugin)
+        .add_plugin(AudioPlugin);
+    let mut keys = HashMap::default();
+    keys.insert(
+        "plop".to_owned(),
+        DynamicAsset::File {
+            path: "variants/{quality}/plop.ogg".to_owned(),
+        },
+    );
+    AssetLoader::new(MyStates::Load)
+        .continue_to_state(MyStates::Next)
+        .add_keys(keys)
+        .with_collection::<SoundAssets>()
+        .build(&mut app);
+    app.add_state(MyStates::Load);
+
+    app
+}
+
+fn assert_sounds(app: &App, path: &str) {
+    let sounds = app.world.get_resource::<SoundAssets>().unwrap();
+    let handle = app
+        .world
+        .get_resource::<AssetServer>()
+        .unwrap()
+        .get_handle(path);
+    assert_eq!(sounds.variant, handle);
+    assert_eq!(sounds.dynamic_variant, handle);
+}
+
+#[derive(AssetCollection)]
+struct SoundAssets {
+    #[asset(path = "variants/plop.ogg", variants)]
+    variant: Handle<AudioSource>,
+    #[asset(key = "plop")]
+    dynamic_variant: Handle<AudioSource>,
+}
+
+#[derive(AssetCollection)]
+struct VariantAssets {
+    #[asset(path = "variants/plop.ogg", variants)]
+    variant: Handle<AudioSource>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+enum MyStates {
+    Load,
+    Next,
+}
@@ -0,0 +1,113 @@
+#![allow(dead_code)]
+
+use bevy::app::AppExit;
+use bevy::asset::AssetPlugin;
+use bevy::audio::AudioPlugin;
+use bevy::prelude::*;
+#[cfg(feature = "test_support")]
+use bevy_asset_loader::test_support::{
+    run_until_loaded, MemoryAssetIo, MemoryAssetIoPlugin, MemoryFile, RunUntilLoadedError,
+};
+use bevy_asset_loader::{AssetCollection, AssetCollectionEntityCommands, AssetLoader, Loading};
+#[cfg(feature = "test_support")]
+use bevy_asset_loader::{CollectionComponentsPlugin, LoadingFailed};
+
+#[test]
+fn collection_components() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin)
+        .add_plugin(AudioPlugin);
+
+    AssetLoader::new(MyStates::Load)
+        .continue_to_state(MyStates::Next)
+        .build(&mut app);
+
+    app.add_state(MyStates::Load)
+        .add_startup_system(spawn)
+        .add_system(timeout)
+        .add_system(expect)
+        .run();
+}
+
+#[cfg(feature = "test_support")]
+#[test]
+fn collection_components_without_asset_loader() {
+    let io = MemoryAssetIo::default();
+    io.insert("audio/plop.ogg", MemoryFile::new(*b"plop").with_delay(2));
+    let mut app = memory_app(&io);
+
+    assert!(run_until_loaded(&mut app, 100).is_ok());
+    let mut query = app
+        .world
+        .query_filtered::<&VoiceAssets, Without<Loading<VoiceAssets>>>();
+    assert_eq!(query.iter(&app.world).count(), 1);
+}
+
+#[cfg(feature = "test_support")]
+#[test]
+fn failed_collection_components() {
+    let io = MemoryAssetIo::default();
+    io.insert("audio/plop.ogg", MemoryFile::failing());
+    let mut app = memory_app(&io);
+
+    assert_eq!(
+        run_until_loaded(&mut app, 100),
+        Err(RunUntilLoadedError::Failed {
+            paths: vec!["audio/plop.ogg".to_owned()]
+        })
+    );
+    let mut query = app
+        .world
+        .query_filtered::<&LoadingFailed<VoiceAssets>, Without<Loading<VoiceAssets>>>();
+    let failed = query.iter(&app.world).next().unwrap();
+    assert_eq!(failed.paths, vec!["audio/plop.ogg".to_owned()]);
+}
+
+#[cfg(feature = "test_support")]
+fn memory_app(io: &MemoryAssetIo) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(MemoryAssetIoPlugin::new(io))
+        .add_plugin(AssetPlugin)
+        .add_plugin(AudioPlugin)
+        .add_plugin(CollectionComponentsPlugin)
+        .add_startup_system(spawn);
+
+    app
+}
+
+fn spawn(mut commands: Commands) {
+    commands.spawn().load_collection::<VoiceAssets>();
+}
+
+fn timeout(time: Res<Time>) {
+    if time.seconds_since_startup() > 10. {
+        panic!("The collection was not inserted in 10 seconds");
+    }
+}
+
+fn expect(
+    loading: Query<Entity, With<Loading<VoiceAssets>>>,
+    voices: Query<&VoiceAssets, Without<Loading<VoiceAssets>>>,
+    asset_server: Res<AssetServer>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if let Ok(voice) = voices.get_single() {
+        assert!(loading.is_empty());
+        assert_eq!(voice.plop, asset_server.get_handle("audio/plop.ogg"));
+        exit.send(AppExit);
+    }
+}
+
+#[derive(AssetCollection, Component)]
+struct VoiceAssets {
+    #[asset(path = "audio/plop.ogg")]
+    plop: Handle<AudioSource>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+enum MyStates {
+    Load,
+    Next,
+}
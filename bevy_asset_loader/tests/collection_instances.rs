@@ -0,0 +1,138 @@
+#![allow(dead_code)]
+
+use bevy::app::AppExit;
+use bevy::asset::AssetPlugin;
+use bevy::audio::AudioPlugin;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+#[cfg(feature = "test_support")]
+use bevy_asset_loader::test_support::{
+    run_until_loaded, MemoryAssetIo, MemoryAssetIoPlugin, MemoryFile,
+};
+use bevy_asset_loader::{AssetCollection, AssetLoader, Collections, DynamicAsset};
+
+#[test]
+fn collection_instances() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin)
+        .add_plugin(AudioPlugin);
+
+    let mut keys = HashMap::default();
+    keys.insert("knight.voice".to_owned(), file("audio/plop.ogg"));
+    keys.insert("wizard.voice".to_owned(), file("audio/background.ogg"));
+    keys.insert("music".to_owned(), file("audio/background.ogg"));
+
+    AssetLoader::new(MyStates::Load)
+        .continue_to_state(MyStates::Next)
+        .add_keys(keys)
+        .with_collection_instance_sharing_keys::<CharacterAssets, _>(Character::Knight, "knight.")
+        .with_collection_instance_sharing_keys::<CharacterAssets, _>(Character::Wizard, "wizard.")
+        .build(&mut app);
+
+    app.add_state(MyStates::Load)
+        .add_system_set(SystemSet::on_update(MyStates::Load).with_system(timeout))
+        .add_system_set(SystemSet::on_enter(MyStates::Next).with_system(expect))
+        .run();
+}
+
+#[cfg(feature = "test_support")]
+#[test]
+fn instances_only_share_keys_if_enabled() {
+    let io = MemoryAssetIo::default();
+    io.insert("audio/plop.ogg", MemoryFile::new(*b"plop"));
+    io.insert("audio/background.ogg", MemoryFile::new(*b"background"));
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(MemoryAssetIoPlugin::new(&io))
+        .add_plugin(AssetPlugin)
+        .add_plugin(AudioPlugin);
+
+    let mut keys = HashMap::default();
+    keys.insert("knight.voice".to_owned(), file("audio/plop.ogg"));
+    keys.insert("wizard.voice".to_owned(), file("audio/background.ogg"));
+    keys.insert("music".to_owned(), file("audio/background.ogg"));
+
+    AssetLoader::new(MyStates::Load)
+        .continue_to_state(MyStates::Next)
+        .add_keys(keys)
+        .with_collection_instance::<OptionalMusicAssets, _>(Character::Knight, "knight.")
+        .with_collection_instance_sharing_keys::<OptionalMusicAssets, _>(
+            Character::Wizard,
+            "wizard.",
+        )
+        .build(&mut app);
+    app.add_state(MyStates::Load);
+
+    assert!(run_until_loaded(&mut app, 100).is_ok());
+    let characters = app
+        .world
+        .get_resource::<Collections<OptionalMusicAssets, Character>>()
+        .unwrap();
+    let asset_server = app.world.get_resource::<AssetServer>().unwrap();
+    let knight = characters.get(&Character::Knight).unwrap();
+    let wizard = characters.get(&Character::Wizard).unwrap();
+    assert_eq!(knight.voice, asset_server.get_handle("audio/plop.ogg"));
+    assert_eq!(knight.music, None);
+    assert_eq!(
+        wizard.music,
+        Some(asset_server.get_handle("audio/background.ogg"))
+    );
+}
+
+fn file(path: &str) -> DynamicAsset {
+    DynamicAsset::File {
+        path: path.to_owned(),
+    }
+}
+
+fn timeout(time: Res<Time>) {
+    if time.seconds_since_startup() > 10. {
+        panic!("The asset loader did not change the state in 10 seconds");
+    }
+}
+
+fn expect(
+    characters: Res<Collections<CharacterAssets, Character>>,
+    asset_server: Res<AssetServer>,
+    mut exit: EventWriter<AppExit>,
+) {
+    assert_eq!(characters.len(), 2);
+    let knight = characters.get(&Character::Knight).unwrap();
+    let wizard = characters.get(&Character::Wizard).unwrap();
+    assert_eq!(knight.voice, asset_server.get_handle("audio/plop.ogg"));
+    assert_eq!(
+        wizard.voice,
+        asset_server.get_handle("audio/background.ogg")
+    );
+    assert_eq!(knight.music, wizard.music);
+    exit.send(AppExit);
+}
+
+#[derive(AssetCollection)]
+struct CharacterAssets {
+    #[asset(key = "voice")]
+    voice: Handle<AudioSource>,
+    #[asset(key = "music")]
+    music: Handle<AudioSource>,
+}
+
+#[derive(AssetCollection)]
+struct OptionalMusicAssets {
+    #[asset(key = "voice")]
+    voice: Handle<AudioSource>,
+    #[asset(key = "music", optional)]
+    music: Option<Handle<AudioSource>>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+enum Character {
+    Knight,
+    Wizard,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+enum MyStates {
+    Load,
+    Next,
+}
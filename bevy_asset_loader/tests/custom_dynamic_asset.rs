@@ -0,0 +1,157 @@
+#![allow(dead_code)]
+
+use bevy::app::AppExit;
+use bevy::asset::{AssetPlugin, HandleId};
+use bevy::audio::AudioPlugin;
+use bevy::ecs::world::WorldCell;
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy_asset_loader::{
+    AssetCollection, AssetLoader, DynamicAssetApp, DynamicAssetType, DynamicAssetValue,
+};
+
+#[test]
+fn custom_dynamic_asset() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin)
+        .add_plugin(AudioPlugin)
+        .add_asset::<Playlist>()
+        .register_dynamic_asset::<PlaylistAsset>();
+
+    AssetLoader::new(MyStates::Load)
+        .continue_to_state(MyStates::Next)
+        .with_asset_collection_file("custom_dynamic_asset.assets")
+        .with_collection::<MyAssets>()
+        .build(&mut app);
+
+    app.add_state(MyStates::Load)
+        .add_system_set(SystemSet::on_update(MyStates::Load).with_system(timeout))
+        .add_system_set(SystemSet::on_enter(MyStates::Next).with_system(expect))
+        .run();
+}
+
+#[test]
+fn generic_dynamic_assets_use_their_tag() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin)
+        .add_plugin(AudioPlugin)
+        .add_asset::<Playlist>()
+        .register_dynamic_asset::<Tracks<String>>();
+
+    AssetLoader::new(MyStates::Load)
+        .continue_to_state(MyStates::Next)
+        .with_asset_collection_file("generic_dynamic_asset.assets")
+        .with_collection::<TrackAssets>()
+        .build(&mut app);
+
+    app.add_state(MyStates::Load)
+        .add_system_set(SystemSet::on_update(MyStates::Load).with_system(timeout))
+        .add_system_set(SystemSet::on_enter(MyStates::Next).with_system(exit))
+        .run();
+}
+
+fn timeout(time: Res<Time>) {
+    if time.seconds_since_startup() > 10. {
+        panic!("The asset loader did not change the state in 10 seconds");
+    }
+}
+
+fn expect(
+    collection: Res<MyAssets>,
+    playlists: Res<Assets<Playlist>>,
+    audio_sources: Res<Assets<AudioSource>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    let playlist = playlists
+        .get(collection.playlist.clone())
+        .expect("Playlist was not created");
+    assert_eq!(playlist.tracks.len(), 2);
+    for track in &playlist.tracks {
+        assert!(audio_sources.get(track).is_some());
+    }
+    assert!(audio_sources.get(collection.plop.clone()).is_some());
+    exit.send(AppExit);
+}
+
+fn exit(_collection: Res<TrackAssets>, mut exit: EventWriter<AppExit>) {
+    exit.send(AppExit);
+}
+
+#[derive(TypeUuid)]
+#[uuid = "7a1e2b0c-37a8-4c3f-9c1e-8f45a0d2f6b1"]
+struct Playlist {
+    tracks: Vec<Handle<AudioSource>>,
+}
+
+#[derive(serde::Deserialize)]
+struct PlaylistAsset {
+    tracks: Vec<String>,
+}
+
+impl DynamicAssetType for PlaylistAsset {
+    fn load(&self, cell: &WorldCell) -> Vec<HandleUntyped> {
+        let asset_server = cell.get_resource::<AssetServer>().unwrap();
+        self.tracks
+            .iter()
+            .map(|track| asset_server.load_untyped(track.as_str()))
+            .collect()
+    }
+
+    fn create(&self, cell: &WorldCell) -> DynamicAssetValue {
+        let asset_server = cell.get_resource::<AssetServer>().unwrap();
+        let mut playlists = cell.get_resource_mut::<Assets<Playlist>>().unwrap();
+        let playlist = Playlist {
+            tracks: self
+                .tracks
+                .iter()
+                .map(|track| asset_server.get_handle(track.as_str()))
+                .collect(),
+        };
+        DynamicAssetValue::Handle(playlists.add(playlist).clone_untyped())
+    }
+
+    fn tag() -> &'static str {
+        "Playlist"
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct Tracks<T> {
+    tracks: Vec<T>,
+}
+
+impl DynamicAssetType for Tracks<String> {
+    fn load(&self, _cell: &WorldCell) -> Vec<HandleUntyped> {
+        vec![]
+    }
+
+    fn create(&self, _cell: &WorldCell) -> DynamicAssetValue {
+        DynamicAssetValue::Handle(HandleUntyped::weak(HandleId::default::<Playlist>()))
+    }
+
+    fn tag() -> &'static str {
+        "Tracks"
+    }
+}
+
+#[derive(AssetCollection)]
+struct MyAssets {
+    #[asset(key = "playlist")]
+    playlist: Handle<Playlist>,
+    #[asset(key = "plop")]
+    plop: Handle<AudioSource>,
+}
+
+#[derive(AssetCollection)]
+struct TrackAssets {
+    #[asset(key = "tracks")]
+    tracks: Handle<Playlist>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+enum MyStates {
+    Load,
+    Next,
+}
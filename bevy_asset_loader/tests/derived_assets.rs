@@ -0,0 +1,113 @@
+#![allow(dead_code)]
+
+use bevy::asset::AssetPlugin;
+use bevy::ecs::event::{Events, ManualEventReader};
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy_asset_loader::{AssetCollection, AssetCollectionApp, DerivedAssets};
+
+#[test]
+fn derived_assets_update_when_their_image_is_modified() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin)
+        .add_asset::<Image>()
+        .add_asset::<TextureAtlas>()
+        .add_asset::<StandardMaterial>()
+        .init_collection::<MyAssets>();
+    let image = app
+        .world
+        .get_resource::<AssetServer>()
+        .unwrap()
+        .get_handle("images/sheet.png");
+    let (atlas, material) = {
+        let assets = app.world.get_resource::<MyAssets>().unwrap();
+        (assets.sheet.clone(), assets.material.clone())
+    };
+    let mut material_events = ManualEventReader::<AssetEvent<StandardMaterial>>::default();
+
+    set_image(&mut app, &image, 64, 32);
+    update(&mut app, 2);
+    assert!(!material_modified(
+        &mut app,
+        &mut material_events,
+        &material
+    ));
+
+    set_image(&mut app, &image, 128, 64);
+    update(&mut app, 2);
+    assert!(material_modified(&mut app, &mut material_events, &material));
+
+    let atlases = app.world.get_resource::<Assets<TextureAtlas>>().unwrap();
+    let texture_atlas = atlases.get(&atlas).unwrap();
+    assert_eq!(texture_atlas.size, Vec2::new(128., 64.));
+    assert_eq!(texture_atlas.len(), 4);
+}
+
+#[test]
+fn removed_derived_assets_are_not_tracked_anymore() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin)
+        .add_asset::<Image>()
+        .add_asset::<TextureAtlas>()
+        .add_asset::<StandardMaterial>()
+        .init_collection::<MyAssets>();
+    update(&mut app, 2);
+    assert_eq!(app.world.get_resource::<DerivedAssets>().unwrap().len(), 2);
+
+    app.world.remove_resource::<MyAssets>();
+    update(&mut app, 4);
+    assert!(app
+        .world
+        .get_resource::<DerivedAssets>()
+        .unwrap()
+        .is_empty());
+}
+
+fn set_image(app: &mut App, handle: &Handle<Image>, width: u32, height: u32) {
+    let image = Image::new_fill(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[255, 255, 255, 255],
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    let _ = app
+        .world
+        .get_resource_mut::<Assets<Image>>()
+        .unwrap()
+        .set(handle.clone(), image);
+}
+
+fn update(app: &mut App, frames: usize) {
+    for _ in 0..frames {
+        app.update();
+    }
+}
+
+fn material_modified(
+    app: &mut App,
+    reader: &mut ManualEventReader<AssetEvent<StandardMaterial>>,
+    material: &Handle<StandardMaterial>,
+) -> bool {
+    let events = app
+        .world
+        .get_resource::<Events<AssetEvent<StandardMaterial>>>()
+        .unwrap();
+    reader
+        .iter(events)
+        .any(|event| matches!(event, AssetEvent::Modified { handle } if handle == material))
+}
+
+#[derive(AssetCollection)]
+struct MyAssets {
+    #[asset(texture_atlas(tile_size_x = 32., tile_size_y = 32., columns = 2, rows = 2))]
+    #[asset(path = "images/sheet.png")]
+    sheet: Handle<TextureAtlas>,
+    #[asset(path = "images/sheet.png", standard_material)]
+    material: Handle<StandardMaterial>,
+}
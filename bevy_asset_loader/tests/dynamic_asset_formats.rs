@@ -0,0 +1,61 @@
+#![allow(dead_code)]
+
+use bevy::app::AppExit;
+use bevy::asset::AssetPlugin;
+use bevy::audio::AudioPlugin;
+use bevy::prelude::*;
+use bevy_asset_loader::{AssetCollection, AssetLoader};
+
+#[test]
+fn dynamic_asset_formats() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin)
+        .add_plugin(AudioPlugin);
+
+    AssetLoader::new(MyStates::Load)
+        .continue_to_state(MyStates::Next)
+        .with_asset_collection_file("formats/plop.assets.json")
+        .with_asset_collection_file("formats/background.assets.toml")
+        .with_asset_collection_file("formats/ambience.assets.yaml")
+        .with_collection::<MyAssets>()
+        .build(&mut app);
+
+    app.add_state(MyStates::Load)
+        .add_system_set(SystemSet::on_update(MyStates::Load).with_system(timeout))
+        .add_system_set(SystemSet::on_enter(MyStates::Next).with_system(expect))
+        .run();
+}
+
+fn timeout(time: Res<Time>) {
+    if time.seconds_since_startup() > 10. {
+        panic!("The asset loader did not change the state in 10 seconds");
+    }
+}
+
+fn expect(
+    collection: Res<MyAssets>,
+    audio_sources: Res<Assets<AudioSource>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    assert!(audio_sources.get(collection.plop.clone()).is_some());
+    assert!(audio_sources.get(collection.background.clone()).is_some());
+    assert!(audio_sources.get(collection.ambience.clone()).is_some());
+    exit.send(AppExit);
+}
+
+#[derive(AssetCollection)]
+struct MyAssets {
+    #[asset(key = "plop")]
+    plop: Handle<AudioSource>,
+    #[asset(key = "background")]
+    background: Handle<AudioSource>,
+    #[asset(key = "ambience")]
+    ambience: Handle<AudioSource>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+enum MyStates {
+    Load,
+    Next,
+}
@@ -0,0 +1,98 @@
+#![allow(dead_code)]
+
+use bevy::prelude::*;
+use bevy_asset_loader::{export_asset_collection_file, AssetCollection, NamedTextureAtlas};
+
+#[test]
+fn export_asset_collection_file_from_paths() {
+    assert_eq!(
+        export_asset_collection_file::<MyAssets>(),
+        r#"({
+    "background": File (
+        path: "audio/background.ogg",
+    ),
+    "images": File (
+        path: "images",
+    ),
+    "crate_mesh": LabeledFile (
+        path: "models/crate.gltf",
+        label: "Mesh0/Primitive0",
+    ),
+    "tree": StandardMaterial (
+        path: "images/tree.png",
+    ),
+    "player": TextureAtlas (
+        path: "images/female_adventurer_sheet.png",
+        tile_size_x: 96.0,
+        tile_size_y: 99.0,
+        columns: 8,
+        rows: 1,
+        padding_x: 2.0,
+        animations: {
+            "idle": (start: 0, end: 1, fps: 1.0),
+            "walk": (start: 1, end: 8, fps: 10.0),
+        },
+    ),
+    "tiles": File (
+        path: "images",
+    ),
+})
+"#
+    );
+}
+
+#[cfg(feature = "gltf")]
+#[test]
+fn export_gltf_sub_assets_as_their_file() {
+    assert_eq!(
+        export_asset_collection_file::<GltfAssets>(),
+        r#"({
+    "crate_scene": File (
+        path: "models/crate.gltf",
+    ),
+    "materials": File (
+        path: "models/crate.gltf",
+    ),
+})
+"#
+    );
+}
+
+#[derive(AssetCollection)]
+struct MyAssets {
+    #[asset(path = "audio/background.ogg")]
+    background: Handle<AudioSource>,
+    #[asset(path = "images", folder)]
+    images: Vec<HandleUntyped>,
+    #[asset(path = "models/crate.gltf", label = "Mesh0/Primitive0")]
+    crate_mesh: Handle<Mesh>,
+    #[asset(path = "images/tree.png", standard_material)]
+    tree: Handle<StandardMaterial>,
+    #[asset(texture_atlas(
+        tile_size_x = 96.,
+        tile_size_y = 99.,
+        columns = 8,
+        rows = 1,
+        padding_x = 2.,
+        animations(
+            walk(start = 1, end = 8, fps = 10.),
+            idle(start = 0, end = 1, fps = 1.)
+        )
+    ))]
+    #[asset(path = "images/female_adventurer_sheet.png")]
+    player: NamedTextureAtlas,
+    #[asset(texture_atlas(folder = "images"))]
+    tiles: NamedTextureAtlas,
+    // fields with keys are already dynamic
+    #[asset(key = "music")]
+    music: Handle<AudioSource>,
+}
+
+#[cfg(feature = "gltf")]
+#[derive(AssetCollection)]
+struct GltfAssets {
+    #[asset(path = "models/crate.gltf", gltf(scene = "Crate"))]
+    crate_scene: Handle<Scene>,
+    #[asset(path = "models/crate.gltf", gltf(named_materials))]
+    materials: bevy::utils::HashMap<String, Handle<StandardMaterial>>,
+}
@@ -0,0 +1,69 @@
+#![allow(dead_code)]
+
+use bevy::app::AppExit;
+use bevy::asset::AssetPlugin;
+use bevy::gltf::{GltfMesh, GltfPlugin};
+use bevy::prelude::*;
+use bevy::scene::ScenePlugin;
+use bevy::utils::HashMap;
+use bevy_asset_loader::{AssetCollection, AssetLoader};
+
+#[test]
+fn gltf_sub_assets() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin)
+        .add_asset::<Mesh>()
+        .add_asset::<Image>()
+        .add_asset::<StandardMaterial>()
+        .add_plugin(ScenePlugin)
+        .add_plugin(GltfPlugin);
+
+    AssetLoader::new(MyStates::Load)
+        .continue_to_state(MyStates::Next)
+        .with_collection::<MyAssets>()
+        .build(&mut app);
+
+    app.add_state(MyStates::Load)
+        .add_system_set(SystemSet::on_update(MyStates::Load).with_system(timeout))
+        .add_system_set(SystemSet::on_enter(MyStates::Next).with_system(expect))
+        .run();
+}
+
+fn timeout(time: Res<Time>) {
+    if time.seconds_since_startup() > 10. {
+        panic!("The asset loader did not change the state in 10 seconds");
+    }
+}
+
+fn expect(
+    collection: Res<MyAssets>,
+    scenes: Res<Assets<Scene>>,
+    gltf_meshes: Res<Assets<GltfMesh>>,
+    meshes: Res<Assets<Mesh>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    assert!(scenes.get(collection.scene.clone()).is_some());
+    assert!(gltf_meshes.get(collection.mesh.clone()).is_some());
+    assert!(collection.materials.contains_key("Wood"));
+    assert!(meshes.get(collection.primitive.clone()).is_some());
+    exit.send(AppExit);
+}
+
+#[derive(AssetCollection)]
+struct MyAssets {
+    #[asset(path = "models/crate.gltf", gltf(scene = "Crate"))]
+    scene: Handle<Scene>,
+    #[asset(path = "models/crate.gltf", gltf(mesh = "Crate"))]
+    mesh: Handle<GltfMesh>,
+    #[asset(path = "models/crate.gltf", gltf(named_materials))]
+    materials: HashMap<String, Handle<StandardMaterial>>,
+    #[asset(path = "models/crate.gltf", label = "Mesh0/Primitive0")]
+    primitive: Handle<Mesh>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+enum MyStates {
+    Load,
+    Next,
+}
@@ -0,0 +1,193 @@
+#![allow(dead_code)]
+
+use bevy::asset::{AssetPlugin, LoadState};
+use bevy::audio::AudioPlugin;
+use bevy::prelude::*;
+use bevy_asset_loader::test_support::{
+    run_until_loaded, MemoryAssetIo, MemoryAssetIoPlugin, MemoryFile,
+};
+use bevy_asset_loader::{
+    AssetCollection, AssetKeys, AssetLoader, Collections, DynamicAssetCollection,
+};
+
+#[test]
+fn hot_reload_asset_collection_files() {
+    let io = memory_io();
+    let mut app = app(&io);
+    assert!(run_until_loaded(&mut app, 100).is_ok());
+    assert_eq!(sound(&app), handle(&app, "first.ogg"));
+    assert_eq!(knight_sound(&app), handle(&app, "first.ogg"));
+
+    let second = prepare_collection_file(&mut app, "second.assets");
+    replace_collection_file(&mut app, "sounds.assets", second);
+    update(&mut app, 10);
+
+    assert_eq!(sound(&app), handle(&app, "second.ogg"));
+    assert_eq!(knight_sound(&app), handle(&app, "second.ogg"));
+}
+
+#[test]
+fn removes_deleted_global_keys() {
+    let io = memory_io();
+    let mut app = app(&io);
+    assert!(run_until_loaded(&mut app, 100).is_ok());
+    let asset_keys = app.world.get_resource::<AssetKeys>().unwrap();
+    assert!(asset_keys.get_asset("global_sound").is_some());
+
+    let second = prepare_collection_file(&mut app, "second_global.assets");
+    replace_collection_file(&mut app, "global.assets", second);
+    update(&mut app, 10);
+
+    let asset_keys = app.world.get_resource::<AssetKeys>().unwrap();
+    assert!(asset_keys.get_asset("global_sound").is_none());
+}
+
+#[test]
+fn picks_up_changes_during_the_loading_state() {
+    let io = memory_io();
+    io.insert("first.ogg", MemoryFile::new(*b"first").with_delay(10));
+    let mut app = app(&io);
+    let second = prepare_collection_file(&mut app, "second.assets");
+    while app
+        .world
+        .get_resource::<AssetServer>()
+        .unwrap()
+        .get_load_state("sounds.assets")
+        != LoadState::Loaded
+    {
+        app.update();
+    }
+    assert_eq!(
+        app.world
+            .get_resource::<State<MyStates>>()
+            .unwrap()
+            .current(),
+        &MyStates::Load
+    );
+    replace_collection_file(&mut app, "sounds.assets", second);
+
+    assert!(run_until_loaded(&mut app, 100).is_ok());
+    update(&mut app, 10);
+    assert_eq!(sound(&app), handle(&app, "second.ogg"));
+}
+
+fn memory_io() -> MemoryAssetIo {
+    let io = MemoryAssetIo::default();
+    io.insert("first.ogg", MemoryFile::new(*b"first"));
+    io.insert("second.ogg", MemoryFile::new(*b"second"));
+    io.insert(
+        "sounds.assets",
+        MemoryFile::new(
+            *br#"({
+    "sound": File ( path: "first.ogg" ),
+    "knight.sound": File ( path: "first.ogg" ),
+})"#,
+        ),
+    );
+    io.insert(
+        "second.assets",
+        MemoryFile::new(
+            *br#"({
+    "sound": File ( path: "second.ogg" ),
+    "knight.sound": File ( path: "second.ogg" ),
+})"#,
+        ),
+    );
+    io.insert(
+        "global.assets",
+        MemoryFile::new(*br#"({ "global_sound": File ( path: "first.ogg" ) })"#),
+    );
+    io.insert("second_global.assets", MemoryFile::new(*b"({})"));
+
+    io
+}
+
+fn app(io: &MemoryAssetIo) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(MemoryAssetIoPlugin::new(io))
+        .add_plugin(AssetPlugin)
+        .add_plugin(AudioPlugin);
+    AssetLoader::new(MyStates::Load)
+        .continue_to_state(MyStates::Next)
+        .with_asset_collection_file("sounds.assets")
+        .with_global_asset_collection_file("global.assets")
+        .with_collection::<SoundAssets>()
+        .with_collection_instance::<SoundAssets, _>("knight".to_owned(), "knight.")
+        .hot_reload_asset_collection_files()
+        .build(&mut app);
+    app.add_state(MyStates::Load);
+
+    app
+}
+
+/// Load an asset collection file to replace another one with
+fn prepare_collection_file(app: &mut App, path: &str) -> Handle<DynamicAssetCollection> {
+    let handle: Handle<DynamicAssetCollection> =
+        app.world.get_resource::<AssetServer>().unwrap().load(path);
+    for _ in 0..10 {
+        app.update();
+    }
+
+    handle
+}
+
+/// Change the asset of an asset collection file, like a file watcher would
+fn replace_collection_file(app: &mut App, path: &str, replacement: Handle<DynamicAssetCollection>) {
+    let handle: Handle<DynamicAssetCollection> = app
+        .world
+        .get_resource::<AssetServer>()
+        .unwrap()
+        .get_handle(path);
+    let mut collections = app
+        .world
+        .get_resource_mut::<Assets<DynamicAssetCollection>>()
+        .unwrap();
+    let collection = collections
+        .remove(replacement)
+        .expect("The replacement was not loaded");
+    collections.set_untracked(handle, collection);
+}
+
+fn update(app: &mut App, frames: usize) {
+    for _ in 0..frames {
+        app.update();
+    }
+}
+
+fn handle(app: &App, path: &str) -> Handle<AudioSource> {
+    app.world
+        .get_resource::<AssetServer>()
+        .unwrap()
+        .get_handle(path)
+}
+
+fn sound(app: &App) -> Handle<AudioSource> {
+    app.world
+        .get_resource::<SoundAssets>()
+        .unwrap()
+        .sound
+        .clone()
+}
+
+fn knight_sound(app: &App) -> Handle<AudioSource> {
+    app.world
+        .get_resource::<Collections<SoundAssets, String>>()
+        .unwrap()
+        .get("knight")
+        .unwrap()
+        .sound
+        .clone()
+}
+
+#[derive(AssetCollection)]
+struct SoundAssets {
+    #[asset(key = "sound")]
+    sound: Handle<AudioSource>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+enum MyStates {
+    Load,
+    Next,
+}
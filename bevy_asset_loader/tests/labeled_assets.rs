@@ -0,0 +1,97 @@
+use bevy::asset::{AssetLoader, AssetPlugin, LoadContext, LoadedAsset};
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy::utils::BoxedFuture;
+use bevy_asset_loader::test_support::{
+    run_until_loaded, MemoryAssetIo, MemoryAssetIoPlugin, MemoryFile,
+};
+use bevy_asset_loader::AssetCollection;
+
+#[test]
+fn loads_labeled_sub_assets() {
+    let io = MemoryAssetIo::default();
+    io.insert(
+        "greetings.lines",
+        MemoryFile::new(*b"hello: Hello world\nbye: Goodbye world"),
+    );
+    io.insert(
+        "greetings.assets",
+        MemoryFile::new(
+            *br#"({
+    "bye": LabeledFile (
+        path: "greetings.lines",
+        label: "bye",
+    ),
+})"#,
+        ),
+    );
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(MemoryAssetIoPlugin::new(&io))
+        .add_plugin(AssetPlugin)
+        .add_asset::<Line>()
+        .add_asset_loader(LinesLoader);
+    bevy_asset_loader::AssetLoader::new(MyStates::Load)
+        .continue_to_state(MyStates::Next)
+        .with_asset_collection_file("greetings.assets")
+        .with_collection::<MyAssets>()
+        .build(&mut app);
+    app.add_state(MyStates::Load);
+
+    assert!(run_until_loaded(&mut app, 100).is_ok());
+    let collection = app.world.get_resource::<MyAssets>().unwrap();
+    let lines = app.world.get_resource::<Assets<Line>>().unwrap();
+    assert_eq!(
+        lines.get(collection.hello.clone()),
+        Some(&Line("Hello world".to_owned()))
+    );
+    assert_eq!(
+        lines.get(collection.bye.clone()),
+        Some(&Line("Goodbye world".to_owned()))
+    );
+}
+
+#[derive(AssetCollection)]
+struct MyAssets {
+    #[asset(path = "greetings.lines", label = "hello")]
+    hello: Handle<Line>,
+    #[asset(key = "bye")]
+    bye: Handle<Line>,
+}
+
+#[derive(Debug, PartialEq, TypeUuid)]
+#[uuid = "6f0a3d1e-8c4b-4c1e-9a57-3b2d7c9e0f41"]
+struct Line(String);
+
+/// Loads every line "label: text" of a file as a sub asset with the given label
+struct LinesLoader;
+
+impl AssetLoader for LinesLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let content = std::str::from_utf8(bytes)?;
+            for line in content.lines() {
+                let (label, text) = line
+                    .split_once(": ")
+                    .ok_or_else(|| anyhow::anyhow!("Invalid line '{}'", line))?;
+                load_context.set_labeled_asset(label, LoadedAsset::new(Line(text.to_owned())));
+            }
+            load_context.set_default_asset(LoadedAsset::new(Line(content.to_owned())));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["lines"]
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+enum MyStates {
+    Load,
+    Next,
+}
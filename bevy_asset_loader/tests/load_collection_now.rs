@@ -0,0 +1,129 @@
+#![allow(dead_code)]
+
+use bevy::asset::AssetPlugin;
+use bevy::audio::AudioPlugin;
+use bevy::prelude::*;
+use bevy_asset_loader::test_support::{MemoryAssetIo, MemoryAssetIoPlugin, MemoryFile};
+use bevy_asset_loader::{
+    AssetCollection, AssetCollectionApp, AssetCollectionWorld, LoadCollectionError,
+};
+
+#[test]
+fn blocks_until_the_collection_is_loaded() {
+    let io = MemoryAssetIo::default();
+    io.insert("audio/plop.ogg", MemoryFile::new(*b"plop").with_delay(10));
+    io.insert("audio/background.ogg", MemoryFile::new(*b"background"));
+    let mut app = app(&io);
+
+    assert_eq!(app.load_collection_now::<AudioAssets>(100), Ok(()));
+    assert_eq!(io.frame(), 11);
+    let collection = app.world.get_resource::<AudioAssets>().unwrap();
+    let audio_sources = app.world.get_resource::<Assets<AudioSource>>().unwrap();
+    assert!(audio_sources.get(collection.plop.clone()).is_some());
+    assert!(audio_sources.get(collection.background.clone()).is_some());
+}
+
+#[test]
+fn returns_the_failed_files() {
+    let io = MemoryAssetIo::default();
+    io.insert("audio/plop.ogg", MemoryFile::failing().with_delay(2));
+    let mut app = app(&io);
+
+    assert_eq!(
+        app.load_collection_now::<AudioAssets>(100),
+        Err(LoadCollectionError::Failed {
+            collection: std::any::type_name::<AudioAssets>(),
+            paths: vec![
+                "audio/background.ogg".to_owned(),
+                "audio/plop.ogg".to_owned()
+            ]
+        })
+    );
+    assert!(!app.world.contains_resource::<AudioAssets>());
+}
+
+#[test]
+fn times_out_after_the_given_frames() {
+    let io = MemoryAssetIo::default();
+    io.insert("audio/plop.ogg", MemoryFile::new(*b"plop").with_delay(10));
+    io.insert("audio/background.ogg", MemoryFile::new(*b"background"));
+    let mut app = app(&io);
+
+    assert_eq!(
+        app.load_collection_now::<AudioAssets>(5),
+        Err(LoadCollectionError::Timeout {
+            collection: std::any::type_name::<AudioAssets>(),
+            frames: 5
+        })
+    );
+    assert_eq!(io.frame(), 5);
+    assert!(!app.world.contains_resource::<AudioAssets>());
+}
+
+#[test]
+fn worlds_load_collections_without_updating_the_app() {
+    let io = MemoryAssetIo::default();
+    io.insert("audio/plop.ogg", MemoryFile::new(*b"plop"));
+    io.insert("audio/background.ogg", MemoryFile::new(*b"background"));
+    let mut app = app(&io);
+    app.world.register_asset_type::<AudioSource>();
+
+    assert_eq!(app.world.load_collection_now::<AudioAssets>(10_000), Ok(()));
+    assert_eq!(io.frame(), 0);
+    let collection = app.world.get_resource::<AudioAssets>().unwrap();
+    let audio_sources = app.world.get_resource::<Assets<AudioSource>>().unwrap();
+    assert!(audio_sources.get(collection.plop.clone()).is_some());
+    assert!(audio_sources.get(collection.background.clone()).is_some());
+}
+
+#[test]
+fn worlds_return_the_failed_files() {
+    let io = MemoryAssetIo::default();
+    io.insert("audio/plop.ogg", MemoryFile::failing());
+    io.insert("audio/background.ogg", MemoryFile::new(*b"background"));
+    let mut app = app(&io);
+    app.world.register_asset_type::<AudioSource>();
+
+    assert_eq!(
+        app.world.load_collection_now::<AudioAssets>(10_000),
+        Err(LoadCollectionError::Failed {
+            collection: std::any::type_name::<AudioAssets>(),
+            paths: vec!["audio/plop.ogg".to_owned()]
+        })
+    );
+    assert!(!app.world.contains_resource::<AudioAssets>());
+}
+
+#[test]
+fn worlds_only_update_registered_asset_types() {
+    let io = MemoryAssetIo::default();
+    io.insert("audio/plop.ogg", MemoryFile::new(*b"plop"));
+    io.insert("audio/background.ogg", MemoryFile::new(*b"background"));
+    let mut app = app(&io);
+
+    assert_eq!(
+        app.world.load_collection_now::<AudioAssets>(5),
+        Err(LoadCollectionError::Timeout {
+            collection: std::any::type_name::<AudioAssets>(),
+            frames: 5
+        })
+    );
+}
+
+fn app(io: &MemoryAssetIo) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(MemoryAssetIoPlugin::new(io))
+        .add_plugin(AssetPlugin)
+        .add_plugin(AudioPlugin);
+
+    app
+}
+
+#[derive(AssetCollection)]
+struct AudioAssets {
+    #[asset(path = "audio/plop.ogg")]
+    plop: Handle<AudioSource>,
+    #[asset(path = "audio/background.ogg")]
+    background: Handle<AudioSource>,
+}
@@ -0,0 +1,167 @@
+#![allow(dead_code)]
+
+use bevy::asset::AssetPlugin;
+use bevy::audio::AudioPlugin;
+use bevy::prelude::*;
+use bevy_asset_loader::test_support::{
+    run_until_loaded, MemoryAssetIo, MemoryAssetIoPlugin, MemoryFile,
+};
+use bevy_asset_loader::{AssetCollection, AssetLoader, CurrentLocale};
+
+#[test]
+fn localized_assets() {
+    let io = memory_io();
+    let mut app = app(&io);
+    assert!(run_until_loaded(&mut app, 100).is_ok());
+    let voice = app.world.get_resource::<VoiceAssets>().unwrap();
+    assert_eq!(voice.intro, handle(&app, "localized/de/plop.ogg"));
+    assert_eq!(voice.greeting, handle(&app, "localized/de/plop.ogg"));
+    assert_eq!(voice.music, handle(&app, "audio/plop.ogg"));
+
+    app.world
+        .get_resource_mut::<CurrentLocale>()
+        .unwrap()
+        .set_locale("en");
+    update(&mut app, 10);
+    assert_eq!(current_state(&app), &MyStates::Next);
+    let voice = app.world.get_resource::<VoiceAssets>().unwrap();
+    assert_eq!(voice.intro, handle(&app, "localized/en/plop.ogg"));
+    assert_eq!(voice.greeting, handle(&app, "localized/en/plop.ogg"));
+    assert_eq!(voice.music, handle(&app, "audio/plop.ogg"));
+}
+
+#[test]
+fn keeps_the_current_state_when_the_locale_changes() {
+    let io = memory_io();
+    let mut app = app(&io);
+    // runs before the reload system and queues a state change in the same frame
+    app.add_system(change_locale_and_state.exclusive_system());
+    assert!(run_until_loaded(&mut app, 100).is_ok());
+
+    app.insert_resource(ChangeLocale);
+    update(&mut app, 10);
+    assert_eq!(current_state(&app), &MyStates::Other);
+    let voice = app.world.get_resource::<VoiceAssets>().unwrap();
+    assert_eq!(voice.intro, handle(&app, "localized/en/plop.ogg"));
+}
+
+#[test]
+fn falls_back_along_the_locale_chain() {
+    let io = MemoryAssetIo::default();
+    io.insert("localized/en/plop.ogg", MemoryFile::new(*b"plop"));
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(MemoryAssetIoPlugin::new(&io))
+        .add_plugin(AssetPlugin)
+        .add_plugin(AudioPlugin);
+    AssetLoader::new(MyStates::Load)
+        .continue_to_state(MyStates::Next)
+        .with_collection::<IntroAssets>()
+        .build(&mut app);
+    app.insert_resource(CurrentLocale::new("de-AT").with_fallback("en"))
+        .add_state(MyStates::Load);
+
+    assert!(run_until_loaded(&mut app, 100).is_ok());
+    let intro = &app.world.get_resource::<IntroAssets>().unwrap().intro;
+    assert_eq!(intro, &handle(&app, "localized/en/plop.ogg"));
+}
+
+fn update(app: &mut App, frames: usize) {
+    for _ in 0..frames {
+        app.update();
+    }
+}
+
+fn current_state(app: &App) -> &MyStates {
+    app.world
+        .get_resource::<State<MyStates>>()
+        .unwrap()
+        .current()
+}
+
+fn memory_io() -> MemoryAssetIo {
+    let io = MemoryAssetIo::default();
+    io.insert(
+        "localized/voice.assets",
+        MemoryFile::new(include_bytes!("../assets/localized/voice.assets").to_vec()),
+    );
+    for path in [
+        "localized/de/plop.ogg",
+        "localized/en/plop.ogg",
+        "audio/plop.ogg",
+        "audio/background.ogg",
+    ] {
+        io.insert(path, MemoryFile::new(*b"plop"));
+    }
+
+    io
+}
+
+fn app(io: &MemoryAssetIo) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(MemoryAssetIoPlugin::new(io))
+        .add_plugin(AssetPlugin)
+        .add_plugin(AudioPlugin);
+    AssetLoader::new(MyStates::Load)
+        .continue_to_state(MyStates::Next)
+        .with_asset_collection_file("localized/voice.assets")
+        .with_collection::<VoiceAssets>()
+        .reload_on_locale_change()
+        .build(&mut app);
+    app.insert_resource(
+        CurrentLocale::new("de-AT")
+            .with_fallback("en")
+            .with_available_locales(&["de", "en"]),
+    )
+    .add_state(MyStates::Load);
+
+    app
+}
+
+fn handle(app: &App, path: &str) -> Handle<AudioSource> {
+    app.world
+        .get_resource::<AssetServer>()
+        .unwrap()
+        .get_handle(path)
+}
+
+struct ChangeLocale;
+
+fn change_locale_and_state(world: &mut World) {
+    if world.remove_resource::<ChangeLocale>().is_none() {
+        return;
+    }
+    world
+        .get_resource_mut::<CurrentLocale>()
+        .unwrap()
+        .set_locale("en");
+    world
+        .get_resource_mut::<State<MyStates>>()
+        .unwrap()
+        .set(MyStates::Other)
+        .unwrap();
+}
+
+#[derive(AssetCollection)]
+struct VoiceAssets {
+    #[asset(path = "localized/plop.ogg", localized)]
+    intro: Handle<AudioSource>,
+    #[asset(key = "greeting")]
+    greeting: Handle<AudioSource>,
+    #[asset(key = "music")]
+    music: Handle<AudioSource>,
+}
+
+#[derive(AssetCollection)]
+struct IntroAssets {
+    #[asset(path = "localized/plop.ogg", localized)]
+    intro: Handle<AudioSource>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+enum MyStates {
+    Load,
+    Next,
+    Other,
+}
@@ -0,0 +1,220 @@
+#![allow(dead_code)]
+
+use bevy::app::AppExit;
+use bevy::asset::AssetPlugin;
+use bevy::audio::AudioPlugin;
+use bevy::prelude::*;
+#[cfg(feature = "test_support")]
+use bevy_asset_loader::test_support::{
+    run_until_loaded, MemoryAssetIo, MemoryAssetIoPlugin, MemoryFile,
+};
+use bevy_asset_loader::{AssetCollection, AssetKeyProvenance, AssetKeys, AssetLoader};
+
+#[test]
+fn override_layers() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin)
+        .add_plugin(AudioPlugin);
+
+    AssetLoader::new(MyStates::Load)
+        .continue_to_state(MyStates::Next)
+        .with_override_layer("layers/mod.assets", 10)
+        .with_asset_collection_file("layers/base.assets")
+        .with_override_layer("layers/fallback.assets", -5)
+        .with_collection::<MyAssets>()
+        .build(&mut app);
+
+    app.add_state(MyStates::Load)
+        .add_system_set(SystemSet::on_update(MyStates::Load).with_system(timeout))
+        .add_system_set(SystemSet::on_exit(MyStates::Load).with_system(expect_provenance))
+        .add_system_set(SystemSet::on_enter(MyStates::Next).with_system(expect))
+        .run();
+}
+
+#[cfg(feature = "test_support")]
+#[test]
+fn global_and_state_keys_compete_by_priority() {
+    let io = MemoryAssetIo::default();
+    io.insert("audio/plop.ogg", MemoryFile::new(*b"plop"));
+    io.insert("audio/background.ogg", MemoryFile::new(*b"background"));
+    io.insert(
+        "global.assets",
+        MemoryFile::new(
+            *br#"({
+    "music": File (path: "audio/plop.ogg"),
+    "click": File (path: "audio/plop.ogg"),
+    "ambience": File (path: "audio/plop.ogg"),
+})"#,
+        ),
+    );
+    io.insert(
+        "state.assets",
+        MemoryFile::new(
+            *br#"({
+    "click": File (path: "audio/background.ogg"),
+})"#,
+        ),
+    );
+    io.insert(
+        "fallback.assets",
+        MemoryFile::new(
+            *br#"({
+    "music": File (path: "audio/background.ogg"),
+})"#,
+        ),
+    );
+    let mut app = memory_app(&io);
+    AssetLoader::new(MyStates::Load)
+        .continue_to_state(MyStates::Next)
+        .with_global_asset_collection_file("global.assets")
+        .with_asset_collection_file("state.assets")
+        .with_override_layer("fallback.assets", -5)
+        .with_collection::<MyAssets>()
+        .build(&mut app);
+    app.add_state(MyStates::Load);
+
+    assert!(run_until_loaded(&mut app, 100).is_ok());
+    let collection = app.world.get_resource::<MyAssets>().unwrap();
+    let asset_server = app.world.get_resource::<AssetServer>().unwrap();
+    // the global file has a higher priority than the fallback layer
+    assert_eq!(collection.music, asset_server.get_handle("audio/plop.ogg"));
+    // with the same priority, the file of the loading state wins
+    assert_eq!(
+        collection.click,
+        asset_server.get_handle("audio/background.ogg")
+    );
+    assert_eq!(
+        collection.ambience,
+        asset_server.get_handle("audio/plop.ogg")
+    );
+}
+
+#[cfg(feature = "test_support")]
+#[test]
+fn provenance_names_the_included_file() {
+    let io = MemoryAssetIo::default();
+    io.insert("audio/plop.ogg", MemoryFile::new(*b"plop"));
+    io.insert("audio/background.ogg", MemoryFile::new(*b"background"));
+    io.insert(
+        "level.assets",
+        MemoryFile::new(
+            *br##"({
+    "#includes": ["common.assets"],
+    "music": File (path: "audio/plop.ogg"),
+})"##,
+        ),
+    );
+    io.insert(
+        "common.assets",
+        MemoryFile::new(
+            *br#"({
+    "click": File (path: "audio/background.ogg"),
+    "ambience": File (path: "audio/background.ogg"),
+})"#,
+        ),
+    );
+    let mut app = memory_app(&io);
+    AssetLoader::new(MyStates::Load)
+        .continue_to_state(MyStates::Next)
+        .with_global_asset_collection_file("level.assets")
+        .with_collection::<MyAssets>()
+        .build(&mut app);
+    app.add_state(MyStates::Load);
+
+    assert!(run_until_loaded(&mut app, 100).is_ok());
+    let asset_keys = app.world.get_resource::<AssetKeys>().unwrap();
+    assert_eq!(
+        asset_keys.provenance("music"),
+        Some(&AssetKeyProvenance::Layer {
+            path: "level.assets".to_owned(),
+            file: "level.assets".to_owned(),
+            priority: 0
+        })
+    );
+    assert_eq!(
+        asset_keys.provenance("click"),
+        Some(&AssetKeyProvenance::Layer {
+            path: "level.assets".to_owned(),
+            file: "common.assets".to_owned(),
+            priority: 0
+        })
+    );
+}
+
+#[cfg(feature = "test_support")]
+fn memory_app(io: &MemoryAssetIo) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(MemoryAssetIoPlugin::new(io))
+        .add_plugin(AssetPlugin)
+        .add_plugin(AudioPlugin);
+
+    app
+}
+
+fn timeout(time: Res<Time>) {
+    if time.seconds_since_startup() > 10. {
+        panic!("The asset loader did not change the state in 10 seconds");
+    }
+}
+
+fn expect_provenance(asset_keys: Res<AssetKeys>) {
+    assert_eq!(
+        asset_keys.provenance("music"),
+        Some(&AssetKeyProvenance::Layer {
+            path: "layers/mod.assets".to_owned(),
+            file: "layers/mod.assets".to_owned(),
+            priority: 10
+        })
+    );
+    assert_eq!(
+        asset_keys.provenance("click"),
+        Some(&AssetKeyProvenance::Layer {
+            path: "layers/base.assets".to_owned(),
+            file: "layers/base.assets".to_owned(),
+            priority: 0
+        })
+    );
+    assert_eq!(
+        asset_keys.provenance("ambience"),
+        Some(&AssetKeyProvenance::Layer {
+            path: "layers/fallback.assets".to_owned(),
+            file: "layers/fallback.assets".to_owned(),
+            priority: -5
+        })
+    );
+}
+
+fn expect(
+    collection: Res<MyAssets>,
+    asset_server: Res<AssetServer>,
+    mut exit: EventWriter<AppExit>,
+) {
+    assert_eq!(collection.music, asset_server.get_handle("audio/plop.ogg"));
+    assert_eq!(
+        collection.click,
+        asset_server.get_handle("audio/background.ogg")
+    );
+    assert_eq!(
+        collection.ambience,
+        asset_server.get_handle("audio/plop.ogg")
+    );
+    exit.send(AppExit);
+}
+
+#[derive(AssetCollection)]
+struct MyAssets {
+    #[asset(key = "music")]
+    music: Handle<AudioSource>,
+    #[asset(key = "click")]
+    click: Handle<AudioSource>,
+    #[asset(key = "ambience")]
+    ambience: Handle<AudioSource>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+enum MyStates {
+    Load,
+    Next,
+}
@@ -0,0 +1,83 @@
+#![allow(dead_code)]
+
+use bevy::app::AppExit;
+use bevy::asset::AssetPlugin;
+use bevy::audio::AudioPlugin;
+use bevy::prelude::*;
+use bevy_asset_loader::{AssetCollection, AssetLoader};
+
+#[test]
+fn scoped_dynamic_assets() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin)
+        .add_plugin(AudioPlugin);
+
+    AssetLoader::new(MyStates::LevelOne)
+        .continue_to_state(MyStates::LevelTwo)
+        .with_asset_collection_file("scoped/level_one.assets")
+        .with_global_asset_collection_file("scoped/global.assets")
+        .with_collection::<LevelOneAssets>()
+        .build(&mut app);
+    AssetLoader::new(MyStates::LevelTwo)
+        .continue_to_state(MyStates::Next)
+        .with_asset_collection_file("scoped/level_two.assets")
+        .with_collection::<LevelTwoAssets>()
+        .build(&mut app);
+
+    app.add_state(MyStates::LevelOne)
+        .add_system_set(SystemSet::on_update(MyStates::LevelOne).with_system(timeout))
+        .add_system_set(SystemSet::on_update(MyStates::LevelTwo).with_system(timeout))
+        .add_system_set(SystemSet::on_enter(MyStates::Next).with_system(expect))
+        .run();
+}
+
+fn timeout(time: Res<Time>) {
+    if time.seconds_since_startup() > 10. {
+        panic!("The asset loader did not change the state in 10 seconds");
+    }
+}
+
+fn expect(
+    level_one: Res<LevelOneAssets>,
+    level_two: Res<LevelTwoAssets>,
+    asset_server: Res<AssetServer>,
+    mut exit: EventWriter<AppExit>,
+) {
+    assert_eq!(
+        level_one.music,
+        asset_server.get_handle("audio/background.ogg")
+    );
+    assert_eq!(level_two.music, asset_server.get_handle("audio/plop.ogg"));
+    assert!(
+        level_two.level_one_sound.is_none(),
+        "Key of the first loading state leaked into the second one"
+    );
+    assert_eq!(level_two.click, asset_server.get_handle("audio/plop.ogg"));
+    exit.send(AppExit);
+}
+
+#[derive(AssetCollection)]
+struct LevelOneAssets {
+    #[asset(key = "level.music")]
+    music: Handle<AudioSource>,
+    #[asset(key = "level_one.sound")]
+    sound: Handle<AudioSource>,
+}
+
+#[derive(AssetCollection)]
+struct LevelTwoAssets {
+    #[asset(key = "level.music")]
+    music: Handle<AudioSource>,
+    #[asset(key = "level_one.sound", optional)]
+    level_one_sound: Option<Handle<AudioSource>>,
+    #[asset(key = "ui.click")]
+    click: Handle<AudioSource>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+enum MyStates {
+    LevelOne,
+    LevelTwo,
+    Next,
+}
@@ -0,0 +1,174 @@
+#![allow(dead_code)]
+
+use bevy::asset::AssetPlugin;
+use bevy::audio::AudioPlugin;
+use bevy::prelude::*;
+use bevy_asset_loader::test_support::{
+    run_until_loaded, MemoryAssetIo, MemoryAssetIoPlugin, MemoryFile, RunUntilLoadedError,
+};
+use bevy_asset_loader::{AssetCollection, AssetLoader};
+
+#[test]
+fn loads_collections_from_memory() {
+    let io = MemoryAssetIo::default();
+    io.insert("audio/plop.ogg", MemoryFile::new(*b"plop"));
+    io.insert("audio/background.ogg", MemoryFile::new(*b"background"));
+    let mut app = app(&io);
+
+    assert!(run_until_loaded(&mut app, 100).is_ok());
+    assert!(app.world.contains_resource::<PlopAudio>());
+    assert!(app.world.contains_resource::<BackgroundAudio>());
+    assert_eq!(
+        app.world
+            .get_resource::<State<MyStates>>()
+            .unwrap()
+            .current(),
+        &MyStates::Next
+    );
+}
+
+#[test]
+fn delays_files_by_frames() {
+    let io = MemoryAssetIo::default();
+    io.insert("audio/plop.ogg", MemoryFile::new(*b"plop").with_delay(10));
+    io.insert("audio/background.ogg", MemoryFile::new(*b"background"));
+    let mut app = app(&io);
+
+    assert_eq!(
+        run_until_loaded(&mut app, 9),
+        Err(RunUntilLoadedError::Timeout { frames: 9 })
+    );
+    assert!(!app.world.contains_resource::<PlopAudio>());
+
+    assert_eq!(run_until_loaded(&mut app, 100), Ok(2));
+    assert!(app.world.contains_resource::<PlopAudio>());
+}
+
+#[test]
+fn frames_only_depend_on_delays() {
+    for delay in [1, 3, 7] {
+        let io = MemoryAssetIo::default();
+        io.insert(
+            "audio/plop.ogg",
+            MemoryFile::new(*b"plop").with_delay(delay),
+        );
+        io.insert("audio/background.ogg", MemoryFile::new(*b"background"));
+        let mut app = app(&io);
+
+        assert_eq!(run_until_loaded(&mut app, 100), Ok(delay + 1));
+    }
+}
+
+#[test]
+fn finishes_loading_states_without_collections() {
+    let io = MemoryAssetIo::default();
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(MemoryAssetIoPlugin::new(&io))
+        .add_plugin(AssetPlugin);
+    AssetLoader::new(MyStates::Load)
+        .continue_to_state(MyStates::Next)
+        .build(&mut app);
+    app.add_state(MyStates::Load);
+
+    assert!(run_until_loaded(&mut app, 10).is_ok());
+    assert_eq!(
+        app.world
+            .get_resource::<State<MyStates>>()
+            .unwrap()
+            .current(),
+        &MyStates::Next
+    );
+}
+
+#[cfg(feature = "dynamic_assets")]
+#[test]
+fn reports_failing_asset_collection_files() {
+    let io = MemoryAssetIo::default();
+    io.insert("audio/plop.ogg", MemoryFile::new(*b"plop"));
+    io.insert("audio/background.ogg", MemoryFile::new(*b"background"));
+    io.insert("collection.assets", MemoryFile::failing());
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(MemoryAssetIoPlugin::new(&io))
+        .add_plugin(AssetPlugin)
+        .add_plugin(AudioPlugin);
+    AssetLoader::new(MyStates::Load)
+        .continue_to_state(MyStates::Next)
+        .with_asset_collection_file("collection.assets")
+        .with_collection::<PlopAudio>()
+        .build(&mut app);
+    app.add_state(MyStates::Load);
+
+    assert_eq!(
+        run_until_loaded(&mut app, 100),
+        Err(RunUntilLoadedError::Failed {
+            paths: vec!["collection.assets".to_owned()]
+        })
+    );
+}
+
+#[test]
+fn reports_failing_files() {
+    let io = MemoryAssetIo::default();
+    io.insert("audio/plop.ogg", MemoryFile::failing().with_delay(2));
+    io.insert("audio/background.ogg", MemoryFile::new(*b"background"));
+    let mut app = app(&io);
+
+    assert_eq!(
+        run_until_loaded(&mut app, 100),
+        Err(RunUntilLoadedError::Failed {
+            paths: vec!["audio/plop.ogg".to_owned()]
+        })
+    );
+    assert!(!app.world.contains_resource::<PlopAudio>());
+}
+
+#[test]
+fn reports_missing_files() {
+    let io = MemoryAssetIo::default();
+    io.insert("audio/plop.ogg", MemoryFile::new(*b"plop"));
+    let mut app = app(&io);
+
+    assert_eq!(
+        run_until_loaded(&mut app, 100),
+        Err(RunUntilLoadedError::Failed {
+            paths: vec!["audio/background.ogg".to_owned()]
+        })
+    );
+}
+
+fn app(io: &MemoryAssetIo) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(MemoryAssetIoPlugin::new(io))
+        .add_plugin(AssetPlugin)
+        .add_plugin(AudioPlugin);
+
+    AssetLoader::new(MyStates::Load)
+        .continue_to_state(MyStates::Next)
+        .with_collection::<PlopAudio>()
+        .with_collection::<BackgroundAudio>()
+        .build(&mut app);
+    app.add_state(MyStates::Load);
+
+    app
+}
+
+#[derive(AssetCollection)]
+struct PlopAudio {
+    #[asset(path = "audio/plop.ogg")]
+    plop: Handle<AudioSource>,
+}
+
+#[derive(AssetCollection)]
+struct BackgroundAudio {
+    #[asset(path = "audio/background.ogg")]
+    background: Handle<AudioSource>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+enum MyStates {
+    Load,
+    Next,
+}
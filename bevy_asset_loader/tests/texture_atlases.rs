@@ -0,0 +1,358 @@
+use bevy::asset::{AssetPlugin, LoadContext, LoadedAsset};
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::utils::BoxedFuture;
+#[cfg(feature = "dynamic_assets")]
+use bevy_asset_loader::test_support::{run_until_loaded, RunUntilLoadedError};
+use bevy_asset_loader::test_support::{MemoryAssetIo, MemoryAssetIoPlugin, MemoryFile};
+#[cfg(feature = "dynamic_assets")]
+use bevy_asset_loader::AssetLoader;
+use bevy_asset_loader::{
+    AnimationClip, AnimationClips, AssetCollection, AssetCollectionApp, DynamicAssetValue,
+    FromDynamicAssetValue, NamedTextureAtlas,
+};
+
+#[test]
+fn names_the_textures_after_their_images() {
+    let io = MemoryAssetIo::default();
+    io.insert("images/tiles/grass.png", MemoryFile::new(*b"32x16"));
+    io.insert("images/tiles/water.png", MemoryFile::new(*b"16x48"));
+    let mut app = app(&io);
+
+    assert_eq!(app.load_collection_now::<FolderAssets>(10), Ok(()));
+    let tiles = &app.world.get_resource::<FolderAssets>().unwrap().tiles;
+    let atlases = app.world.get_resource::<Assets<TextureAtlas>>().unwrap();
+    let atlas = atlases.get(tiles.atlas.clone()).unwrap();
+    assert_eq!(atlas.len(), 2);
+    let grass = atlas.textures[tiles.index("grass").unwrap()];
+    assert_eq!(grass.max - grass.min, Vec2::new(32., 16.));
+    let water = atlas.textures[tiles.index("water").unwrap()];
+    assert_eq!(water.max - water.min, Vec2::new(16., 48.));
+    assert_eq!(tiles.index("stone"), None);
+}
+
+#[test]
+fn names_images_in_subfolders_after_their_relative_path() {
+    let io = MemoryAssetIo::default();
+    io.insert("images/tiles/walk/01.png", MemoryFile::new(*b"32x16"));
+    io.insert("images/tiles/jump/01.png", MemoryFile::new(*b"16x48"));
+    let mut app = app(&io);
+
+    assert_eq!(app.load_collection_now::<FolderAssets>(10), Ok(()));
+    let tiles = &app.world.get_resource::<FolderAssets>().unwrap().tiles;
+    let atlases = app.world.get_resource::<Assets<TextureAtlas>>().unwrap();
+    let atlas = atlases.get(tiles.atlas.clone()).unwrap();
+    assert_eq!(atlas.len(), 2);
+    let walk = atlas.textures[tiles.index("walk/01").unwrap()];
+    assert_eq!(walk.max - walk.min, Vec2::new(32., 16.));
+    let jump = atlas.textures[tiles.index("jump/01").unwrap()];
+    assert_eq!(jump.max - jump.min, Vec2::new(16., 48.));
+}
+
+#[test]
+fn rejects_images_with_the_same_name() {
+    let io = MemoryAssetIo::default();
+    io.insert("images/tiles/summer/grass.png", MemoryFile::new(*b"32x16"));
+    io.insert("images/tiles/winter/grass.png", MemoryFile::new(*b"32x16"));
+    let mut app = app(&io);
+    assert_eq!(app.load_collection_now::<FolderAssets>(10), Ok(()));
+    let asset_server = app.world.get_resource::<AssetServer>().unwrap().clone();
+    let cell = app.world.cell();
+    let mut images = cell.get_resource_mut::<Assets<Image>>().unwrap();
+    let mut atlases = cell.get_resource_mut::<Assets<TextureAtlas>>().unwrap();
+
+    let handles = vec![
+        asset_server.get_handle_untyped("images/tiles/summer/grass.png"),
+        asset_server.get_handle_untyped("images/tiles/winter/grass.png"),
+    ];
+    assert_eq!(
+        NamedTextureAtlas::from_images(handles, &asset_server, &mut images, &mut atlases)
+            .unwrap_err(),
+        "The images 'images/tiles/summer/grass.png' and 'images/tiles/winter/grass.png' are both named 'grass'"
+    );
+}
+
+#[test]
+fn folder_atlases_are_stitched_again_when_an_image_is_modified() {
+    let io = MemoryAssetIo::default();
+    io.insert("images/tiles/grass.png", MemoryFile::new(*b"32x16"));
+    io.insert("images/tiles/water.png", MemoryFile::new(*b"16x48"));
+    let mut app = app(&io);
+    assert_eq!(app.load_collection_now::<FolderAssets>(10), Ok(()));
+    let tiles = app
+        .world
+        .get_resource::<FolderAssets>()
+        .unwrap()
+        .tiles
+        .clone();
+    let grass: Handle<Image> = app
+        .world
+        .get_resource::<AssetServer>()
+        .unwrap()
+        .get_handle("images/tiles/grass.png");
+
+    let _ = app
+        .world
+        .get_resource_mut::<Assets<Image>>()
+        .unwrap()
+        .set(grass, filled_image(64, 64));
+    for _ in 0..2 {
+        app.update();
+    }
+
+    let atlases = app.world.get_resource::<Assets<TextureAtlas>>().unwrap();
+    let atlas = atlases.get(tiles.atlas.clone()).unwrap();
+    assert_eq!(atlas.len(), 2);
+    let grass = atlas.textures[tiles.index("grass").unwrap()];
+    assert_eq!(grass.max - grass.min, Vec2::new(64., 64.));
+    let water = atlas.textures[tiles.index("water").unwrap()];
+    assert_eq!(water.max - water.min, Vec2::new(16., 48.));
+}
+
+#[test]
+fn keeps_the_animation_clips_of_grid_atlases() {
+    let io = MemoryAssetIo::default();
+    io.insert("images/sheet.png", MemoryFile::new(*b"64x32"));
+    let mut app = app(&io);
+
+    assert_eq!(app.load_collection_now::<GridAssets>(10), Ok(()));
+    let sheet = &app.world.get_resource::<GridAssets>().unwrap().sheet;
+    assert_eq!(
+        sheet.animation("walk"),
+        Some(&AnimationClip {
+            start: 1,
+            end: 4,
+            fps: 10.
+        })
+    );
+}
+
+#[cfg(feature = "dynamic_assets")]
+#[test]
+fn keeps_the_animation_clips_of_dynamic_atlases() {
+    let io = MemoryAssetIo::default();
+    io.insert("images/sheet.png", MemoryFile::new(*b"64x32"));
+    io.insert(
+        "sheet.assets",
+        MemoryFile::new(
+            *br#"({
+    "sheet": TextureAtlas (
+        path: "images/sheet.png",
+        tile_size_x: 16.0,
+        tile_size_y: 16.0,
+        columns: 4,
+        rows: 2,
+        animations: {
+            "walk": (start: 1, end: 4, fps: 10.0),
+        },
+    ),
+})"#,
+        ),
+    );
+    let mut app = app(&io);
+    AssetLoader::new(MyStates::Load)
+        .continue_to_state(MyStates::Next)
+        .with_asset_collection_file("sheet.assets")
+        .with_collection::<DynamicAssets>()
+        .build(&mut app);
+    app.add_state(MyStates::Load);
+
+    assert!(run_until_loaded(&mut app, 100).is_ok());
+    let sheet = &app.world.get_resource::<DynamicAssets>().unwrap().sheet;
+    assert_eq!(
+        sheet.animation("walk"),
+        Some(&AnimationClip {
+            start: 1,
+            end: 4,
+            fps: 10.
+        })
+    );
+}
+
+#[cfg(feature = "dynamic_assets")]
+#[test]
+fn invalid_animation_clips_fail_to_load() {
+    for clip in [
+        "(start: 4, end: 4, fps: 10.0)",
+        "(start: 1, end: 4, fps: 0.0)",
+        "(start: 6, end: 9, fps: 10.0)",
+    ] {
+        let io = MemoryAssetIo::default();
+        io.insert("images/sheet.png", MemoryFile::new(*b"64x32"));
+        io.insert(
+            "sheet.assets",
+            MemoryFile::new(format!(
+                r#"({{
+    "sheet": TextureAtlas (
+        path: "images/sheet.png",
+        tile_size_x: 16.0,
+        tile_size_y: 16.0,
+        columns: 4,
+        rows: 2,
+        animations: {{ "walk": {} }},
+    ),
+}})"#,
+                clip
+            )),
+        );
+        let mut app = app(&io);
+        AssetLoader::new(MyStates::Load)
+            .continue_to_state(MyStates::Next)
+            .with_asset_collection_file("sheet.assets")
+            .with_collection::<DynamicAssets>()
+            .build(&mut app);
+        app.add_state(MyStates::Load);
+
+        assert_eq!(
+            run_until_loaded(&mut app, 100),
+            Err(RunUntilLoadedError::Failed {
+                paths: vec!["sheet.assets".to_owned()]
+            }),
+            "{}",
+            clip
+        );
+    }
+}
+
+#[test]
+fn animation_clips_need_to_fit_the_atlas() {
+    let io = MemoryAssetIo::default();
+    io.insert("images/sheet.png", MemoryFile::new(*b"64x32"));
+    let mut app = app(&io);
+    assert_eq!(app.load_collection_now::<GridAssets>(10), Ok(()));
+    let sheet = app
+        .world
+        .get_resource::<GridAssets>()
+        .unwrap()
+        .sheet
+        .clone();
+    let atlases = app.world.get_resource::<Assets<TextureAtlas>>().unwrap();
+    let mut animations = AnimationClips::default();
+    animations.insert(
+        "run",
+        AnimationClip {
+            start: 4,
+            end: 9,
+            fps: 10.,
+        },
+    );
+
+    assert_eq!(
+        sheet.with_animations(animations, atlases).unwrap_err(),
+        "The animation 'run' with frames 4..9 does not fit a texture atlas with 8 textures"
+    );
+}
+
+#[test]
+fn atlases_with_animation_clips_do_not_fit_handles() {
+    let mut animations = AnimationClips::default();
+    animations.insert(
+        "walk",
+        AnimationClip {
+            start: 0,
+            end: 1,
+            fps: 10.,
+        },
+    );
+    let with_animations = NamedTextureAtlas {
+        animations,
+        ..Default::default()
+    };
+
+    assert!(
+        Handle::<TextureAtlas>::from_dynamic_asset_value(DynamicAssetValue::TextureAtlas(
+            NamedTextureAtlas::default()
+        ))
+        .is_some()
+    );
+    assert!(
+        Handle::<TextureAtlas>::from_dynamic_asset_value(DynamicAssetValue::TextureAtlas(
+            with_animations
+        ))
+        .is_none()
+    );
+}
+
+fn app(io: &MemoryAssetIo) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(MemoryAssetIoPlugin::new(io))
+        .add_plugin(AssetPlugin)
+        .add_asset::<Image>()
+        .add_asset::<TextureAtlas>()
+        .add_asset::<StandardMaterial>()
+        .add_asset_loader(FilledImageLoader);
+
+    app
+}
+
+#[derive(AssetCollection)]
+struct FolderAssets {
+    #[asset(texture_atlas(folder = "images/tiles"))]
+    tiles: NamedTextureAtlas,
+}
+
+#[derive(AssetCollection)]
+struct GridAssets {
+    #[asset(texture_atlas(
+        tile_size_x = 16.,
+        tile_size_y = 16.,
+        columns = 4,
+        rows = 2,
+        animations(walk(start = 1, end = 4, fps = 10.))
+    ))]
+    #[asset(path = "images/sheet.png")]
+    sheet: NamedTextureAtlas,
+}
+
+#[cfg(feature = "dynamic_assets")]
+#[derive(AssetCollection)]
+struct DynamicAssets {
+    #[asset(key = "sheet")]
+    sheet: NamedTextureAtlas,
+}
+
+#[cfg(feature = "dynamic_assets")]
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+enum MyStates {
+    Load,
+    Next,
+}
+
+/// Loads "png" files with the content "WIDTHxHEIGHT" as white images of that size
+struct FilledImageLoader;
+
+impl bevy::asset::AssetLoader for FilledImageLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let size = std::str::from_utf8(bytes)?;
+            let (width, height) = size
+                .split_once('x')
+                .ok_or_else(|| anyhow::anyhow!("Invalid image size '{}'", size))?;
+            let image = filled_image(width.parse()?, height.parse()?);
+            load_context.set_default_asset(LoadedAsset::new(image));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["png"]
+    }
+}
+
+fn filled_image(width: u32, height: u32) -> Image {
+    Image::new_fill(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[255, 255, 255, 255],
+        TextureFormat::Rgba8UnormSrgb,
+    )
+}
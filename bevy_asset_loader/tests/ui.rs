@@ -5,7 +5,7 @@ fn ui_with_render_feature() {
     t.compile_fail("tests/ui_with_render_feature/*.rs");
 }
 
-#[cfg(not(feature = "render"))]
+#[cfg(not(any(feature = "render", feature = "gltf")))]
 #[test]
 fn ui() {
     let t = trybuild::TestCases::new();
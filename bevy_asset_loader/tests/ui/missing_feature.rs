@@ -9,4 +9,6 @@ struct Test {
     atlas: Handle<TextureAtlas>,
     #[asset(standard_material)]
     material: Handle<StandardMaterial>,
+    #[asset(path = "models/crate.gltf", gltf(scene = "Crate"))]
+    scene: Handle<Scene>,
 }
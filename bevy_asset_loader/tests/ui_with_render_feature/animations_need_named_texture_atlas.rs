@@ -0,0 +1,14 @@
+use bevy_asset_loader::*;
+use bevy::prelude::*;
+
+fn main() {}
+
+#[derive(AssetCollection)]
+struct Test {
+    #[asset(texture_atlas(
+        tile_size_x = 16., tile_size_y = 16., columns = 4, rows = 1,
+        animations(walk(start = 0, end = 4, fps = 10.))
+    ))]
+    #[asset(path = "test.png")]
+    test: Handle<TextureAtlas>
+}
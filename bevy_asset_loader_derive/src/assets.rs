@@ -1,4 +1,4 @@
-use crate::{ParseFieldError, TextureAtlasAttribute, TEXTURE_ATLAS_ATTRIBUTE};
+use crate::{ParseFieldError, TextureAtlasAttribute, PATH_ATTRIBUTE, TEXTURE_ATLAS_ATTRIBUTE};
 use proc_macro2::{Ident, TokenStream};
 use quote::quote;
 
@@ -12,6 +12,39 @@ pub(crate) struct TextureAtlasAssetField {
     pub rows: usize,
     pub padding_x: f32,
     pub padding_y: f32,
+    pub offset_x: f32,
+    pub offset_y: f32,
+    pub count: Option<usize>,
+    pub animations: Vec<AnimationClipField>,
+}
+
+#[derive(PartialEq, Debug)]
+pub(crate) struct PackedTextureAtlasAssetField {
+    pub field_ident: Ident,
+    pub asset_path: String,
+    pub layout_path: String,
+    pub animations: Vec<AnimationClipField>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub(crate) struct AnimationClipField {
+    pub name: String,
+    pub start: usize,
+    pub end: usize,
+    pub fps: f32,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(not(feature = "gltf"), allow(dead_code))]
+pub(crate) enum GltfSubAsset {
+    Scene(String),
+    Mesh(String),
+    Material(String),
+    Node(String),
+    NamedScenes,
+    NamedMeshes,
+    NamedMaterials,
+    NamedNodes,
 }
 
 #[derive(PartialEq, Debug)]
@@ -35,6 +68,10 @@ pub(crate) enum AssetField {
     StandardMaterial(BasicAssetField),
     Folder(BasicAssetField, Typed),
     TextureAtlas(TextureAtlasAssetField),
+    FolderTextureAtlas(BasicAssetField),
+    PackedTextureAtlas(PackedTextureAtlasAssetField),
+    GltfSubAsset(BasicAssetField, GltfSubAsset),
+    Labeled(BasicAssetField, String),
 }
 
 #[derive(PartialEq, Debug)]
@@ -57,39 +94,15 @@ impl AssetField {
         &self,
         token_stream: TokenStream,
     ) -> TokenStream {
-        #[allow(unused_mut, unused_assignments)]
-        let mut conditional_dynamic_asset_collections = quote! {};
-
-        #[cfg(feature = "render")]
-        {
-            conditional_dynamic_asset_collections = quote! {
-            bevy_asset_loader::DynamicAsset::TextureAtlas {
-                path,
-                tile_size_x,
-                tile_size_y,
-                columns,
-                rows,
-                padding_x,
-                padding_y,
-            } => atlases.add(TextureAtlas::from_grid_with_padding(
-                asset_server.get_handle(path),
-                Vec2::new(*tile_size_x, *tile_size_y),
-                *columns,
-                *rows,
-                Vec2::new(padding_x.unwrap_or(0.), padding_y.unwrap_or(0.)),
-            )).clone_untyped(),
-            bevy_asset_loader::DynamicAsset::StandardMaterial { path } => materials.add(asset_server.get_handle::<bevy::prelude::Image, &String>(path).into()).clone_untyped(),};
-        }
-
         match self {
             AssetField::Basic(basic) => {
                 let field_ident = basic.field_ident.clone();
-                let asset_path = basic.asset_path.clone();
+                let asset_path = path_tokens(&basic.asset_path);
                 quote!(#token_stream #field_ident : asset_server.get_handle(#asset_path),)
             }
             AssetField::Folder(basic, typed) => {
                 let field_ident = basic.field_ident.clone();
-                let asset_path = basic.asset_path.clone();
+                let asset_path = path_tokens(&basic.asset_path);
                 match typed {
                     Typed::Yes => {
                         quote!(#token_stream #field_ident : asset_server.load_folder(#asset_path)
@@ -106,27 +119,23 @@ impl AssetField {
             }
             AssetField::Dynamic(dynamic) => {
                 let field_ident = dynamic.field_ident.clone();
+                let field_name = field_ident.to_string();
                 let asset_key = dynamic.key.clone();
                 quote!(#token_stream #field_ident : {
                     let asset = asset_keys.get_asset(#asset_key.into()).unwrap_or_else(|| panic!("Failed to get asset for key '{}'", #asset_key));
-                    let handle = match asset {
-                        bevy_asset_loader::DynamicAsset::File { path } => asset_server.get_handle_untyped(path),
-                        #conditional_dynamic_asset_collections
-                    };
-                    handle.typed()
+                    bevy_asset_loader::FromDynamicAssetValue::from_dynamic_asset_value(asset.create(&cell))
+                        .unwrap_or_else(|| panic!("The asset '{}' does not fit the type of the field '{}'", #asset_key, #field_name))
                 },)
             }
             AssetField::OptionalDynamic(dynamic) => {
                 let field_ident = dynamic.field_ident.clone();
+                let field_name = field_ident.to_string();
                 let asset_key = dynamic.key.clone();
                 quote!(#token_stream #field_ident : {
                     let asset = asset_keys.get_asset(#asset_key.into());
                     asset.map(|asset| {
-                        let handle = match asset {
-                            bevy_asset_loader::DynamicAsset::File { path } => asset_server.get_handle_untyped(path),
-                            #conditional_dynamic_asset_collections
-                        };
-                        handle.typed()
+                        bevy_asset_loader::FromDynamicAssetValue::from_dynamic_asset_value(asset.create(&cell))
+                            .unwrap_or_else(|| panic!("The asset '{}' does not fit the type of the field '{}'", #asset_key, #field_name))
                     })
                 },)
             }
@@ -135,70 +144,204 @@ impl AssetField {
                 let asset_key = dynamic.key.clone();
                 let load = match typed {
                     Typed::Yes => {
-                        quote!(bevy_asset_loader::DynamicAsset::File { path } => asset_server.load_folder(path)
+                        quote!(asset_server
+                            .load_folder(path)
                             .unwrap()
                             .drain(..)
                             .map(|handle| handle.typed())
-                            .collect()
-                        )
+                            .collect())
                     }
                     Typed::No => {
-                        quote!(bevy_asset_loader::DynamicAsset::File { path } => asset_server.load_folder(path).unwrap())
+                        quote!(asset_server.load_folder(path).unwrap())
                     }
                 };
                 quote!(#token_stream #field_ident : {
                     let asset = asset_keys.get_asset(#asset_key.into()).unwrap_or_else(|| panic!("Failed to get asset for key '{}'", #asset_key));
-                    match asset {
-                        #load,
-                        _ => panic!("The asset '{}' cannot be loaded as a folder, because it is not of the type 'File'", #asset_key)
-                    }
+                    let path = asset.folder_path().unwrap_or_else(|| panic!("The asset '{}' cannot be loaded as a folder", #asset_key));
+                    let path = bevy_asset_loader::AssetVariables::resolve_path(&cell, path);
+                    let path = path.as_str();
+                    #load
                 },)
             }
             AssetField::StandardMaterial(basic) => {
                 let field_ident = basic.field_ident.clone();
-                let asset_path = basic.asset_path.clone();
-                quote!(#token_stream #field_ident : materials.add(asset_server.get_handle(#asset_path).into()),)
+                let asset_path = path_tokens(&basic.asset_path);
+                quote!(#token_stream #field_ident : {
+                    let mut materials = cell
+                        .get_resource_mut::<Assets<StandardMaterial>>()
+                        .expect("Cannot get resource Assets<StandardMaterial>");
+                    let image = asset_server.get_handle(#asset_path);
+                    let material = materials.add(image.clone().into());
+                    bevy_asset_loader::DerivedAssets::track(
+                        &cell,
+                        bevy_asset_loader::DerivedAsset::StandardMaterial {
+                            material: material.clone(),
+                            image,
+                        },
+                    );
+                    material
+                },)
             }
             AssetField::TextureAtlas(texture_atlas) => {
                 let field_ident = texture_atlas.field_ident.clone();
-                let asset_path = texture_atlas.asset_path.clone();
+                let asset_path = path_tokens(&texture_atlas.asset_path);
                 let tile_size_x = texture_atlas.tile_size_x;
                 let tile_size_y = texture_atlas.tile_size_y;
                 let columns = texture_atlas.columns;
                 let rows = texture_atlas.rows;
                 let padding_x = texture_atlas.padding_x;
                 let padding_y = texture_atlas.padding_y;
+                let offset_x = texture_atlas.offset_x;
+                let offset_y = texture_atlas.offset_y;
+                let with_animations = with_animations(&texture_atlas.animations, &asset_path);
+                let count = match texture_atlas.count {
+                    Some(count) => quote!(Some(#count)),
+                    None => quote!(None),
+                };
                 quote!(
                     #token_stream #field_ident : {
-                    atlases.add(TextureAtlas::from_grid_with_padding(
-                        asset_server.get_handle(#asset_path),
-                        Vec2::new(#tile_size_x, #tile_size_y),
-                        #columns,
-                        #rows,
-                        Vec2::new(#padding_x, #padding_y),
-                    ))},
+                    let images = cell
+                        .get_resource::<Assets<bevy::prelude::Image>>()
+                        .expect("Cannot get resource Assets<Image>");
+                    let mut atlases = cell
+                        .get_resource_mut::<Assets<TextureAtlas>>()
+                        .expect("Cannot get resource Assets<TextureAtlas>");
+                    let grid = bevy_asset_loader::TextureAtlasGrid {
+                        tile_size: Vec2::new(#tile_size_x, #tile_size_y),
+                        columns: #columns,
+                        rows: #rows,
+                        padding: Vec2::new(#padding_x, #padding_y),
+                        offset: Vec2::new(#offset_x, #offset_y),
+                        count: #count,
+                    };
+                    let atlas = atlases.add(grid.build(asset_server.get_handle(#asset_path), &images));
+                    bevy_asset_loader::DerivedAssets::track(
+                        &cell,
+                        bevy_asset_loader::DerivedAsset::TextureAtlasGrid {
+                            atlas: atlas.clone(),
+                            grid,
+                        },
+                    );
+                    bevy_asset_loader::NamedTextureAtlas {
+                        atlas,
+                        ..Default::default()
+                    }#with_animations.into()},
                 )
             }
+            AssetField::FolderTextureAtlas(basic) => {
+                let field_ident = basic.field_ident.clone();
+                let asset_path = path_tokens(&basic.asset_path);
+                quote!(#token_stream #field_ident : {
+                    let folder = #asset_path.to_owned();
+                    let mut images = cell
+                        .get_resource_mut::<Assets<bevy::prelude::Image>>()
+                        .expect("Cannot get resource Assets<Image>");
+                    let mut atlases = cell
+                        .get_resource_mut::<Assets<TextureAtlas>>()
+                        .expect("Cannot get resource Assets<TextureAtlas>");
+                    let named_atlas = bevy_asset_loader::NamedTextureAtlas::from_folder(
+                        &folder,
+                        &asset_server,
+                        &mut images,
+                        &mut atlases,
+                    )
+                    .unwrap_or_else(|error| {
+                        panic!("Failed to stitch a texture atlas from the folder '{}': {}", folder, error)
+                    });
+                    bevy_asset_loader::DerivedAssets::track(
+                        &cell,
+                        bevy_asset_loader::DerivedAsset::StitchedTextureAtlas {
+                            atlas: named_atlas.atlas.clone(),
+                        },
+                    );
+                    named_atlas.into()
+                },)
+            }
+            AssetField::PackedTextureAtlas(packed_atlas) => {
+                let field_ident = packed_atlas.field_ident.clone();
+                let asset_path = path_tokens(&packed_atlas.asset_path);
+                let layout_path = path_tokens(&packed_atlas.layout_path);
+                let with_animations = with_animations(&packed_atlas.animations, &asset_path);
+                quote!(#token_stream #field_ident : {
+                    let images = cell
+                        .get_resource::<Assets<bevy::prelude::Image>>()
+                        .expect("Cannot get resource Assets<Image>");
+                    let mut atlases = cell
+                        .get_resource_mut::<Assets<TextureAtlas>>()
+                        .expect("Cannot get resource Assets<TextureAtlas>");
+                    let layouts = cell
+                        .get_resource::<Assets<bevy_asset_loader::PackedTextureAtlasLayout>>()
+                        .expect("Cannot get resource Assets<PackedTextureAtlasLayout>. Did you add the PackedTextureAtlasPlugin?");
+                    let layout = layouts
+                        .get(#layout_path)
+                        .unwrap_or_else(|| panic!("Texture atlas layout '{}' is not loaded", #layout_path));
+                    bevy_asset_loader::NamedTextureAtlas::from_layout(
+                        asset_server.get_handle(#asset_path),
+                        layout,
+                        &images,
+                        &mut atlases,
+                    )
+                    .unwrap_or_else(|error| {
+                        panic!("Failed to create a texture atlas from the layout '{}': {}", #layout_path, error)
+                    })#with_animations.into()
+                },)
+            }
+            AssetField::Labeled(basic, label) => {
+                let field_ident = basic.field_ident.clone();
+                let asset_path = path_tokens(&format!("{}#{}", basic.asset_path, label));
+                quote!(#token_stream #field_ident : asset_server.get_handle(#asset_path),)
+            }
+            AssetField::GltfSubAsset(basic, sub_asset) => {
+                let field_ident = basic.field_ident.clone();
+                let asset_path = path_tokens(&basic.asset_path);
+                let (collection, kind, name) = match sub_asset {
+                    GltfSubAsset::Scene(name) => (quote!(named_scenes), "scene", Some(name)),
+                    GltfSubAsset::Mesh(name) => (quote!(named_meshes), "mesh", Some(name)),
+                    GltfSubAsset::Material(name) => {
+                        (quote!(named_materials), "material", Some(name))
+                    }
+                    GltfSubAsset::Node(name) => (quote!(named_nodes), "node", Some(name)),
+                    GltfSubAsset::NamedScenes => (quote!(named_scenes), "scene", None),
+                    GltfSubAsset::NamedMeshes => (quote!(named_meshes), "mesh", None),
+                    GltfSubAsset::NamedMaterials => (quote!(named_materials), "material", None),
+                    GltfSubAsset::NamedNodes => (quote!(named_nodes), "node", None),
+                };
+                let sub_asset = match name {
+                    Some(name) => quote!(gltf.#collection.get(#name)
+                        .unwrap_or_else(|| panic!("The glTF '{}' has no {} named '{}'", #asset_path, #kind, #name))
+                        .clone()),
+                    None => quote!(gltf.#collection.clone()),
+                };
+                quote!(#token_stream #field_ident : {
+                    let gltfs = cell
+                        .get_resource::<Assets<bevy::gltf::Gltf>>()
+                        .expect("Cannot get resource Assets<Gltf>");
+                    let gltf = gltfs
+                        .get(#asset_path)
+                        .unwrap_or_else(|| panic!("The glTF '{}' is not loaded", #asset_path));
+                    #sub_asset
+                },)
+            }
         }
     }
 
     pub(crate) fn attach_token_stream_for_loading(&self, token_stream: TokenStream) -> TokenStream {
         match self {
             AssetField::Basic(asset) => {
-                let asset_path = asset.asset_path.clone();
+                let asset_path = path_tokens(&asset.asset_path);
                 quote!(#token_stream handles.push(asset_server.load_untyped(#asset_path));)
             }
             AssetField::Folder(asset, _) => {
-                let asset_path = asset.asset_path.clone();
+                let asset_path = path_tokens(&asset.asset_path);
                 quote!(#token_stream asset_server.load_folder(#asset_path).unwrap().drain(..).for_each(|handle| handles.push(handle));)
             }
             AssetField::Dynamic(dynamic) => {
                 let asset_key = dynamic.key.clone();
                 quote!(
-                    #token_stream handles.push({
+                    #token_stream {
                         let dynamic_asset = asset_keys.get_asset(#asset_key.into()).unwrap_or_else(|| panic!("Failed to get asset for key '{}'", #asset_key));
-                        asset_server.load_untyped(dynamic_asset.get_file_path())
-                    });
+                        handles.append(&mut dynamic_asset.load(&cell));
+                    }
                 )
             }
             AssetField::OptionalDynamic(dynamic) => {
@@ -207,7 +350,7 @@ impl AssetField {
                     #token_stream {
                         let dynamic_asset = asset_keys.get_asset(#asset_key.into());
                         if let Some(dynamic_asset) = dynamic_asset {
-                            handles.push(asset_server.load_untyped(dynamic_asset.get_file_path()));
+                            handles.append(&mut dynamic_asset.load(&cell));
                         }
                     }
                 )
@@ -217,22 +360,189 @@ impl AssetField {
                 quote!(
                     #token_stream {
                         let dynamic_asset = asset_keys.get_asset(#asset_key.into()).unwrap_or_else(|| panic!("Failed to get asset for key '{}'", #asset_key));
-                        asset_server.load_folder(dynamic_asset.get_file_path()).unwrap().drain(..).for_each(|handle| handles.push(handle));
+                        let path = dynamic_asset.folder_path().unwrap_or_else(|| panic!("The asset '{}' cannot be loaded as a folder", #asset_key));
+                        let path = bevy_asset_loader::AssetVariables::resolve_path(&cell, path);
+                        asset_server.load_folder(path.as_str()).unwrap().drain(..).for_each(|handle| handles.push(handle));
                     }
                 )
             }
             AssetField::StandardMaterial(asset) => {
-                let asset_path = asset.asset_path.clone();
+                let asset_path = path_tokens(&asset.asset_path);
                 quote!(#token_stream handles.push(asset_server.load_untyped(#asset_path));)
             }
             AssetField::TextureAtlas(asset) => {
-                let asset_path = asset.asset_path.clone();
+                let asset_path = path_tokens(&asset.asset_path);
+                quote!(#token_stream handles.push(asset_server.load_untyped(#asset_path));)
+            }
+            AssetField::FolderTextureAtlas(asset) => {
+                let asset_path = path_tokens(&asset.asset_path);
+                quote!(#token_stream asset_server.load_folder(#asset_path).unwrap().drain(..).for_each(|handle| handles.push(handle));)
+            }
+            AssetField::PackedTextureAtlas(asset) => {
+                let asset_path = path_tokens(&asset.asset_path);
+                let layout_path = path_tokens(&asset.layout_path);
+                quote!(#token_stream
+                    handles.push(asset_server.load_untyped(#asset_path));
+                    handles.push(asset_server.load_untyped(#layout_path));
+                )
+            }
+            AssetField::GltfSubAsset(asset, _) | AssetField::Labeled(asset, _) => {
+                let asset_path = path_tokens(&asset.asset_path);
                 quote!(#token_stream handles.push(asset_server.load_untyped(#asset_path));)
             }
         }
     }
 }
 
+impl AssetField {
+    /// Push the dynamic asset equivalent to a field with a path
+    ///
+    /// Fields with keys are skipped. glTF sub assets are selected by name and not by label,
+    /// so they push their glTF file.
+    pub(crate) fn attach_token_stream_for_dynamic_assets(
+        &self,
+        token_stream: TokenStream,
+    ) -> TokenStream {
+        let (field_ident, dynamic_asset) = match self {
+            AssetField::Dynamic(_)
+            | AssetField::OptionalDynamic(_)
+            | AssetField::DynamicFolder(_, _) => return token_stream,
+            AssetField::Basic(basic)
+            | AssetField::Folder(basic, _)
+            | AssetField::FolderTextureAtlas(basic)
+            | AssetField::GltfSubAsset(basic, _) => {
+                let path = &basic.asset_path;
+                (
+                    &basic.field_ident,
+                    quote!(bevy_asset_loader::DynamicAsset::File { path: #path.to_owned() }),
+                )
+            }
+            AssetField::Labeled(basic, label) => {
+                let path = &basic.asset_path;
+                (
+                    &basic.field_ident,
+                    quote!(bevy_asset_loader::DynamicAsset::LabeledFile {
+                        path: #path.to_owned(),
+                        label: #label.to_owned(),
+                    }),
+                )
+            }
+            #[cfg(feature = "render")]
+            AssetField::StandardMaterial(basic) => {
+                let path = &basic.asset_path;
+                (
+                    &basic.field_ident,
+                    quote!(bevy_asset_loader::DynamicAsset::StandardMaterial { path: #path.to_owned() }),
+                )
+            }
+            #[cfg(feature = "render")]
+            AssetField::TextureAtlas(texture_atlas) => {
+                let TextureAtlasAssetField {
+                    field_ident,
+                    asset_path,
+                    tile_size_x,
+                    tile_size_y,
+                    columns,
+                    rows,
+                    padding_x,
+                    padding_y,
+                    offset_x,
+                    offset_y,
+                    count,
+                    animations,
+                } = texture_atlas;
+                let optional = |value: &f32| {
+                    if *value == 0. {
+                        quote!(None)
+                    } else {
+                        quote!(Some(#value))
+                    }
+                };
+                let (padding_x, padding_y) = (optional(padding_x), optional(padding_y));
+                let (offset_x, offset_y) = (optional(offset_x), optional(offset_y));
+                let count = match count {
+                    Some(count) => quote!(Some(#count)),
+                    None => quote!(None),
+                };
+                let animations = animation_clips(animations);
+                (
+                    field_ident,
+                    quote!(bevy_asset_loader::DynamicAsset::TextureAtlas {
+                        path: #asset_path.to_owned(),
+                        tile_size_x: #tile_size_x,
+                        tile_size_y: #tile_size_y,
+                        columns: #columns,
+                        rows: #rows,
+                        padding_x: #padding_x,
+                        padding_y: #padding_y,
+                        offset_x: #offset_x,
+                        offset_y: #offset_y,
+                        count: #count,
+                        animations: #animations,
+                    }),
+                )
+            }
+            #[cfg(feature = "render")]
+            AssetField::PackedTextureAtlas(packed_atlas) => {
+                let path = &packed_atlas.asset_path;
+                let layout = &packed_atlas.layout_path;
+                let animations = animation_clips(&packed_atlas.animations);
+                (
+                    &packed_atlas.field_ident,
+                    quote!(bevy_asset_loader::DynamicAsset::PackedTextureAtlas {
+                        path: #path.to_owned(),
+                        layout: #layout.to_owned(),
+                        animations: #animations,
+                    }),
+                )
+            }
+            // these fields are only parsed with the render feature
+            #[cfg(not(feature = "render"))]
+            AssetField::StandardMaterial(_)
+            | AssetField::TextureAtlas(_)
+            | AssetField::PackedTextureAtlas(_) => return token_stream,
+        };
+        let key = field_ident.to_string().trim_start_matches("r#").to_owned();
+        quote!(#token_stream dynamic_assets.push((#key.to_owned(), #dynamic_asset));)
+    }
+}
+
+fn animation_clips(animations: &[AnimationClipField]) -> TokenStream {
+    let clips = animations.iter().map(|clip| {
+        let AnimationClipField {
+            name,
+            start,
+            end,
+            fps,
+        } = clip;
+        quote!(animations.insert(#name, bevy_asset_loader::AnimationClip { start: #start, end: #end, fps: #fps });)
+    });
+    quote!({
+        let mut animations = bevy_asset_loader::AnimationClips::default();
+        #(#clips)*
+        animations
+    })
+}
+
+/// Paths with variables like `"levels/{level}/tiles.png"` are resolved at runtime
+fn path_tokens(path: &str) -> TokenStream {
+    if path.contains('{') {
+        quote!(bevy_asset_loader::AssetVariables::resolve_path(&cell, #path).as_str())
+    } else {
+        quote!(#path)
+    }
+}
+
+fn with_animations(animations: &[AnimationClipField], asset_path: &TokenStream) -> TokenStream {
+    if animations.is_empty() {
+        return quote!();
+    }
+    let animations = animation_clips(animations);
+    quote!(.with_animations(#animations, &atlases).unwrap_or_else(|error| {
+        panic!("Failed to add the animations of the texture atlas '{}': {}", #asset_path, error)
+    }))
+}
+
 #[derive(Default)]
 pub(crate) struct AssetBuilder {
     pub field_ident: Option<Ident>,
@@ -248,10 +558,38 @@ pub(crate) struct AssetBuilder {
     pub rows: Option<usize>,
     pub padding_x: Option<f32>,
     pub padding_y: Option<f32>,
+    pub offset_x: Option<f32>,
+    pub offset_y: Option<f32>,
+    pub count: Option<usize>,
+    pub animations: Vec<AnimationClipField>,
+    pub texture_atlas_folder: Option<String>,
+    pub texture_atlas_layout: Option<String>,
+    pub gltf_sub_assets: Vec<GltfSubAsset>,
+    pub label: Option<String>,
+    pub is_localized: bool,
+    pub is_variants: bool,
 }
 
 impl AssetBuilder {
-    pub(crate) fn build(self) -> Result<AssetField, Vec<ParseFieldError>> {
+    pub(crate) fn build(mut self) -> Result<AssetField, Vec<ParseFieldError>> {
+        if self.is_localized || self.is_variants {
+            if self.key.is_some() {
+                return Err(vec![ParseFieldError::KeyAttributeStandsAlone]);
+            }
+            if self.asset_path.is_none() && self.texture_atlas_folder.is_none() {
+                return Err(vec![ParseFieldError::MissingAttributes(vec![
+                    PATH_ATTRIBUTE.to_owned(),
+                ])]);
+            }
+        }
+        if self.is_localized {
+            self.insert_path_folder("{locale}");
+        }
+        if self.is_variants {
+            self.insert_path_folder("{quality}");
+        }
+        let has_optional_grid_attributes = self.has_optional_grid_attributes();
+        let has_animations = !self.animations.is_empty();
         let mut missing_fields = vec![];
         if self.tile_size_x.is_none() {
             missing_fields.push(format!(
@@ -281,14 +619,123 @@ impl AssetBuilder {
                 TextureAtlasAttribute::ROWS
             ));
         }
+        if let Some(label) = self.label {
+            if self.key.is_some() {
+                return Err(vec![ParseFieldError::KeyAttributeStandsAlone]);
+            }
+            if self.asset_path.is_none() {
+                return Err(vec![ParseFieldError::MissingAttributes(vec![
+                    PATH_ATTRIBUTE.to_owned(),
+                ])]);
+            }
+            if missing_fields.len() < 4
+                || has_optional_grid_attributes
+                || has_animations
+                || self.is_standard_material
+                || self.is_folder
+                || self.texture_atlas_folder.is_some()
+                || self.texture_atlas_layout.is_some()
+                || !self.gltf_sub_assets.is_empty()
+            {
+                return Err(vec![ParseFieldError::LabelStandsAlone]);
+            }
+            if self.is_optional {
+                return Err(vec![ParseFieldError::OnlyDynamicCanBeOptional]);
+            }
+            return Ok(AssetField::Labeled(
+                BasicAssetField {
+                    field_ident: self.field_ident.unwrap(),
+                    asset_path: self.asset_path.unwrap(),
+                },
+                label,
+            ));
+        }
+        if let Some(texture_atlas_folder) = self.texture_atlas_folder {
+            if self.key.is_some() {
+                return Err(vec![ParseFieldError::KeyAttributeStandsAlone]);
+            }
+            if self.asset_path.is_some()
+                || missing_fields.len() < 4
+                || has_optional_grid_attributes
+                || has_animations
+                || self.is_standard_material
+                || self.is_folder
+            {
+                return Err(vec![ParseFieldError::TextureAtlasFolderStandsAlone]);
+            }
+            if self.is_optional {
+                return Err(vec![ParseFieldError::OnlyDynamicCanBeOptional]);
+            }
+            return Ok(AssetField::FolderTextureAtlas(BasicAssetField {
+                field_ident: self.field_ident.unwrap(),
+                asset_path: texture_atlas_folder,
+            }));
+        }
+        if let Some(texture_atlas_layout) = self.texture_atlas_layout {
+            if self.key.is_some() {
+                return Err(vec![ParseFieldError::KeyAttributeStandsAlone]);
+            }
+            if self.asset_path.is_none() {
+                return Err(vec![ParseFieldError::MissingAttributes(vec![
+                    PATH_ATTRIBUTE.to_owned(),
+                ])]);
+            }
+            if missing_fields.len() < 4
+                || has_optional_grid_attributes
+                || self.is_standard_material
+                || self.is_folder
+            {
+                return Err(vec![ParseFieldError::TextureAtlasLayoutStandsAlone]);
+            }
+            if self.is_optional {
+                return Err(vec![ParseFieldError::OnlyDynamicCanBeOptional]);
+            }
+            return Ok(AssetField::PackedTextureAtlas(
+                PackedTextureAtlasAssetField {
+                    field_ident: self.field_ident.unwrap(),
+                    asset_path: self.asset_path.unwrap(),
+                    layout_path: texture_atlas_layout,
+                    animations: self.animations,
+                },
+            ));
+        }
+        if !self.gltf_sub_assets.is_empty() {
+            if self.key.is_some() {
+                return Err(vec![ParseFieldError::KeyAttributeStandsAlone]);
+            }
+            if self.asset_path.is_none() {
+                return Err(vec![ParseFieldError::MissingAttributes(vec![
+                    PATH_ATTRIBUTE.to_owned(),
+                ])]);
+            }
+            if self.gltf_sub_assets.len() > 1
+                || missing_fields.len() < 4
+                || has_optional_grid_attributes
+                || has_animations
+                || self.is_standard_material
+                || self.is_folder
+            {
+                return Err(vec![ParseFieldError::GltfStandsAlone]);
+            }
+            if self.is_optional {
+                return Err(vec![ParseFieldError::OnlyDynamicCanBeOptional]);
+            }
+            return Ok(AssetField::GltfSubAsset(
+                BasicAssetField {
+                    field_ident: self.field_ident.unwrap(),
+                    asset_path: self.asset_path.unwrap(),
+                },
+                self.gltf_sub_assets[0].clone(),
+            ));
+        }
         if self.asset_path.is_none() && self.key.is_none() {
             return Err(vec![ParseFieldError::NoAttributes]);
         }
         if self.key.is_some()
             && (self.asset_path.is_some()
                 || missing_fields.len() < 4
-                || self.padding_x.is_some()
-                || self.padding_y.is_some()
+                || has_optional_grid_attributes
+                || has_animations
                 || self.is_standard_material)
         {
             return Err(vec![ParseFieldError::KeyAttributeStandsAlone]);
@@ -297,28 +744,31 @@ impl AssetBuilder {
             return Err(vec![ParseFieldError::OnlyDynamicCanBeOptional]);
         }
         if missing_fields.len() == 4 {
-            if self.key.is_some() {
+            if let Some(key) = self.key {
                 return if self.is_optional {
                     // Todo support optional folder?
                     Ok(AssetField::OptionalDynamic(DynamicAssetField {
                         field_ident: self.field_ident.unwrap(),
-                        key: self.key.unwrap(),
+                        key,
                     }))
                 } else if self.is_folder {
                     Ok(AssetField::DynamicFolder(
                         DynamicAssetField {
                             field_ident: self.field_ident.unwrap(),
-                            key: self.key.unwrap(),
+                            key,
                         },
                         self.is_typed_folder.into(),
                     ))
                 } else {
                     Ok(AssetField::Dynamic(DynamicAssetField {
                         field_ident: self.field_ident.unwrap(),
-                        key: self.key.unwrap(),
+                        key,
                     }))
                 };
             }
+            if has_animations {
+                return Err(vec![ParseFieldError::MissingAttributes(missing_fields)]);
+            }
             if self.is_folder {
                 return Ok(AssetField::Folder(
                     BasicAssetField {
@@ -347,10 +797,32 @@ impl AssetBuilder {
                 rows: self.rows.unwrap(),
                 padding_x: self.padding_x.unwrap_or_default(),
                 padding_y: self.padding_y.unwrap_or_default(),
+                offset_x: self.offset_x.unwrap_or_default(),
+                offset_y: self.offset_y.unwrap_or_default(),
+                count: self.count,
+                animations: self.animations,
             }));
         }
         Err(vec![ParseFieldError::MissingAttributes(missing_fields)])
     }
+
+    /// Insert a folder in front of the last segment of the asset paths
+    fn insert_path_folder(&mut self, folder: &str) {
+        let insert = |path: String| match path.rfind('/') {
+            Some(index) => format!("{}/{}/{}", &path[..index], folder, &path[index + 1..]),
+            None => format!("{}/{}", folder, path),
+        };
+        self.asset_path = self.asset_path.take().map(insert);
+        self.texture_atlas_folder = self.texture_atlas_folder.take().map(insert);
+    }
+
+    fn has_optional_grid_attributes(&self) -> bool {
+        self.padding_x.is_some()
+            || self.padding_y.is_some()
+            || self.offset_x.is_some()
+            || self.offset_y.is_some()
+            || self.count.is_some()
+    }
 }
 
 #[cfg(test)]
@@ -376,6 +848,45 @@ mod test {
         );
     }
 
+    #[test]
+    fn localized_asset() {
+        let builder = AssetBuilder {
+            field_ident: Some(Ident::new("test", Span::call_site())),
+            asset_path: Some("vo/intro.ogg".to_owned()),
+            is_localized: true,
+            ..Default::default()
+        };
+
+        let asset = builder.build().expect("This should be a valid BasicAsset");
+        assert_eq!(
+            asset,
+            AssetField::Basic(BasicAssetField {
+                field_ident: Ident::new("test", Span::call_site()),
+                asset_path: "vo/{locale}/intro.ogg".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn localized_asset_variants() {
+        let builder = AssetBuilder {
+            field_ident: Some(Ident::new("test", Span::call_site())),
+            asset_path: Some("rock.png".to_owned()),
+            is_localized: true,
+            is_variants: true,
+            ..Default::default()
+        };
+
+        let asset = builder.build().expect("This should be a valid BasicAsset");
+        assert_eq!(
+            asset,
+            AssetField::Basic(BasicAssetField {
+                field_ident: Ident::new("test", Span::call_site()),
+                asset_path: "{locale}/{quality}/rock.png".to_owned()
+            })
+        );
+    }
+
     #[test]
     fn standard_material() {
         let builder = AssetBuilder {
@@ -483,9 +994,279 @@ mod test {
                 columns: 10,
                 rows: 5,
                 padding_x: 2.0,
-                padding_y: 0.0
+                padding_y: 0.0,
+                offset_x: 0.0,
+                offset_y: 0.0,
+                count: None,
+                animations: vec![]
+            })
+        );
+    }
+
+    #[test]
+    fn texture_atlas_with_offset_and_count() {
+        let builder = AssetBuilder {
+            field_ident: Some(Ident::new("test", Span::call_site())),
+            asset_path: Some("some/image.png".to_owned()),
+            tile_size_x: Some(100.),
+            tile_size_y: Some(50.),
+            columns: Some(10),
+            rows: Some(5),
+            offset_x: Some(4.),
+            offset_y: Some(8.),
+            count: Some(42),
+            ..Default::default()
+        };
+
+        let asset = builder
+            .build()
+            .expect("This should be a valid TextureAtlasAsset");
+        assert_eq!(
+            asset,
+            AssetField::TextureAtlas(TextureAtlasAssetField {
+                field_ident: Ident::new("test", Span::call_site()),
+                asset_path: "some/image.png".to_owned(),
+                tile_size_x: 100.0,
+                tile_size_y: 50.0,
+                columns: 10,
+                rows: 5,
+                padding_x: 0.0,
+                padding_y: 0.0,
+                offset_x: 4.0,
+                offset_y: 8.0,
+                count: Some(42),
+                animations: vec![]
+            })
+        );
+    }
+
+    #[test]
+    fn texture_atlas_with_animations() {
+        let walk = AnimationClipField {
+            name: "walk".to_owned(),
+            start: 0,
+            end: 8,
+            fps: 10.,
+        };
+        let builder = AssetBuilder {
+            field_ident: Some(Ident::new("test", Span::call_site())),
+            asset_path: Some("some/image.png".to_owned()),
+            tile_size_x: Some(100.),
+            tile_size_y: Some(50.),
+            columns: Some(8),
+            rows: Some(2),
+            animations: vec![walk.clone()],
+            ..Default::default()
+        };
+
+        let asset = builder
+            .build()
+            .expect("This should be a valid TextureAtlasAsset");
+        assert_eq!(
+            asset,
+            AssetField::TextureAtlas(TextureAtlasAssetField {
+                field_ident: Ident::new("test", Span::call_site()),
+                asset_path: "some/image.png".to_owned(),
+                tile_size_x: 100.0,
+                tile_size_y: 50.0,
+                columns: 8,
+                rows: 2,
+                padding_x: 0.0,
+                padding_y: 0.0,
+                offset_x: 0.0,
+                offset_y: 0.0,
+                count: None,
+                animations: vec![walk.clone()]
+            })
+        );
+
+        let builder = AssetBuilder {
+            field_ident: Some(Ident::new("test", Span::call_site())),
+            asset_path: Some("some/image.png".to_owned()),
+            texture_atlas_layout: Some("some/image.atlas.json".to_owned()),
+            animations: vec![walk.clone()],
+            ..Default::default()
+        };
+        assert!(builder.build().is_ok());
+
+        let builder = AssetBuilder {
+            field_ident: Some(Ident::new("test", Span::call_site())),
+            asset_path: Some("some/image.png".to_owned()),
+            animations: vec![walk.clone()],
+            ..Default::default()
+        };
+        assert!(builder.build().is_err());
+
+        let builder = AssetBuilder {
+            field_ident: Some(Ident::new("test", Span::call_site())),
+            texture_atlas_folder: Some("some/folder".to_owned()),
+            animations: vec![walk],
+            ..Default::default()
+        };
+        assert!(builder.build().is_err());
+    }
+
+    #[test]
+    fn folder_texture_atlas() {
+        let builder = AssetBuilder {
+            field_ident: Some(Ident::new("test", Span::call_site())),
+            texture_atlas_folder: Some("some/folder".to_owned()),
+            ..Default::default()
+        };
+
+        let asset = builder
+            .build()
+            .expect("This should be a valid FolderTextureAtlasAsset");
+        assert_eq!(
+            asset,
+            AssetField::FolderTextureAtlas(BasicAssetField {
+                field_ident: Ident::new("test", Span::call_site()),
+                asset_path: "some/folder".to_owned()
+            })
+        );
+
+        let builder = AssetBuilder {
+            field_ident: Some(Ident::new("test", Span::call_site())),
+            asset_path: Some("some/image.png".to_owned()),
+            texture_atlas_folder: Some("some/folder".to_owned()),
+            ..Default::default()
+        };
+        assert!(builder.build().is_err());
+
+        let builder = AssetBuilder {
+            field_ident: Some(Ident::new("test", Span::call_site())),
+            texture_atlas_folder: Some("some/folder".to_owned()),
+            columns: Some(5),
+            ..Default::default()
+        };
+        assert!(builder.build().is_err());
+    }
+
+    #[test]
+    fn packed_texture_atlas() {
+        let builder = AssetBuilder {
+            field_ident: Some(Ident::new("test", Span::call_site())),
+            asset_path: Some("some/image.png".to_owned()),
+            texture_atlas_layout: Some("some/image.atlas.json".to_owned()),
+            ..Default::default()
+        };
+
+        let asset = builder
+            .build()
+            .expect("This should be a valid PackedTextureAtlasAsset");
+        assert_eq!(
+            asset,
+            AssetField::PackedTextureAtlas(PackedTextureAtlasAssetField {
+                field_ident: Ident::new("test", Span::call_site()),
+                asset_path: "some/image.png".to_owned(),
+                layout_path: "some/image.atlas.json".to_owned(),
+                animations: vec![]
             })
         );
+
+        let builder = AssetBuilder {
+            field_ident: Some(Ident::new("test", Span::call_site())),
+            texture_atlas_layout: Some("some/image.atlas.json".to_owned()),
+            ..Default::default()
+        };
+        assert!(builder.build().is_err());
+
+        let builder = AssetBuilder {
+            field_ident: Some(Ident::new("test", Span::call_site())),
+            asset_path: Some("some/image.png".to_owned()),
+            texture_atlas_layout: Some("some/image.atlas.json".to_owned()),
+            rows: Some(2),
+            ..Default::default()
+        };
+        assert!(builder.build().is_err());
+    }
+
+    #[test]
+    fn labeled_asset() {
+        let builder = AssetBuilder {
+            field_ident: Some(Ident::new("test", Span::call_site())),
+            asset_path: Some("models/ship.gltf".to_owned()),
+            label: Some("Mesh0/Primitive0".to_owned()),
+            ..Default::default()
+        };
+
+        let asset = builder
+            .build()
+            .expect("This should be a valid LabeledAsset");
+        assert_eq!(
+            asset,
+            AssetField::Labeled(
+                BasicAssetField {
+                    field_ident: Ident::new("test", Span::call_site()),
+                    asset_path: "models/ship.gltf".to_owned(),
+                },
+                "Mesh0/Primitive0".to_owned()
+            )
+        );
+
+        let builder = AssetBuilder {
+            field_ident: Some(Ident::new("test", Span::call_site())),
+            label: Some("Mesh0/Primitive0".to_owned()),
+            ..Default::default()
+        };
+        assert!(builder.build().is_err());
+
+        let builder = AssetBuilder {
+            field_ident: Some(Ident::new("test", Span::call_site())),
+            asset_path: Some("models/ship.gltf".to_owned()),
+            label: Some("Mesh0/Primitive0".to_owned()),
+            is_folder: true,
+            ..Default::default()
+        };
+        assert!(builder.build().is_err());
+    }
+
+    #[test]
+    fn gltf_sub_asset() {
+        let builder = AssetBuilder {
+            field_ident: Some(Ident::new("test", Span::call_site())),
+            asset_path: Some("models/ship.gltf".to_owned()),
+            gltf_sub_assets: vec![GltfSubAsset::Scene("Hull".to_owned())],
+            ..Default::default()
+        };
+
+        let asset = builder
+            .build()
+            .expect("This should be a valid GltfSubAsset");
+        assert_eq!(
+            asset,
+            AssetField::GltfSubAsset(
+                BasicAssetField {
+                    field_ident: Ident::new("test", Span::call_site()),
+                    asset_path: "models/ship.gltf".to_owned(),
+                },
+                GltfSubAsset::Scene("Hull".to_owned())
+            )
+        );
+
+        let builder = AssetBuilder {
+            field_ident: Some(Ident::new("test", Span::call_site())),
+            gltf_sub_assets: vec![GltfSubAsset::NamedMaterials],
+            ..Default::default()
+        };
+        assert!(builder.build().is_err());
+
+        let builder = AssetBuilder {
+            field_ident: Some(Ident::new("test", Span::call_site())),
+            asset_path: Some("models/ship.gltf".to_owned()),
+            gltf_sub_assets: vec![GltfSubAsset::NamedMaterials, GltfSubAsset::NamedMeshes],
+            ..Default::default()
+        };
+        assert!(builder.build().is_err());
+
+        let builder = AssetBuilder {
+            field_ident: Some(Ident::new("test", Span::call_site())),
+            asset_path: Some("models/ship.gltf".to_owned()),
+            gltf_sub_assets: vec![GltfSubAsset::Mesh("Hull".to_owned())],
+            is_standard_material: true,
+            ..Default::default()
+        };
+        assert!(builder.build().is_err());
     }
 
     #[test]
@@ -0,0 +1,546 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use serde::de::{EnumAccess, MapAccess, VariantAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+use syn::parse::{Parse, ParseStream};
+use syn::{Attribute, LitStr, Token, Visibility};
+
+/// Key of the list of included files in asset collection files
+const INCLUDES: &str = "#includes";
+/// Key of the localized assets in asset collection files
+const LOCALIZED: &str = "#localized";
+/// Bevy's default asset folder, include paths are relative to it
+///
+/// The macro cannot know the asset folder configured at runtime, so includes of apps with a
+/// different asset folder cannot be resolved.
+const ASSET_FOLDER: &str = "assets";
+
+/// Input of `asset_collection_from_file!`
+///
+/// `"path/to/file.assets"` optionally followed by `, <attributes> <visibility> struct Name`
+pub(crate) struct AssetCollectionFromFile {
+    path: LitStr,
+    attributes: Vec<Attribute>,
+    visibility: Visibility,
+    name: Option<Ident>,
+}
+
+impl Parse for AssetCollectionFromFile {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = input.parse()?;
+        let mut collection = AssetCollectionFromFile {
+            path,
+            attributes: vec![],
+            visibility: Visibility::Inherited,
+            name: None,
+        };
+        if input.is_empty() {
+            return Ok(collection);
+        }
+        input.parse::<Token![,]>()?;
+        if input.is_empty() {
+            return Ok(collection);
+        }
+        collection.attributes = input.call(Attribute::parse_outer)?;
+        collection.visibility = input.parse()?;
+        input.parse::<Token![struct]>()?;
+        collection.name = Some(input.parse()?);
+        if input.peek(Token![;]) {
+            input.parse::<Token![;]>()?;
+        }
+
+        Ok(collection)
+    }
+}
+
+pub(crate) fn impl_asset_collection_from_file(
+    input: AssetCollectionFromFile,
+) -> Result<TokenStream, Vec<syn::Error>> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let manifest_dir = Path::new(&manifest_dir);
+    let path = manifest_dir.join(input.path.value());
+    let mut files = vec![];
+    let assets = read_assets(
+        &path,
+        &manifest_dir.join(ASSET_FOLDER),
+        &mut files,
+        &mut vec![],
+    )
+    .map_err(|error| vec![syn::Error::new(input.path.span(), error)])?;
+
+    let mut errors = vec![];
+    let mut field_names: HashMap<String, String> = HashMap::new();
+    let mut fields = vec![];
+    let keys = assets.keys();
+    let visibility = &input.visibility;
+    for (key, asset) in assets.iter() {
+        let field_name = field_name(key);
+        if let Some(other_key) = field_names.insert(field_name.to_string(), key.clone()) {
+            errors.push(syn::Error::new(
+                input.path.span(),
+                format!(
+                    "The keys '{}' and '{}' result in the same field name '{}'",
+                    other_key, key, field_name
+                ),
+            ));
+            continue;
+        }
+        match asset.field_type(key) {
+            Ok(FieldType { ty, is_folder }) => {
+                let attribute = if is_folder {
+                    quote!(#[asset(key = #key, folder)])
+                } else {
+                    quote!(#[asset(key = #key)])
+                };
+                fields.push(quote!(
+                    #attribute
+                    #visibility #field_name: #ty,
+                ));
+            }
+            Err(error) => errors.push(syn::Error::new(input.path.span(), error)),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let name = input.name.unwrap_or_else(|| default_name(&path));
+    let attributes = &input.attributes;
+    // Including the files makes cargo recompile the collection when one of them changes
+    let files = files.iter().map(|file| file.to_string_lossy().into_owned());
+    Ok(quote! {
+        #(#attributes)*
+        #[derive(bevy_asset_loader::AssetCollection)]
+        #visibility struct #name {
+            #(#fields)*
+        }
+
+        impl #name {
+            /// Keys of the asset collection file this collection was generated from
+            #visibility const KEYS: &'static [&'static str] = &[#(#keys),*];
+        }
+
+        #(const _: &str = include_str!(#files);)*
+    })
+}
+
+/// Read all assets of an asset collection file and its includes
+///
+/// Only RON files are supported, independent of the enabled dynamic asset formats.
+///
+/// Assets are overridden in the same order as at runtime: includes in the order they are listed,
+/// then the assets of the file itself and finally its localized assets.
+fn read_assets(
+    path: &Path,
+    asset_folder: &Path,
+    files: &mut Vec<PathBuf>,
+    include_stack: &mut Vec<PathBuf>,
+) -> Result<BTreeMap<String, FileAsset>, String> {
+    if include_stack.iter().any(|included| included == path) {
+        include_stack.push(path.to_owned());
+        let cycle: Vec<String> = include_stack
+            .iter()
+            .map(|file| file.display().to_string())
+            .collect();
+        return Err(format!(
+            "Asset collection files include each other in a cycle: {}",
+            cycle.join(" -> ")
+        ));
+    }
+    let content = fs::read_to_string(path).map_err(|error| {
+        format!(
+            "Failed to read asset collection file '{}': {}",
+            path.display(),
+            error
+        )
+    })?;
+    let file: AssetCollectionFile = ron::de::from_str(&content).map_err(|error| {
+        format!(
+            "Failed to parse asset collection file '{}': {}",
+            path.display(),
+            error
+        )
+    })?;
+    files.push(path.to_owned());
+
+    let mut assets = BTreeMap::new();
+    include_stack.push(path.to_owned());
+    for include in file.includes.iter() {
+        assets.extend(read_assets(
+            &asset_folder.join(include),
+            asset_folder,
+            files,
+            include_stack,
+        )?);
+    }
+    include_stack.pop();
+    assets.extend(file.assets);
+    for localized in file.localized.into_values() {
+        for (key, asset) in localized {
+            assets.entry(key).or_insert(asset);
+        }
+    }
+
+    Ok(assets)
+}
+
+/// The asset collection struct is named after the file (`level.assets` -> `LevelAssets`)
+fn default_name(path: &Path) -> Ident {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let stem = file_name.split('.').next().unwrap_or_default();
+    let mut name: String = stem
+        .split(|character: char| !character.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut characters = part.chars();
+            let first = characters.next().unwrap().to_ascii_uppercase();
+            std::iter::once(first).chain(characters).collect::<String>()
+        })
+        .collect();
+    if name.starts_with(|character: char| character.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+    name.push_str("Assets");
+
+    Ident::new(&name, Span::call_site())
+}
+
+/// Turn an asset key into a field name (`image.player` -> `image_player`)
+fn field_name(key: &str) -> Ident {
+    let mut name: String = key
+        .chars()
+        .map(|character| {
+            if character.is_ascii_alphanumeric() {
+                character.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.is_empty() || name.starts_with(|character: char| character.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+    // these keywords cannot be raw identifiers
+    if ["crate", "self", "super"].contains(&name.as_str()) {
+        name.push('_');
+    }
+    if syn::parse_str::<Ident>(&name).is_ok() {
+        Ident::new(&name, Span::call_site())
+    } else {
+        Ident::new_raw(&name, Span::call_site())
+    }
+}
+
+struct FieldType {
+    ty: TokenStream,
+    is_folder: bool,
+}
+
+impl FieldType {
+    fn handle(ty: TokenStream) -> Self {
+        FieldType {
+            ty: quote!(bevy::prelude::Handle<#ty>),
+            is_folder: false,
+        }
+    }
+}
+
+/// A dynamic asset read from an asset collection file
+struct FileAsset {
+    tag: String,
+    fields: HashMap<String, ron::Value>,
+}
+
+impl FileAsset {
+    fn string_field(&self, field: &str, key: &str) -> Result<&str, String> {
+        match self.fields.get(field) {
+            Some(ron::Value::String(value)) => Ok(value),
+            _ => Err(format!(
+                "The dynamic asset '{}' needs the field '{}'",
+                key, field
+            )),
+        }
+    }
+
+    fn has_animations(&self) -> bool {
+        matches!(self.fields.get("animations"), Some(ron::Value::Map(animations)) if !animations.is_empty())
+    }
+
+    fn field_type(&self, key: &str) -> Result<FieldType, String> {
+        match self.tag.as_str() {
+            "File" | "Folder" => file_type(self.string_field("path", key)?, key),
+            "LabeledFile" => label_type(self.string_field("label", key)?, key),
+            "StandardMaterial" => Ok(FieldType::handle(quote!(bevy::prelude::StandardMaterial))),
+            "TextureAtlas" if !self.has_animations() => {
+                Ok(FieldType::handle(quote!(bevy::prelude::TextureAtlas)))
+            }
+            "TextureAtlas" | "PackedTextureAtlas" => Ok(FieldType {
+                ty: quote!(bevy_asset_loader::NamedTextureAtlas),
+                is_folder: false,
+            }),
+            tag => Err(format!(
+                "Cannot generate a field for the key '{}' with the dynamic asset type '{}'",
+                key, tag
+            )),
+        }
+    }
+}
+
+/// Infer the asset type from the file ending of the path
+///
+/// Paths without a file ending are loaded as folders.
+fn file_type(path: &str, key: &str) -> Result<FieldType, String> {
+    let file_name = path.rsplit('/').next().unwrap_or_default();
+    let extension = match file_name.rsplit_once('.') {
+        Some((_, extension)) => extension.to_ascii_lowercase(),
+        None => {
+            return Ok(FieldType {
+                ty: quote!(Vec<bevy::prelude::HandleUntyped>),
+                is_folder: true,
+            })
+        }
+    };
+    match extension.as_str() {
+        "png" | "jpg" | "jpeg" | "bmp" | "tga" | "dds" | "hdr" | "ktx2" | "basis" => {
+            Ok(FieldType::handle(quote!(bevy::prelude::Image)))
+        }
+        "ogg" | "oga" | "wav" | "mp3" | "flac" => {
+            Ok(FieldType::handle(quote!(bevy::prelude::AudioSource)))
+        }
+        "ttf" | "otf" => Ok(FieldType::handle(quote!(bevy::prelude::Font))),
+        "gltf" | "glb" => Ok(FieldType::handle(quote!(bevy::gltf::Gltf))),
+        _ => Err(format!(
+            "Cannot infer the asset type of the key '{}' from the path '{}'",
+            key, path
+        )),
+    }
+}
+
+/// Infer the asset type from a glTF label
+fn label_type(label: &str, key: &str) -> Result<FieldType, String> {
+    let kind = label.trim_end_matches(|character: char| character.is_ascii_digit());
+    if label.contains("/Primitive") {
+        return Ok(FieldType::handle(quote!(bevy::prelude::Mesh)));
+    }
+    match kind {
+        "Scene" => Ok(FieldType::handle(quote!(bevy::prelude::Scene))),
+        "Mesh" => Ok(FieldType::handle(quote!(bevy::gltf::GltfMesh))),
+        "Material" | "DefaultMaterial" => {
+            Ok(FieldType::handle(quote!(bevy::prelude::StandardMaterial)))
+        }
+        "Texture" => Ok(FieldType::handle(quote!(bevy::prelude::Image))),
+        "Node" => Ok(FieldType::handle(quote!(bevy::gltf::GltfNode))),
+        _ => Err(format!(
+            "Cannot infer the asset type of the key '{}' from the label '{}'",
+            key, label
+        )),
+    }
+}
+
+/// The parts of an asset collection file that are relevant for the generated struct
+struct AssetCollectionFile {
+    includes: Vec<String>,
+    assets: BTreeMap<String, FileAsset>,
+    localized: BTreeMap<String, BTreeMap<String, FileAsset>>,
+}
+
+impl<'de> Deserialize<'de> for AssetCollectionFile {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer
+            .deserialize_newtype_struct("DynamicAssetCollection", AssetCollectionFileVisitor)
+    }
+}
+
+struct AssetCollectionFileVisitor;
+
+impl<'de> Visitor<'de> for AssetCollectionFileVisitor {
+    type Value = AssetCollectionFile;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map of asset keys to dynamic assets")
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut file = AssetCollectionFile {
+            includes: vec![],
+            assets: BTreeMap::new(),
+            localized: BTreeMap::new(),
+        };
+        while let Some(key) = map.next_key::<String>()? {
+            if key == INCLUDES {
+                file.includes = map.next_value()?;
+            } else if key == LOCALIZED {
+                file.localized = map.next_value()?;
+            } else {
+                let asset = map.next_value()?;
+                file.assets.insert(key, asset);
+            }
+        }
+
+        Ok(file)
+    }
+}
+
+impl<'de> Deserialize<'de> for FileAsset {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_enum("DynamicAsset", &[], FileAssetVisitor)
+    }
+}
+
+struct FileAssetVisitor;
+
+impl<'de> Visitor<'de> for FileAssetVisitor {
+    type Value = FileAsset;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a tagged dynamic asset")
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        let (Identifier(tag), variant) = data.variant()?;
+        let fields = variant.struct_variant(&[], FieldsVisitor)?;
+
+        Ok(FileAsset { tag, fields })
+    }
+}
+
+/// Tag or field name of a dynamic asset
+struct Identifier(String);
+
+impl<'de> Deserialize<'de> for Identifier {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_identifier(IdentifierVisitor)
+    }
+}
+
+struct IdentifierVisitor;
+
+impl<'de> Visitor<'de> for IdentifierVisitor {
+    type Value = Identifier;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an identifier")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        Ok(Identifier(value.to_owned()))
+    }
+}
+
+struct FieldsVisitor;
+
+impl<'de> Visitor<'de> for FieldsVisitor {
+    type Value = HashMap<String, ron::Value>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("the fields of a dynamic asset")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut fields = HashMap::new();
+        while let Some((Identifier(field), value)) = map.next_entry()? {
+            fields.insert(field, value);
+        }
+
+        Ok(fields)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn field_names_from_keys() {
+        assert_eq!(field_name("image.player").to_string(), "image_player");
+        assert_eq!(
+            field_name("Sounds-Background").to_string(),
+            "sounds_background"
+        );
+        assert_eq!(field_name("2d.tree").to_string(), "_2d_tree");
+        assert_eq!(field_name("type").to_string(), "r#type");
+        assert_eq!(field_name("crate").to_string(), "crate_");
+    }
+
+    #[test]
+    fn default_names_from_files() {
+        assert_eq!(
+            default_name(Path::new("assets/level.assets")).to_string(),
+            "LevelAssets"
+        );
+        assert_eq!(
+            default_name(Path::new("assets/dynamic_asset.assets.ron")).to_string(),
+            "DynamicAssetAssets"
+        );
+    }
+
+    #[test]
+    fn field_types_from_assets() {
+        let file: AssetCollectionFile = ron::de::from_str(
+            r#"({
+                "music": File ( path: "audio/background.ogg" ),
+                "images": Folder ( path: "images" ),
+                "crate": LabeledFile ( path: "models/crate.gltf", label: "Mesh0/Primitive0" ),
+                "player": TextureAtlas (
+                    path: "images/sheet.png",
+                    tile_size_x: 96.,
+                    tile_size_y: 99.,
+                    columns: 8,
+                    rows: 1,
+                    animations: { "walk": ( start: 0, end: 8, fps: 10. ) },
+                ),
+            })"#,
+        )
+        .unwrap();
+        let field_type = |key: &str| {
+            let field_type = file.assets[key].field_type(key).unwrap();
+            (field_type.ty.to_string(), field_type.is_folder)
+        };
+
+        assert_eq!(
+            field_type("music"),
+            (
+                quote!(bevy::prelude::Handle<bevy::prelude::AudioSource>).to_string(),
+                false
+            )
+        );
+        assert_eq!(
+            field_type("images"),
+            (quote!(Vec<bevy::prelude::HandleUntyped>).to_string(), true)
+        );
+        assert_eq!(
+            field_type("crate"),
+            (
+                quote!(bevy::prelude::Handle<bevy::prelude::Mesh>).to_string(),
+                false
+            )
+        );
+        assert_eq!(
+            field_type("player"),
+            (
+                quote!(bevy_asset_loader::NamedTextureAtlas).to_string(),
+                false
+            )
+        );
+    }
+
+    #[test]
+    fn unknown_file_endings_are_errors() {
+        assert!(file_type("levels/forest.level", "level").is_err());
+    }
+}
@@ -9,6 +9,8 @@
 extern crate proc_macro;
 
 mod assets;
+#[cfg(feature = "dynamic_assets")]
+mod from_file;
 
 use proc_macro::TokenStream;
 use std::option::Option::Some;
@@ -31,10 +33,32 @@ pub fn asset_collection_derive(input: TokenStream) -> TokenStream {
         .into()
 }
 
+/// Generate an [`AssetCollection`](bevy_asset_loader::AssetCollection) from an asset collection file
+///
+/// The macro reads the RON asset collection file at compile time and generates a struct with a
+/// field for every key of the file, its includes and its localized assets. The path of the file is
+/// relative to the crate root, while include paths are relative to the `assets` folder in the crate
+/// root. Other file formats and custom asset folders are not supported. The keys of the file are
+/// available as `KEYS` constant on the struct.
+///
+/// The struct is named after the file (`level.assets` -> `LevelAssets`) or can be declared after the path:
+/// `asset_collection_from_file!("assets/level.assets", pub struct Level)`.
+#[cfg(feature = "dynamic_assets")]
+#[proc_macro]
+pub fn asset_collection_from_file(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as from_file::AssetCollectionFromFile);
+    from_file::impl_asset_collection_from_file(input)
+        .unwrap_or_else(to_compile_errors)
+        .into()
+}
+
 pub(crate) const ASSET_ATTRIBUTE: &str = "asset";
 pub(crate) const PATH_ATTRIBUTE: &str = "path";
 pub(crate) const KEY_ATTRIBUTE: &str = "key";
+pub(crate) const LABEL_ATTRIBUTE: &str = "label";
 pub(crate) const OPTIONAL_ATTRIBUTE: &str = "optional";
+pub(crate) const LOCALIZED_ATTRIBUTE: &str = "localized";
+pub(crate) const VARIANTS_ATTRIBUTE: &str = "variants";
 
 pub(crate) const TEXTURE_ATLAS_ATTRIBUTE: &str = "texture_atlas";
 pub(crate) struct TextureAtlasAttribute;
@@ -47,6 +71,42 @@ impl TextureAtlasAttribute {
     pub const PADDING_X: &'static str = "padding_x";
     #[allow(dead_code)]
     pub const PADDING_Y: &'static str = "padding_y";
+    #[allow(dead_code)]
+    pub const OFFSET_X: &'static str = "offset_x";
+    #[allow(dead_code)]
+    pub const OFFSET_Y: &'static str = "offset_y";
+    #[allow(dead_code)]
+    pub const COUNT: &'static str = "count";
+    #[allow(dead_code)]
+    pub const FOLDER: &'static str = "folder";
+    #[allow(dead_code)]
+    pub const LAYOUT: &'static str = "layout";
+    #[allow(dead_code)]
+    pub const ANIMATIONS: &'static str = "animations";
+}
+
+#[cfg(feature = "render")]
+pub(crate) struct AnimationClipAttribute;
+#[cfg(feature = "render")]
+impl AnimationClipAttribute {
+    pub const START: &'static str = "start";
+    pub const END: &'static str = "end";
+    pub const FPS: &'static str = "fps";
+}
+
+pub(crate) const GLTF_ATTRIBUTE: &str = "gltf";
+#[cfg(feature = "gltf")]
+pub(crate) struct GltfAttribute;
+#[cfg(feature = "gltf")]
+impl GltfAttribute {
+    pub const SCENE: &'static str = "scene";
+    pub const MESH: &'static str = "mesh";
+    pub const MATERIAL: &'static str = "material";
+    pub const NODE: &'static str = "node";
+    pub const NAMED_SCENES: &'static str = "named_scenes";
+    pub const NAMED_MESHES: &'static str = "named_meshes";
+    pub const NAMED_MATERIALS: &'static str = "named_materials";
+    pub const NAMED_NODES: &'static str = "named_nodes";
 }
 
 pub(crate) const FOLDER_ATTRIBUTE: &str = "folder";
@@ -78,6 +138,30 @@ fn impl_asset_collection(
                                         "The 'key' attribute cannot be combined with any other asset defining attributes",
                                     ));
                                 }
+                                ParseFieldError::TextureAtlasFolderStandsAlone => {
+                                    compile_errors.push(syn::Error::new_spanned(
+                                        field.into_token_stream(),
+                                        "The 'texture_atlas/folder' attribute cannot be combined with 'path' or any other asset defining attributes",
+                                    ));
+                                }
+                                ParseFieldError::TextureAtlasLayoutStandsAlone => {
+                                    compile_errors.push(syn::Error::new_spanned(
+                                        field.into_token_stream(),
+                                        "The 'texture_atlas/layout' attribute can only be combined with the 'path' attribute",
+                                    ));
+                                }
+                                ParseFieldError::LabelStandsAlone => {
+                                    compile_errors.push(syn::Error::new_spanned(
+                                        field.into_token_stream(),
+                                        "The 'label' attribute can only be combined with the 'path' attribute",
+                                    ));
+                                }
+                                ParseFieldError::GltfStandsAlone => {
+                                    compile_errors.push(syn::Error::new_spanned(
+                                        field.into_token_stream(),
+                                        "The 'gltf' attribute needs exactly one sub asset and can only be combined with the 'path' attribute",
+                                    ));
+                                }
                                 ParseFieldError::OnlyDynamicCanBeOptional => {
                                     compile_errors.push(syn::Error::new_spanned(
                                         field.into_token_stream(),
@@ -111,10 +195,31 @@ fn impl_asset_collection(
                                         "Unknown attribute",
                                     ));
                                 }
-                                ParseFieldError::MissingRenderFeature(token_stream) => {
+                                ParseFieldError::EmptyAnimationClip(token_stream) => {
                                     compile_errors.push(syn::Error::new_spanned(
                                         token_stream,
-                                        "This attribute requires the 'render' feature",
+                                        "An animation clip needs to end after its start",
+                                    ));
+                                }
+                                ParseFieldError::InvalidFramesPerSecond(token_stream) => {
+                                    compile_errors.push(syn::Error::new_spanned(
+                                        token_stream,
+                                        "The frame rate of an animation clip needs to be larger than zero",
+                                    ));
+                                }
+                                ParseFieldError::AnimationsNeedNamedTextureAtlas(token_stream) => {
+                                    compile_errors.push(syn::Error::new_spanned(
+                                        token_stream,
+                                        "Animation clips can only be loaded into fields of type 'NamedTextureAtlas'",
+                                    ));
+                                }
+                                ParseFieldError::MissingFeature(token_stream, feature) => {
+                                    compile_errors.push(syn::Error::new_spanned(
+                                        token_stream,
+                                        format!(
+                                            "This attribute requires the '{}' feature",
+                                            feature
+                                        ),
                                     ));
                                 }
                             }
@@ -133,7 +238,7 @@ fn impl_asset_collection(
         }
     } else {
         return Err(vec![syn::Error::new_spanned(
-            &ast.into_token_stream(),
+            ast.into_token_stream(),
             "AssetCollection can only be derived for a struct",
         )]);
     }
@@ -152,21 +257,6 @@ fn impl_asset_collection(
             }
     };
 
-    #[allow(unused_mut, unused_assignments)]
-    let mut conditional_asset_collections = quote! {};
-    #[cfg(feature = "render")]
-    {
-        // standard materials and texture atlas resources
-        conditional_asset_collections = quote! {
-                let mut materials = cell
-                    .get_resource_mut::<Assets<StandardMaterial>>()
-                    .expect("Cannot get resource Assets<StandardMaterial>");
-                let mut atlases = cell
-                    .get_resource_mut::<Assets<TextureAtlas>>()
-                    .expect("Cannot get resource Assets<TextureAtlas>");
-        };
-    }
-
     let mut asset_creation = assets.iter().fold(quote!(), |token_stream, asset| {
         asset.attach_token_stream_for_creation(token_stream)
     });
@@ -179,13 +269,23 @@ fn impl_asset_collection(
                 let cell = world.cell();
                 let asset_server = cell.get_resource::<AssetServer>().expect("Cannot get AssetServer");
                 let asset_keys = cell.get_resource::<bevy_asset_loader::AssetKeys>().expect("Cannot get bevy_asset_loader::AssetKeys");
-                #conditional_asset_collections
                 #name {
                     #asset_creation
                 }
             }
     };
 
+    let dynamic_assets = assets.iter().fold(quote!(), |token_stream, asset| {
+        asset.attach_token_stream_for_dynamic_assets(token_stream)
+    });
+    let dynamic_assets_function = quote! {
+            fn dynamic_assets() -> Vec<(String, bevy_asset_loader::DynamicAsset)> {
+                let mut dynamic_assets = vec![];
+                #dynamic_assets
+                dynamic_assets
+            }
+    };
+
     let impl_asset_collection = quote! {
         #[automatically_derived]
         #[allow(unused_variables)]
@@ -193,6 +293,8 @@ fn impl_asset_collection(
             #create_function
 
             #load_function
+
+            #dynamic_assets_function
         }
     };
     Ok(impl_asset_collection)
@@ -202,13 +304,23 @@ fn impl_asset_collection(
 enum ParseFieldError {
     NoAttributes,
     KeyAttributeStandsAlone,
+    TextureAtlasFolderStandsAlone,
+    TextureAtlasLayoutStandsAlone,
+    GltfStandsAlone,
+    LabelStandsAlone,
     OnlyDynamicCanBeOptional,
     WrongAttributeType(proc_macro2::TokenStream, &'static str),
     UnknownAttributeType(proc_macro2::TokenStream),
     UnknownAttribute(proc_macro2::TokenStream),
     MissingAttributes(Vec<String>),
     #[allow(dead_code)]
-    MissingRenderFeature(proc_macro2::TokenStream),
+    EmptyAnimationClip(proc_macro2::TokenStream),
+    #[allow(dead_code)]
+    InvalidFramesPerSecond(proc_macro2::TokenStream),
+    #[allow(dead_code)]
+    AnimationsNeedNamedTextureAtlas(proc_macro2::TokenStream),
+    #[allow(dead_code)]
+    MissingFeature(proc_macro2::TokenStream, &'static str),
 }
 
 fn parse_field(field: &Field) -> Result<AssetField, Vec<ParseFieldError>> {
@@ -234,6 +346,15 @@ fn parse_field(field: &Field) -> Result<AssetField, Vec<ParseFieldError>> {
                                 "str",
                             ));
                         }
+                    } else if path == LABEL_ATTRIBUTE {
+                        if let Lit::Str(label_literal) = &named_value.lit {
+                            builder.label = Some(label_literal.value());
+                        } else {
+                            errors.push(ParseFieldError::WrongAttributeType(
+                                named_value.into_token_stream(),
+                                "str",
+                            ));
+                        }
                     } else if path == KEY_ATTRIBUTE {
                         if let Lit::Str(path_literal) = &named_value.lit {
                             builder.key = Some(path_literal.value());
@@ -252,8 +373,9 @@ fn parse_field(field: &Field) -> Result<AssetField, Vec<ParseFieldError>> {
                     let path = meta_path.get_ident().unwrap().clone();
                     if path == STANDARD_MATERIAL_ATTRIBUTE {
                         #[cfg(not(feature = "render"))]
-                        errors.push(ParseFieldError::MissingRenderFeature(
+                        errors.push(ParseFieldError::MissingFeature(
                             meta_path.into_token_stream(),
+                            "render",
                         ));
                         #[cfg(feature = "render")]
                         {
@@ -261,6 +383,10 @@ fn parse_field(field: &Field) -> Result<AssetField, Vec<ParseFieldError>> {
                         }
                     } else if path == OPTIONAL_ATTRIBUTE {
                         builder.is_optional = true;
+                    } else if path == LOCALIZED_ATTRIBUTE {
+                        builder.is_localized = true;
+                    } else if path == VARIANTS_ATTRIBUTE {
+                        builder.is_variants = true;
                     } else if path == FOLDER_ATTRIBUTE {
                         builder.is_folder = true;
                     } else {
@@ -272,8 +398,9 @@ fn parse_field(field: &Field) -> Result<AssetField, Vec<ParseFieldError>> {
                     let path = meta_list.path.get_ident().unwrap().clone();
                     if path == TEXTURE_ATLAS_ATTRIBUTE {
                         #[cfg(not(feature = "render"))]
-                        errors.push(ParseFieldError::MissingRenderFeature(
+                        errors.push(ParseFieldError::MissingFeature(
                             meta_list.into_token_stream(),
+                            "render",
                         ));
                         #[cfg(feature = "render")]
                         for attribute in meta_list.nested.iter() {
@@ -338,17 +465,93 @@ fn parse_field(field: &Field) -> Result<AssetField, Vec<ParseFieldError>> {
                                             "float",
                                         ));
                                     }
+                                } else if path == TextureAtlasAttribute::OFFSET_X {
+                                    if let Lit::Float(offset_x) = &named_value.lit {
+                                        builder.offset_x =
+                                            Some(offset_x.base10_parse::<f32>().unwrap());
+                                    } else {
+                                        errors.push(ParseFieldError::WrongAttributeType(
+                                            named_value.into_token_stream(),
+                                            "float",
+                                        ));
+                                    }
+                                } else if path == TextureAtlasAttribute::OFFSET_Y {
+                                    if let Lit::Float(offset_y) = &named_value.lit {
+                                        builder.offset_y =
+                                            Some(offset_y.base10_parse::<f32>().unwrap());
+                                    } else {
+                                        errors.push(ParseFieldError::WrongAttributeType(
+                                            named_value.into_token_stream(),
+                                            "float",
+                                        ));
+                                    }
+                                } else if path == TextureAtlasAttribute::COUNT {
+                                    if let Lit::Int(count) = &named_value.lit {
+                                        builder.count =
+                                            Some(count.base10_parse::<usize>().unwrap());
+                                    } else {
+                                        errors.push(ParseFieldError::WrongAttributeType(
+                                            named_value.into_token_stream(),
+                                            "integer",
+                                        ));
+                                    }
+                                } else if path == TextureAtlasAttribute::LAYOUT {
+                                    if let Lit::Str(layout) = &named_value.lit {
+                                        builder.texture_atlas_layout = Some(layout.value());
+                                    } else {
+                                        errors.push(ParseFieldError::WrongAttributeType(
+                                            named_value.into_token_stream(),
+                                            "str",
+                                        ));
+                                    }
+                                } else if path == TextureAtlasAttribute::FOLDER {
+                                    if let Lit::Str(folder) = &named_value.lit {
+                                        builder.field_ident = Some(field.clone().ident.unwrap());
+                                        builder.texture_atlas_folder = Some(folder.value());
+                                    } else {
+                                        errors.push(ParseFieldError::WrongAttributeType(
+                                            named_value.into_token_stream(),
+                                            "str",
+                                        ));
+                                    }
                                 } else {
                                     errors.push(ParseFieldError::UnknownAttribute(
                                         named_value.into_token_stream(),
                                     ));
                                 }
+                            } else if let NestedMeta::Meta(Meta::List(ref meta_list)) = attribute {
+                                let path = meta_list.path.get_ident().unwrap().clone();
+                                if path == TextureAtlasAttribute::ANIMATIONS {
+                                    for clip in meta_list.nested.iter() {
+                                        match parse_animation_clip(clip) {
+                                            Ok(clip) => builder.animations.push(clip),
+                                            Err(error) => errors.push(error),
+                                        }
+                                    }
+                                } else {
+                                    errors.push(ParseFieldError::UnknownAttribute(
+                                        meta_list.into_token_stream(),
+                                    ));
+                                }
                             } else {
                                 errors.push(ParseFieldError::UnknownAttributeType(
                                     attribute.into_token_stream(),
                                 ));
                             }
                         }
+                    } else if path == GLTF_ATTRIBUTE {
+                        #[cfg(not(feature = "gltf"))]
+                        errors.push(ParseFieldError::MissingFeature(
+                            meta_list.into_token_stream(),
+                            "gltf",
+                        ));
+                        #[cfg(feature = "gltf")]
+                        for attribute in meta_list.nested.iter() {
+                            match parse_gltf_sub_asset(attribute) {
+                                Ok(sub_asset) => builder.gltf_sub_assets.push(sub_asset),
+                                Err(error) => errors.push(error),
+                            }
+                        }
                     } else if path == FOLDER_ATTRIBUTE {
                         for attribute in meta_list.nested.iter() {
                             if let NestedMeta::Meta(Meta::Path(ref meta_path)) = attribute {
@@ -380,12 +583,169 @@ fn parse_field(field: &Field) -> Result<AssetField, Vec<ParseFieldError>> {
             }
         }
     }
+    #[cfg(feature = "render")]
+    if !builder.animations.is_empty() && !is_named_texture_atlas(&field.ty) {
+        errors.push(ParseFieldError::AnimationsNeedNamedTextureAtlas(
+            field.ty.to_token_stream(),
+        ));
+    }
     if !errors.is_empty() {
         return Err(errors);
     }
     builder.build()
 }
 
+/// Animation clips are part of a `NamedTextureAtlas` and would be lost in any other field type
+#[cfg(feature = "render")]
+fn is_named_texture_atlas(field_type: &syn::Type) -> bool {
+    if let syn::Type::Path(type_path) = field_type {
+        return type_path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident == "NamedTextureAtlas")
+            .unwrap_or(false);
+    }
+    false
+}
+
+#[cfg(feature = "render")]
+fn parse_animation_clip(clip: &NestedMeta) -> Result<AnimationClipField, ParseFieldError> {
+    let clip_list = if let NestedMeta::Meta(Meta::List(ref clip_list)) = clip {
+        clip_list
+    } else {
+        return Err(ParseFieldError::UnknownAttributeType(
+            clip.into_token_stream(),
+        ));
+    };
+    let name = clip_list.path.get_ident().unwrap().to_string();
+    let (mut start, mut end, mut fps) = (None, None, None);
+    for attribute in clip_list.nested.iter() {
+        if let NestedMeta::Meta(Meta::NameValue(ref named_value)) = attribute {
+            let path = named_value.path.get_ident().unwrap().clone();
+            if path == AnimationClipAttribute::START || path == AnimationClipAttribute::END {
+                if let Lit::Int(index) = &named_value.lit {
+                    let index = Some(index.base10_parse::<usize>().unwrap());
+                    if path == AnimationClipAttribute::START {
+                        start = index;
+                    } else {
+                        end = index;
+                    }
+                } else {
+                    return Err(ParseFieldError::WrongAttributeType(
+                        named_value.into_token_stream(),
+                        "integer",
+                    ));
+                }
+            } else if path == AnimationClipAttribute::FPS {
+                if let Lit::Float(frames_per_second) = &named_value.lit {
+                    let frames_per_second = frames_per_second.base10_parse::<f32>().unwrap();
+                    if frames_per_second <= 0. {
+                        return Err(ParseFieldError::InvalidFramesPerSecond(
+                            named_value.into_token_stream(),
+                        ));
+                    }
+                    fps = Some(frames_per_second);
+                } else {
+                    return Err(ParseFieldError::WrongAttributeType(
+                        named_value.into_token_stream(),
+                        "float",
+                    ));
+                }
+            } else {
+                return Err(ParseFieldError::UnknownAttribute(
+                    named_value.into_token_stream(),
+                ));
+            }
+        } else {
+            return Err(ParseFieldError::UnknownAttributeType(
+                attribute.into_token_stream(),
+            ));
+        }
+    }
+    let mut missing_attributes = vec![];
+    for (value, attribute) in [
+        (start.is_none(), AnimationClipAttribute::START),
+        (end.is_none(), AnimationClipAttribute::END),
+        (fps.is_none(), AnimationClipAttribute::FPS),
+    ] {
+        if value {
+            missing_attributes.push(format!(
+                "{}/{}/{}/{}",
+                TEXTURE_ATLAS_ATTRIBUTE,
+                TextureAtlasAttribute::ANIMATIONS,
+                name,
+                attribute
+            ));
+        }
+    }
+    if !missing_attributes.is_empty() {
+        return Err(ParseFieldError::MissingAttributes(missing_attributes));
+    }
+    let (start, end) = (start.unwrap(), end.unwrap());
+    if start >= end {
+        return Err(ParseFieldError::EmptyAnimationClip(
+            clip.into_token_stream(),
+        ));
+    }
+
+    Ok(AnimationClipField {
+        name,
+        start,
+        end,
+        fps: fps.unwrap(),
+    })
+}
+
+#[cfg(feature = "gltf")]
+fn parse_gltf_sub_asset(attribute: &NestedMeta) -> Result<GltfSubAsset, ParseFieldError> {
+    match attribute {
+        NestedMeta::Meta(Meta::NameValue(ref named_value)) => {
+            let path = named_value.path.get_ident().unwrap().clone();
+            let name = if let Lit::Str(name) = &named_value.lit {
+                name.value()
+            } else {
+                return Err(ParseFieldError::WrongAttributeType(
+                    named_value.into_token_stream(),
+                    "str",
+                ));
+            };
+            if path == GltfAttribute::SCENE {
+                Ok(GltfSubAsset::Scene(name))
+            } else if path == GltfAttribute::MESH {
+                Ok(GltfSubAsset::Mesh(name))
+            } else if path == GltfAttribute::MATERIAL {
+                Ok(GltfSubAsset::Material(name))
+            } else if path == GltfAttribute::NODE {
+                Ok(GltfSubAsset::Node(name))
+            } else {
+                Err(ParseFieldError::UnknownAttribute(
+                    named_value.into_token_stream(),
+                ))
+            }
+        }
+        NestedMeta::Meta(Meta::Path(ref meta_path)) => {
+            let path = meta_path.get_ident().unwrap().clone();
+            if path == GltfAttribute::NAMED_SCENES {
+                Ok(GltfSubAsset::NamedScenes)
+            } else if path == GltfAttribute::NAMED_MESHES {
+                Ok(GltfSubAsset::NamedMeshes)
+            } else if path == GltfAttribute::NAMED_MATERIALS {
+                Ok(GltfSubAsset::NamedMaterials)
+            } else if path == GltfAttribute::NAMED_NODES {
+                Ok(GltfSubAsset::NamedNodes)
+            } else {
+                Err(ParseFieldError::UnknownAttribute(
+                    meta_path.into_token_stream(),
+                ))
+            }
+        }
+        _ => Err(ParseFieldError::UnknownAttributeType(
+            attribute.into_token_stream(),
+        )),
+    }
+}
+
 fn to_compile_errors(errors: Vec<syn::Error>) -> proc_macro2::TokenStream {
     let compile_errors = errors.iter().map(syn::Error::to_compile_error);
     quote!(#(#compile_errors)*)